@@ -0,0 +1,406 @@
+//! Expand `#[overflow(..)]` attributes in source files, so the code compiles
+//! without the attribute macro (and on a stable compiler)
+//!
+//! Annotated items are replaced with their rewritten, pretty-printed form;
+//! the rest of each file stays as it is. Imports of the attribute (like
+//! `use overflower::overflow;`) are removed.
+
+use overflower_rewrite::{is_overflow_path, overflow_attr_args, Overflower};
+use proc_macro2::{LineColumn, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use syn::fold::{self, Fold};
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{token, Attribute, Block, ImplItemMethod, Item, ItemFn, ItemMod, Lit, NestedMeta, Stmt, Token,
+    TraitItemMethod, UseTree};
+
+fn has_overflow_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|a| overflow_attr_args(a).is_some())
+}
+
+/// Take the `#[overflow(..)]` attribute out of `attrs`, returning the
+/// rewriter it sets up, with `krate` as the path of the `overflower` crate
+/// unless the attribute gives one
+///
+/// Within a `cfg_attr`, the attribute is expanded as if the condition held,
+/// as the expanded code needs the `overflower` crate either way.
+fn take_overflow_attr(attrs: &mut Vec<Attribute>, krate: &syn::Path) -> Option<Overflower> {
+    let (i, args) = attrs.iter().enumerate().find_map(|(i, a)| Some((i, overflow_attr_args(a)?)))?;
+    // a malformed attribute wouldn't compile with the attribute macro either,
+    // so keep it for the compiler to complain about
+    let overflower = Overflower::parser(krate.clone()).parse2(args).ok()?;
+    let attr = attrs.remove(i);
+    if attr.path.is_ident("cfg_attr") {
+        if let Some(rest) = cfg_attr_without_overflow(&attr) {
+            attrs.insert(i, rest);
+        }
+    }
+    Some(overflower.stable())
+}
+
+// A `cfg_attr` with the attribute taken out, if any others are left
+fn cfg_attr_without_overflow(attr: &Attribute) -> Option<Attribute> {
+    let (condition, rest) = attr.parse_args_with(|input: ParseStream| {
+        let condition = input.parse::<NestedMeta>()?;
+        let mut rest = vec![];
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let path = input.call(syn::Path::parse_mod_style)?;
+            let args = if input.peek(token::Paren) {
+                input.parse::<TokenTree>()?.into_token_stream()
+            } else if input.peek(Token![=]) {
+                let eq = input.parse::<Token![=]>()?;
+                let lit = input.parse::<Lit>()?;
+                quote!(#eq #lit)
+            } else {
+                TokenStream::new()
+            };
+            if !is_overflow_path(&path) {
+                rest.push(quote!(#path #args));
+            }
+        }
+        Ok((condition, rest))
+    }).ok()?;
+    if rest.is_empty() {
+        return None;
+    }
+    let mut attr = attr.clone();
+    attr.tokens = quote!((#condition, #(#rest),*));
+    Some(attr)
+}
+
+fn item_attrs(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    Some(match *item {
+        Item::Const(ref mut i) => &mut i.attrs,
+        Item::Enum(ref mut i) => &mut i.attrs,
+        Item::Fn(ref mut i) => &mut i.attrs,
+        Item::Impl(ref mut i) => &mut i.attrs,
+        Item::Mod(ref mut i) => &mut i.attrs,
+        Item::Static(ref mut i) => &mut i.attrs,
+        Item::Struct(ref mut i) => &mut i.attrs,
+        Item::Trait(ref mut i) => &mut i.attrs,
+        Item::Type(ref mut i) => &mut i.attrs,
+        Item::Union(ref mut i) => &mut i.attrs,
+        _ => return None,
+    })
+}
+
+// The attribute macro sees methods as functions, so we do the same
+fn method_as_fn(m: &ImplItemMethod) -> ItemFn {
+    ItemFn {
+        attrs: m.attrs.clone(),
+        vis: m.vis.clone(),
+        sig: m.sig.clone(),
+        block: Box::new(m.block.clone()),
+    }
+}
+
+fn fn_as_method(f: ItemFn, m: ImplItemMethod) -> ImplItemMethod {
+    ImplItemMethod {
+        attrs: f.attrs,
+        vis: f.vis,
+        sig: f.sig,
+        block: *f.block,
+        ..m
+    }
+}
+
+/// Expands all annotated items within an item that is being expanded
+struct Expander<'a> {
+    // the path of the `overflower` crate, unless the attribute gives one
+    krate: &'a syn::Path,
+}
+
+impl<'a> Expander<'a> {
+    fn expand_item(&mut self, item: Item) -> Item {
+        let mut item = item;
+        match item_attrs(&mut item).and_then(|attrs| take_overflow_attr(attrs, self.krate)) {
+            Some(overflower) => self.fold_item(overflower.rewrite_item(&item)),
+            None => fold::fold_item(self, item),
+        }
+    }
+
+    fn expand_method(&mut self, m: ImplItemMethod) -> ImplItemMethod {
+        let mut f = method_as_fn(&m);
+        match take_overflow_attr(&mut f.attrs, self.krate) {
+            Some(overflower) => match self.fold_item(overflower.rewrite_item(&Item::Fn(f))) {
+                Item::Fn(f) => fn_as_method(f, m),
+                _ => unreachable!(),
+            },
+            None => fold::fold_impl_item_method(self, m),
+        }
+    }
+
+    fn expand_trait_method(&mut self, m: TraitItemMethod) -> TraitItemMethod {
+        let mut f = match m.default {
+            Some(ref block) => ItemFn {
+                attrs: m.attrs.clone(),
+                vis: syn::Visibility::Inherited,
+                sig: m.sig.clone(),
+                block: Box::new(block.clone()),
+            },
+            None => return m,
+        };
+        match take_overflow_attr(&mut f.attrs, self.krate) {
+            Some(overflower) => match self.fold_item(overflower.rewrite_item(&Item::Fn(f))) {
+                Item::Fn(f) => TraitItemMethod {
+                    attrs: f.attrs,
+                    sig: f.sig,
+                    default: Some(*f.block),
+                    ..m
+                },
+                _ => unreachable!(),
+            },
+            None => fold::fold_trait_item_method(self, m),
+        }
+    }
+}
+
+impl<'a> Fold for Expander<'a> {
+    fn fold_item(&mut self, i: Item) -> Item {
+        self.expand_item(i)
+    }
+
+    fn fold_impl_item_method(&mut self, i: ImplItemMethod) -> ImplItemMethod {
+        self.expand_method(i)
+    }
+
+    fn fold_trait_item_method(&mut self, i: TraitItemMethod) -> TraitItemMethod {
+        self.expand_trait_method(i)
+    }
+
+    fn fold_item_mod(&mut self, i: ItemMod) -> ItemMod {
+        let mut i = fold::fold_item_mod(self, i);
+        if let Some((_, ref mut items)) = i.content {
+            items.retain_mut(|item| match *item {
+                Item::Use(ref mut u) => remove_attr_import(&mut u.tree, false),
+                _ => true,
+            });
+        }
+        i
+    }
+
+    fn fold_block(&mut self, b: Block) -> Block {
+        let mut b = fold::fold_block(self, b);
+        b.stmts.retain_mut(|stmt| match *stmt {
+            Stmt::Item(Item::Use(ref mut u)) => remove_attr_import(&mut u.tree, false),
+            _ => true,
+        });
+        b
+    }
+}
+
+// Does this import the attribute macro (from the `overflower` crate)?
+fn imports_attr(tree: &UseTree, from_overflower: bool) -> bool {
+    match *tree {
+        UseTree::Path(ref p) => {
+            imports_attr(&p.tree, from_overflower || p.ident.to_string().starts_with("overflower"))
+        }
+        UseTree::Name(ref n) => from_overflower && n.ident == "overflow",
+        UseTree::Group(ref g) => g.items.iter().any(|t| imports_attr(t, from_overflower)),
+        UseTree::Rename(_) | UseTree::Glob(_) => false,
+    }
+}
+
+// Remove the import of the attribute macro, returning whether anything is left
+fn remove_attr_import(tree: &mut UseTree, from_overflower: bool) -> bool {
+    match *tree {
+        UseTree::Path(ref mut p) => {
+            let from_overflower = from_overflower || p.ident.to_string().starts_with("overflower");
+            remove_attr_import(&mut p.tree, from_overflower)
+        }
+        UseTree::Name(ref n) => !(from_overflower && n.ident == "overflow"),
+        UseTree::Group(ref mut g) => {
+            let items = std::mem::take(&mut g.items);
+            g.items = items.into_iter().filter_map(|mut t| {
+                if remove_attr_import(&mut t, from_overflower) { Some(t) } else { None }
+            }).collect();
+            !g.items.is_empty()
+        }
+        UseTree::Rename(_) | UseTree::Glob(_) => true,
+    }
+}
+
+/// A part of the source to replace
+struct Replacement {
+    start: LineColumn,
+    end: LineColumn,
+    text: String,
+}
+
+/// Finds the outermost annotated items and imports of the attribute
+struct Finder<'a> {
+    krate: &'a syn::Path,
+    replacements: Vec<Replacement>,
+}
+
+fn pretty(item: Item) -> String {
+    let file = syn::File { shebang: None, attrs: vec![], items: vec![item] };
+    prettyplease::unparse(&file).trim_end().to_string()
+}
+
+impl<'a> Finder<'a> {
+    fn replace<T: Spanned>(&mut self, node: &T, text: String) {
+        let span = node.span();
+        self.replacements.push(Replacement { start: span.start(), end: span.end(), text });
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for Finder<'a> {
+    fn visit_item(&mut self, i: &'ast Item) {
+        if let Item::Use(ref u) = *i {
+            if imports_attr(&u.tree, false) {
+                let mut u = u.clone();
+                let text = if remove_attr_import(&mut u.tree, false) {
+                    pretty(Item::Use(u))
+                } else {
+                    String::new()
+                };
+                self.replace(i, text);
+            }
+            return;
+        }
+        let mut item = i.clone();
+        if item_attrs(&mut item).is_some_and(|a| has_overflow_attr(a)) {
+            let expanded = Expander { krate: self.krate }.expand_item(item);
+            self.replace(i, pretty(expanded));
+        } else {
+            visit::visit_item(self, i);
+        }
+    }
+
+    fn visit_impl_item_method(&mut self, i: &'ast ImplItemMethod) {
+        if has_overflow_attr(&i.attrs) {
+            let expanded = Expander { krate: self.krate }.expand_method(i.clone());
+            self.replace(i, pretty(Item::Fn(method_as_fn(&expanded))));
+        } else {
+            visit::visit_impl_item_method(self, i);
+        }
+    }
+
+    fn visit_trait_item_method(&mut self, i: &'ast TraitItemMethod) {
+        if i.default.is_some() && has_overflow_attr(&i.attrs) {
+            let expanded = Expander { krate: self.krate }.expand_trait_method(i.clone());
+            let f = ItemFn {
+                attrs: expanded.attrs,
+                vis: syn::Visibility::Inherited,
+                sig: expanded.sig,
+                block: Box::new(expanded.default.unwrap()),
+            };
+            self.replace(i, pretty(Item::Fn(f)));
+        } else {
+            visit::visit_trait_item_method(self, i);
+        }
+    }
+}
+
+// the byte offset of a line and (character) column
+fn offset(line_starts: &[usize], source: &str, at: LineColumn) -> usize {
+    let start = line_starts[at.line - 1];
+    start + source[start..].char_indices().nth(at.column).map_or(source.len() - start, |(i, _)| i)
+}
+
+/// Expand the annotated items of a source file, returning `None` if there
+/// are none
+///
+/// The expanded code refers to the `overflower` crate by `krate` (see
+/// `overflower_rewrite::crate_path_in`), unless an attribute gives a path.
+pub fn expand_source(source: &str, krate: &syn::Path) -> syn::Result<Option<String>> {
+    let file = syn::parse_file(source)?;
+    let mut finder = Finder { krate, replacements: vec![] };
+    finder.visit_file(&file);
+    if finder.replacements.is_empty() {
+        return Ok(None);
+    }
+    let line_starts = std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+    let mut expanded = source.to_string();
+    for r in finder.replacements.iter().rev() {
+        let (mut start, mut end) = (offset(&line_starts, source, r.start),
+            offset(&line_starts, source, r.end));
+        let text = if r.text.is_empty() {
+            // remove the whole line if nothing else is on it
+            let line_start = line_starts[r.start.line - 1];
+            let rest = &source[end..];
+            let mut line_end = rest.find('\n').map_or(source.len(), |i| end + i + 1);
+            if source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+                // and the blank line after it, if it is the first of a block
+                if (line_start == 0 || source[..line_start - 1].ends_with('\n'))
+                    && source[line_end..].starts_with('\n')
+                {
+                    line_end += 1;
+                }
+                start = line_start;
+                end = line_end;
+            }
+            String::new()
+        } else {
+            // the first line goes where the item started, indent the others
+            let indent = " ".repeat(r.start.column);
+            r.text.lines().enumerate().map(|(i, line)| {
+                if i == 0 || line.is_empty() { line.to_string() } else { format!("{}{}", indent, line) }
+            }).collect::<Vec<_>>().join("\n")
+        };
+        expanded.replace_range(start..end, &text);
+    }
+    Ok(Some(expanded))
+}
+
+fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            rust_files(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Expand a file, in place or into `target`, returning whether it needed
+/// expanding
+///
+/// With a `target`, the file is written there whether it needed expanding or
+/// not.
+pub fn expand_file(file: &Path, target: Option<&Path>, krate: &syn::Path) -> io::Result<bool> {
+    let source = fs::read_to_string(file)?;
+    let expanded = expand_source(&source, krate).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file.display(), e))
+    })?;
+    let changed = expanded.is_some();
+    match (target, expanded) {
+        (Some(target), expanded) => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, expanded.unwrap_or(source))?;
+        }
+        (None, Some(expanded)) => fs::write(file, expanded)?,
+        (None, None) => {}
+    }
+    Ok(changed)
+}
+
+/// Expand all `.rs` files in `dir` (recursively), in place or into `out_dir`
+/// (at the same relative paths), returning the number of expanded files
+pub fn expand_dir(dir: &Path, out_dir: Option<&Path>, krate: &syn::Path) -> io::Result<usize> {
+    let mut files = vec![];
+    rust_files(dir, &mut files)?;
+    files.sort();
+    let mut count = 0;
+    for file in files {
+        let target = out_dir.map(|d| d.join(file.strip_prefix(dir).unwrap()));
+        if expand_file(&file, target.as_deref(), krate)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,198 @@
+//! `cargo overflower`: report which overflow policy covers each arithmetic
+//! operation of a crate
+//!
+//! Usage: `cargo overflower [--json] [--deny-unannotated] [PATH]`
+//!
+//! `PATH` is the crate directory (defaulting to the current one) or the root
+//! source file. Every operation that `#[overflow(..)]` would rewrite is
+//! listed with its location and effective policy, followed by per-module
+//! counts of unannotated operations. With `--deny-unannotated`, the exit
+//! code is 1 if there are any, so this can gate code review.
+//!
+//! Like the attribute, this doesn't see into macro invocations.
+//!
+//! `cargo overflower expand [--out-dir DIR] [PATH]` instead rewrites the
+//! `.rs` files of the crate's `src` directory (or of `PATH`, if that is a
+//! directory or file other than a crate directory), replacing annotated items
+//! with their expansion, so the crate builds without the attribute macro
+//! (e.g. with the `proc_macro` feature of `overflower` turned off, on a
+//! stable compiler). Without `--out-dir`, this happens in place. Comments
+//! within annotated items are lost, and macro invocations are only rewritten
+//! if their arguments are comma-separated expressions.
+
+extern crate overflower_rewrite;
+extern crate prettyplease;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+mod expand;
+mod report;
+
+use overflower_rewrite::Policy;
+use report::Operation;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "Usage: cargo overflower [--json] [--deny-unannotated] [PATH]
+       cargo overflower expand [--out-dir DIR] [PATH]";
+
+fn policy_name(policy: Option<Policy>) -> &'static str {
+    match policy {
+        Some(Policy::Wrap) => "wrap",
+        Some(Policy::Panic) => "panic",
+        Some(Policy::Saturate) => "saturate",
+        Some(Policy::Finite) => "finite",
+        Some(Policy::Default) => "default",
+        Some(Policy::Inherit) => "inherit",
+        None => "unannotated",
+    }
+}
+
+/// Find the root source file of the crate at `path`
+fn crate_root(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    ["src/lib.rs", "src/main.rs"].iter().map(|f| path.join(f)).find(|f| f.is_file())
+}
+
+/// The total and unannotated operation counts per module
+fn summary(ops: &[Operation]) -> BTreeMap<&str, (usize, usize)> {
+    let mut modules = BTreeMap::new();
+    for op in ops {
+        let counts = modules.entry(&op.module[..]).or_insert((0, 0));
+        counts.0 += 1;
+        if op.policy.is_none() {
+            counts.1 += 1;
+        }
+    }
+    modules
+}
+
+fn print_text(ops: &[Operation]) {
+    for op in ops {
+        println!("{}:{}:{}: `{}` {}", op.file.display(), op.line, op.column, op.op,
+            policy_name(op.policy));
+    }
+    println!();
+    println!("{:<40} {:>11} {:>11}", "module", "unannotated", "total");
+    for (module, (total, unannotated)) in summary(ops) {
+        println!("{:<40} {:>11} {:>11}", module, unannotated, total);
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn print_json(ops: &[Operation]) {
+    let operations = ops.iter().map(|op| {
+        let policy = match op.policy {
+            Some(_) => json_string(policy_name(op.policy)),
+            None => "null".to_string(),
+        };
+        format!("{{\"file\":{},\"line\":{},\"column\":{},\"module\":{},\"op\":{},\"policy\":{}}}",
+            json_string(&op.file.display().to_string()), op.line, op.column,
+            json_string(&op.module), json_string(op.op), policy)
+    }).collect::<Vec<_>>();
+    let modules = summary(ops).into_iter().map(|(module, (total, unannotated))| {
+        format!("{{\"module\":{},\"total\":{},\"unannotated\":{}}}",
+            json_string(module), total, unannotated)
+    }).collect::<Vec<_>>();
+    println!("{{\"operations\":[{}],\"modules\":[{}]}}", operations.join(","),
+        modules.join(","));
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn expand<I: Iterator<Item = String>>(mut args: I) {
+    let (mut out_dir, mut path) = (None, None);
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--out-dir" => match args.next() {
+                Some(dir) => out_dir = Some(PathBuf::from(dir)),
+                None => usage_error(),
+            },
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(PathBuf::from(arg)),
+            _ => usage_error(),
+        }
+    }
+    let path = path.unwrap_or_else(|| PathBuf::from("."));
+    let manifest = path.join("Cargo.toml");
+    // the name of the `overflower` dependency, if we have the manifest
+    let (dir, krate) = if manifest.is_file() {
+        (path.join("src"), overflower_rewrite::crate_path_in(&manifest))
+    } else {
+        (path, overflower_rewrite::crate_path())
+    };
+    let result = if dir.is_file() {
+        let target = out_dir.as_ref().map(|d| d.join(dir.file_name().unwrap()));
+        expand::expand_file(&dir, target.as_deref(), &krate).map(usize::from)
+    } else {
+        expand::expand_dir(&dir, out_dir.as_deref(), &krate)
+    };
+    match result {
+        Ok(count) => eprintln!("expanded {} file(s)", count),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let (mut json, mut deny, mut path) = (false, false, None);
+    let mut args = std::env::args().skip(1).peekable();
+    // when run as `cargo overflower`, cargo passes the subcommand name first
+    if args.peek().is_some_and(|a| a == "overflower") {
+        args.next();
+    }
+    if args.peek().is_some_and(|a| a == "expand") {
+        args.next();
+        return expand(args);
+    }
+    for arg in args {
+        match &arg[..] {
+            "--json" => json = true,
+            "--deny-unannotated" => deny = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(PathBuf::from(arg)),
+            _ => usage_error(),
+        }
+    }
+    let path = path.unwrap_or_else(|| PathBuf::from("."));
+    let root = match crate_root(&path) {
+        Some(root) => root,
+        None => {
+            eprintln!("{}: no src/lib.rs or src/main.rs found", path.display());
+            process::exit(2);
+        }
+    };
+    let ops = report::collect(&root);
+    if json {
+        print_json(&ops);
+    } else {
+        print_text(&ops);
+    }
+    if deny && ops.iter().any(|op| op.policy.is_none()) {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Collect the arithmetic operations of a crate along with the overflow
+//! policy that covers them
+
+use overflower_rewrite::{attr_policy, is_int_fn, Policy};
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, BinOp, Expr, Lit, Meta, NestedMeta, UnOp};
+
+/// An arithmetic operation that `#[overflow(..)]` would rewrite
+pub struct Operation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// the module path, e.g. `crate::foo`
+    pub module: String,
+    /// the operator, e.g. `+=`, `.sum()` for method calls or `::pow()` for
+    /// calls by path like `u8::pow(x, n)`
+    pub op: &'static str,
+    /// the effective policy, `None` if the operation is unannotated
+    pub policy: Option<Policy>,
+}
+
+/// Collect the operations of the crate whose root module is in `root`
+/// (e.g. `src/lib.rs`), following `mod` declarations to other files
+///
+/// Files that can't be read or parsed are reported on stderr and skipped.
+pub fn collect(root: &Path) -> Vec<Operation> {
+    let mut collector = Collector {
+        file: PathBuf::new(),
+        dir: root.parent().map(Path::to_path_buf).unwrap_or_default(),
+        module: vec!["crate".to_string()],
+        policy: None,
+        in_policy_fn: false,
+        in_trait_impl: false,
+        ops: vec![],
+    };
+    collector.visit_file_at(root.to_path_buf());
+    let mut ops = collector.ops;
+    ops.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+    ops
+}
+
+struct Collector {
+    file: PathBuf,
+    // the directory the files of child modules are in
+    dir: PathBuf,
+    module: Vec<String>,
+    policy: Option<Policy>,
+    // whether we are within a function the inherit policy makes generic
+    in_policy_fn: bool,
+    in_trait_impl: bool,
+    ops: Vec<Operation>,
+}
+
+// The attributes of an expression, for the expressions whose attributes the
+// rewriting checks for a nested `#[overflow(..)]`
+fn expr_attrs(e: &Expr) -> &[Attribute] {
+    match *e {
+        Expr::Box(ref e) => &e.attrs,
+        Expr::Array(ref e) => &e.attrs,
+        Expr::Call(ref e) => &e.attrs,
+        Expr::MethodCall(ref e) => &e.attrs,
+        Expr::Tuple(ref e) => &e.attrs,
+        Expr::Binary(ref e) => &e.attrs,
+        Expr::Unary(ref e) => &e.attrs,
+        Expr::Cast(ref e) => &e.attrs,
+        Expr::Type(ref e) => &e.attrs,
+        Expr::Let(ref e) => &e.attrs,
+        Expr::If(ref e) => &e.attrs,
+        Expr::While(ref e) => &e.attrs,
+        Expr::ForLoop(ref e) => &e.attrs,
+        Expr::Loop(ref e) => &e.attrs,
+        Expr::Match(ref e) => &e.attrs,
+        Expr::Closure(ref e) => &e.attrs,
+        Expr::Unsafe(ref e) => &e.attrs,
+        Expr::Block(ref e) => &e.attrs,
+        Expr::Assign(ref e) => &e.attrs,
+        Expr::AssignOp(ref e) => &e.attrs,
+        Expr::Field(ref e) => &e.attrs,
+        Expr::Index(ref e) => &e.attrs,
+        Expr::Range(ref e) => &e.attrs,
+        Expr::Path(ref e) => &e.attrs,
+        Expr::Reference(ref e) => &e.attrs,
+        Expr::Break(ref e) => &e.attrs,
+        Expr::Return(ref e) => &e.attrs,
+        Expr::Macro(ref e) => &e.attrs,
+        Expr::Struct(ref e) => &e.attrs,
+        Expr::Repeat(ref e) => &e.attrs,
+        Expr::Paren(ref e) => &e.attrs,
+        Expr::Try(ref e) => &e.attrs,
+        Expr::Async(ref e) => &e.attrs,
+        Expr::TryBlock(ref e) => &e.attrs,
+        Expr::Yield(ref e) => &e.attrs,
+        _ => &[],
+    }
+}
+
+fn bin_op(op: &BinOp) -> Option<&'static str> {
+    Some(match *op {
+        BinOp::Add(_) => "+",
+        BinOp::Sub(_) => "-",
+        BinOp::Mul(_) => "*",
+        BinOp::Div(_) => "/",
+        BinOp::Rem(_) => "%",
+        BinOp::Shl(_) => "<<",
+        BinOp::Shr(_) => ">>",
+        BinOp::AddEq(_) => "+=",
+        BinOp::SubEq(_) => "-=",
+        BinOp::MulEq(_) => "*=",
+        BinOp::DivEq(_) => "/=",
+        BinOp::RemEq(_) => "%=",
+        BinOp::ShlEq(_) => "<<=",
+        BinOp::ShrEq(_) => ">>=",
+        _ => return None,
+    })
+}
+
+fn is_literal(e: &Expr) -> bool {
+    match *e {
+        Expr::Lit(_) => true,
+        Expr::Paren(ref p) => is_literal(&p.expr),
+        Expr::Group(ref g) => is_literal(&g.expr),
+        _ => false,
+    }
+}
+
+// the value of a `#[path = ".."]` attribute, if any
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().filter(|a| a.path.is_ident("path")).find_map(|a| {
+        match a.parse_meta() {
+            Ok(Meta::NameValue(nv)) => match nv.lit {
+                Lit::Str(s) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        }
+    })
+}
+
+// Is this module left out by a `#[cfg(test)]`?
+fn is_test_mod(attrs: &[Attribute]) -> bool {
+    attrs.iter().filter(|a| a.path.is_ident("cfg")).any(|a| {
+        match a.parse_meta() {
+            Ok(Meta::List(l)) => l.nested.iter().any(|n| match *n {
+                NestedMeta::Meta(Meta::Path(ref p)) => p.is_ident("test"),
+                _ => false,
+            }),
+            _ => false,
+        }
+    })
+}
+
+impl Collector {
+    fn visit_file_at(&mut self, path: PathBuf) {
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => return eprintln!("{}: {}", path.display(), e),
+        };
+        let file = match syn::parse_file(&source) {
+            Ok(file) => file,
+            Err(e) => return eprintln!("{}: {}", path.display(), e),
+        };
+        let outer = std::mem::replace(&mut self.file, path);
+        self.with_attrs(&file.attrs, |c| visit::visit_file(c, &file));
+        self.file = outer;
+    }
+
+    fn with_attrs<F: FnOnce(&mut Self)>(&mut self, attrs: &[Attribute], f: F) {
+        let outer = self.policy;
+        if let Some(policy) = attr_policy(attrs) {
+            self.policy = Some(policy);
+        }
+        f(self);
+        self.policy = outer;
+    }
+
+    fn with_policy_fn<F: FnOnce(&mut Self)>(&mut self, in_policy_fn: bool, f: F) {
+        let outer = self.in_policy_fn;
+        self.in_policy_fn = in_policy_fn;
+        f(self);
+        self.in_policy_fn = outer;
+    }
+
+    fn record(&mut self, span: proc_macro2::Span, op: &'static str) {
+        let start = span.start();
+        let policy = match self.policy {
+            // the inherit policy leaves everything outside of functions alone
+            Some(Policy::Inherit) if !self.in_policy_fn => None,
+            policy => policy,
+        };
+        self.ops.push(Operation {
+            file: self.file.clone(),
+            line: start.line,
+            column: start.column + 1,
+            module: self.module.join("::"),
+            op,
+            policy,
+        });
+    }
+
+    fn visit_mod_file(&mut self, i: &syn::ItemMod) {
+        let name = i.ident.to_string();
+        let file = match path_attr(&i.attrs) {
+            Some(path) => self.dir.join(path),
+            None => {
+                let file = self.dir.join(format!("{}.rs", name));
+                if file.exists() { file } else { self.dir.join(&name).join("mod.rs") }
+            }
+        };
+        let dir = if file.ends_with("mod.rs") {
+            file.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            file.with_extension("")
+        };
+        let outer = std::mem::replace(&mut self.dir, dir);
+        self.visit_file_at(file);
+        self.dir = outer;
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector {
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        if is_test_mod(&i.attrs) {
+            return;
+        }
+        self.module.push(i.ident.to_string());
+        self.with_attrs(&i.attrs, |c| {
+            if i.content.is_some() {
+                let dir = c.dir.join(i.ident.to_string());
+                let outer = std::mem::replace(&mut c.dir, dir);
+                visit::visit_item_mod(c, i);
+                c.dir = outer;
+            } else {
+                c.visit_mod_file(i);
+            }
+        });
+        self.module.pop();
+    }
+
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(true, |c| visit::visit_item_fn(c, i))
+        })
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
+        let outer = self.in_trait_impl;
+        self.in_trait_impl = i.trait_.is_some();
+        self.with_attrs(&i.attrs, |c| visit::visit_item_impl(c, i));
+        self.in_trait_impl = outer;
+    }
+
+    fn visit_impl_item_method(&mut self, i: &'ast syn::ImplItemMethod) {
+        let in_policy_fn = !self.in_trait_impl;
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(in_policy_fn, |c| visit::visit_impl_item_method(c, i))
+        })
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(false, |c| visit::visit_item_trait(c, i))
+        })
+    }
+
+    fn visit_trait_item_method(&mut self, i: &'ast syn::TraitItemMethod) {
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(false, |c| visit::visit_trait_item_method(c, i))
+        })
+    }
+
+    fn visit_item_const(&mut self, i: &'ast syn::ItemConst) {
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(false, |c| visit::visit_item_const(c, i))
+        })
+    }
+
+    fn visit_item_static(&mut self, i: &'ast syn::ItemStatic) {
+        self.with_attrs(&i.attrs, |c| {
+            c.with_policy_fn(false, |c| visit::visit_item_static(c, i))
+        })
+    }
+
+    fn visit_expr(&mut self, e: &'ast Expr) {
+        if let Expr::Await(_) | Expr::Group(_) = *e {
+            // the rewriting doesn't look into these
+            let outer = self.policy.take();
+            visit::visit_expr(self, e);
+            self.policy = outer;
+            return;
+        }
+        self.with_attrs(expr_attrs(e), |c| {
+            match *e {
+                Expr::Binary(ref b) => if let Some(op) = bin_op(&b.op) {
+                    c.record(b.op.span(), op);
+                },
+                Expr::AssignOp(ref a) => if let Some(op) = bin_op(&a.op) {
+                    c.record(a.op.span(), op);
+                },
+                Expr::Unary(ref u) => if let UnOp::Neg(ref t) = u.op {
+                    if !is_literal(&u.expr) {
+                        c.record(t.span, "-");
+                    }
+                },
+                Expr::MethodCall(ref m) if m.args.is_empty() => {
+                    if m.method == "sum" {
+                        c.record(m.method.span(), ".sum()");
+                    } else if m.method == "product" {
+                        c.record(m.method.span(), ".product()");
+                    }
+                }
+                Expr::MethodCall(ref m) if m.method == "pow" && m.args.len() == 1
+                    && m.turbofish.is_none() => c.record(m.method.span(), ".pow()"),
+                Expr::Call(ref call) => if let Expr::Path(ref p) = *call.func {
+                    if p.qself.is_none() && is_int_fn(&p.path, call.args.len()) {
+                        let last = &p.path.segments.last().unwrap().ident;
+                        c.record(last.span(), if last == "abs" { "::abs()" } else { "::pow()" });
+                    }
+                },
+                _ => {}
+            }
+            visit::visit_expr(c, e)
+        })
+    }
+}
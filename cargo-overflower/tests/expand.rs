@@ -0,0 +1,102 @@
+//! Expand the crate in `tests/expand_fixture`
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_expand() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand_fixture");
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("expand");
+    let _ = fs::remove_dir_all(&out_dir);
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-overflower"))
+        .args(["overflower", "expand", "--out-dir"])
+        .arg(&out_dir)
+        .arg(fixture.join("src"))
+        .env_remove("CARGO_MANIFEST_DIR")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!("expanded 1 file(s)\n", String::from_utf8(output.stderr).unwrap());
+    let expanded = fs::read_to_string(out_dir.join("lib.rs")).unwrap();
+    assert_eq!(r#"
+/// Add one, saturating
+///
+/// Integer overflow in this function: saturates.
+#[doc(alias = "overflow_saturate")]
+pub fn increment(x: u8) -> u8 {
+    ::overflower::dispatch::Dispatch::<_, ::overflower::Saturate>::new((x, 1)).add()
+}
+
+pub fn unannotated(x: u8) -> u8 {
+    x * 2
+}
+
+pub struct Counter(pub u8);
+
+impl Counter {
+    #[doc(alias = "overflow_wrap")]
+    pub fn bump(&mut self) {
+        {
+            let __overflower_rhs = 1;
+            ::overflower::dispatch::Dispatch::<
+                _,
+                ::overflower::Wrap,
+            >::new((&mut self.0, __overflower_rhs))
+                .add_assign()
+        };
+        assert!(
+            ::overflower::dispatch::Dispatch:: < _, ::overflower::Wrap > ::new((self.0, 1))
+            .sub() < 255, "{}", ::overflower::dispatch::Dispatch:: < _, ::overflower::Wrap >
+            ::new((self.0, 2)).mul()
+        );
+    }
+}
+
+pub mod nested {
+    use overflower::SubPanic;
+
+    #[doc(alias = "overflow_panic")]
+    pub fn decrement(x: u8) -> u8 {
+        let inner = {
+            #[doc(alias = "overflow_wrap")]
+            fn twice(x: u8) -> u8 {
+                ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new((x, 2)).mul()
+            }
+            twice(x)
+        };
+        ::overflower::dispatch::Dispatch::<_, ::overflower::Panic>::new((inner, 1)).sub()
+    }
+}
+
+#[cfg_attr(feature = "overflower", inline)]
+#[doc(alias = "overflow_wrap")]
+pub fn wrapping_double(x: u8) -> u8 {
+    ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new((x, 2)).mul()
+}
+"#.trim_start(), expanded);
+}
+
+
+#[test]
+fn test_expand_renamed() {
+    // with the manifest, the generated code uses the name of the dependency
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/expand_fixture");
+    let krate = Path::new(env!("CARGO_TARGET_TMPDIR")).join("expand_renamed");
+    let _ = fs::remove_dir_all(&krate);
+    fs::create_dir_all(krate.join("src")).unwrap();
+    fs::write(krate.join("Cargo.toml"), "[package]\nname = \"fixture\"\n\n\
+        [dependencies]\nof = { package = \"overflower\", version = \"0.9\" }\n").unwrap();
+    fs::copy(fixture.join("src/lib.rs"), krate.join("src/lib.rs")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-overflower"))
+        .args(["overflower", "expand"])
+        .arg(&krate)
+        .env_remove("CARGO_MANIFEST_DIR")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let expanded = fs::read_to_string(krate.join("src/lib.rs")).unwrap();
+    assert!(expanded.contains(
+        "::of::dispatch::Dispatch::<_, ::of::Saturate>::new((x, 1)).add()"), "{}", expanded);
+    assert!(!expanded.contains("::overflower::"), "{}", expanded);
+}
@@ -0,0 +1,42 @@
+use overflower::overflow;
+
+/// Add one, saturating
+#[overflow(saturate)]
+pub fn increment(x: u8) -> u8 {
+    x + 1
+}
+
+pub fn unannotated(x: u8) -> u8 {
+    x * 2
+}
+
+pub struct Counter(pub u8);
+
+impl Counter {
+    #[overflow(wrap)]
+    pub fn bump(&mut self) {
+        self.0 += 1;
+        assert!(self.0 - 1 < 255, "{}", self.0 * 2);
+    }
+}
+
+pub mod nested {
+    use overflower::{overflow, SubPanic};
+
+    #[overflow(panic)]
+    pub fn decrement(x: u8) -> u8 {
+        let inner = {
+            #[overflow(wrap)]
+            fn twice(x: u8) -> u8 {
+                x * 2
+            }
+            twice(x)
+        };
+        inner - 1
+    }
+}
+
+#[cfg_attr(feature = "overflower", inline, overflower::overflow(wrap))]
+pub fn wrapping_double(x: u8) -> u8 {
+    x * 2
+}
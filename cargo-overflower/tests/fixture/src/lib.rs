@@ -0,0 +1,26 @@
+mod nested;
+
+pub fn plain(a: u32, b: u32) -> u32 {
+    a + b.pow(2)
+}
+
+#[overflow(saturate)]
+pub mod covered {
+    pub fn double(a: u8) -> u8 {
+        let mut x = a * 2;
+        x -= 1;
+        x
+    }
+
+    #[overflow(default)]
+    pub fn negate(a: i8) -> i8 {
+        -a - -1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn ignored() -> u8 {
+        1 + 1
+    }
+}
@@ -0,0 +1,12 @@
+#[overflow(inherit)]
+mod helpers {
+    const SHIFTED: u8 = 1 << 3;
+
+    pub fn total(v: &[u8]) -> u8 {
+        v.iter().sum::<u8>() % SHIFTED
+    }
+
+    pub fn square(x: i8) -> i8 {
+        i8::pow(i8::abs(x), 2)
+    }
+}
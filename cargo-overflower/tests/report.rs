@@ -0,0 +1,62 @@
+//! Run the report on the crate in `tests/fixture`
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn report(args: &[&str]) -> Output {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixture");
+    Command::new(env!("CARGO_BIN_EXE_cargo-overflower"))
+        .args(args)
+        .current_dir(fixture)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_text_report() {
+    let output = report(&["overflower"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(vec![
+        "./src/lib.rs:4:7: `+` unannotated",
+        "./src/lib.rs:4:11: `.pow()` unannotated",
+        "./src/lib.rs:10:23: `*` saturate",
+        "./src/lib.rs:11:11: `-=` saturate",
+        "./src/lib.rs:17:9: `-` default",
+        "./src/lib.rs:17:12: `-` default",
+        "./src/nested/mod.rs:3:27: `<<` unannotated",
+        "./src/nested/mod.rs:6:18: `.sum()` inherit",
+        "./src/nested/mod.rs:6:30: `%` inherit",
+        "./src/nested/mod.rs:10:13: `::pow()` inherit",
+        "./src/nested/mod.rs:10:21: `::abs()` inherit",
+        "",
+    ], lines[..12].to_vec());
+    let counts = lines[13..].iter().map(|l| {
+        l.split_whitespace().collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    assert_eq!(vec![
+        vec!["crate", "2", "2"],
+        vec!["crate::covered", "0", "4"],
+        vec!["crate::nested::helpers", "1", "5"],
+    ], counts);
+}
+
+#[test]
+fn test_json_report() {
+    let output = report(&["--json", "src/lib.rs"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("{\"operations\":[{\"file\":\"src/lib.rs\",\"line\":4,\
+        \"column\":7,\"module\":\"crate\",\"op\":\"+\",\"policy\":null},"));
+    assert!(stdout.contains("{\"file\":\"src/nested/mod.rs\",\"line\":6,\"column\":30,\
+        \"module\":\"crate::nested::helpers\",\"op\":\"%\",\"policy\":\"inherit\"}"));
+    assert!(stdout.trim_end().ends_with(",{\"module\":\"crate::nested::helpers\",\
+        \"total\":5,\"unannotated\":1}]}"));
+}
+
+#[test]
+fn test_deny_unannotated() {
+    assert_eq!(Some(1), report(&["--deny-unannotated"]).status.code());
+    assert_eq!(Some(2), report(&["--frobnicate"]).status.code());
+}
@@ -0,0 +1,61 @@
+//! Measure how long it takes to expand `#[overflow(..)]` on a large module
+//!
+//! Run with `cargo bench -p overflower-rewrite`.
+
+extern crate overflower_rewrite;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use overflower_rewrite::Overflower;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::time::Instant;
+
+// every synthetic function spans 10 lines
+const FUNCTIONS: usize = 2_000;
+const ITERATIONS: u32 = 10;
+
+fn synthetic_module() -> TokenStream {
+    let mut code = String::from("mod synthetic {\n");
+    for i in 0..FUNCTIONS {
+        code += &format!("
+    fn f{}(a: u32, b: u32, v: &mut [u32], x: i32) -> u32 {{
+        let mut c = a * b + (a - b) / 3 % 7;
+        c <<= 2;
+        v[0] += v[1] * c;
+        if a > b {{ c -= a >> 1; }} else {{ c *= b; }}
+        let _ = i32::abs(x) - x;
+        debug_assert!(c > 0);
+        (c + v[2]) * (v[0] - 1)
+    }}", i);
+    }
+    code.push_str("\n}\n");
+    code.parse().unwrap()
+}
+
+// the same steps as the attribute: parse, rewrite, print (with `stable`
+// unless the `specialization` feature is on)
+fn expand(attrs: TokenStream, code: TokenStream, stable: bool) -> TokenStream {
+    let mut overflower = syn::parse2::<Overflower>(attrs).unwrap();
+    if stable {
+        overflower = overflower.stable();
+    }
+    let item = overflower.rewrite_item(&syn::parse2(code).unwrap());
+    quote!(#item)
+}
+
+fn main() {
+    let module = synthetic_module();
+    let attrs: TokenStream = "wrap, crate = ::overflower".parse().unwrap();
+    for &(stable, config) in &[(true, "stable"), (false, "specialization")] {
+        // warm up
+        expand(attrs.clone(), module.clone(), stable);
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            expand(attrs.clone(), module.clone(), stable);
+        }
+        println!("expanding a module of {} functions (~{} lines) for {}: {:?} per iteration",
+            FUNCTIONS, FUNCTIONS * 10, config, start.elapsed() / ITERATIONS);
+    }
+}
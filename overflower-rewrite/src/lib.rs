@@ -0,0 +1,1635 @@
+//! The rewriting behind `#[overflow(..)]`, for use by other proc macros and
+//! build scripts
+//!
+//! This replaces the arithmetic operations within an item, expression or
+//! block with calls to the policy traits of the `overflower` crate, e.g. with
+//! the `Wrap` policy, `a + b` becomes `overflower::AddWrap::add_wrap(a, b)`.
+//! The rewritten code needs to be compiled in a crate that depends on
+//! `overflower`.
+//!
+//! ```
+//! extern crate overflower_rewrite;
+//! extern crate syn;
+//!
+//! use overflower_rewrite::{rewrite_expr, Policy};
+//!
+//! let expr: syn::Expr = syn::parse_str("a * b + 1").unwrap();
+//! let rewritten = rewrite_expr(&expr, Policy::Saturate);
+//! ```
+//!
+//! The free functions find the path of the `overflower` crate from the
+//! manifest of the crate being compiled (see `crate_path`); use
+//! `Overflower::new` or `Overflower::parser` to choose it yourself, e.g. with
+//! `crate_path_in` for the manifest of a crate rewritten outside of the
+//! compiler.
+
+extern crate proc_macro2;
+extern crate proc_macro_crate;
+extern crate quote;
+extern crate syn;
+extern crate toml_edit;
+
+use proc_macro2::Span;
+use proc_macro_crate::FoundCrate;
+use quote::quote;
+use std::collections::HashMap;
+use syn::fold::{self, Fold};
+use syn::parse::{Parse, ParseStream, Parser, Result};
+use syn::visit::{self, Visit};
+use syn::*;
+
+/// The operations we have policy traits for
+#[derive(Copy, Clone, PartialEq)]
+enum Op {
+    Add, Sub, Mul, Div, Rem, Shl, Shr, Neg,
+    AddAssign, SubAssign, MulAssign, DivAssign, RemAssign, ShlAssign, ShrAssign,
+    Abs, Sum, Product, Pow,
+}
+
+// The trait and method name stems of each `Op` (in the same order); these are
+// also the names of the respective `std::ops` traits, except for `Abs` and
+// `Pow` (which have none) and `Sum` and `Product` (which are in `std::iter`)
+static OPS: [(Op, &str, &str); 19] = [
+    (Op::Add, "Add", "add"), (Op::Sub, "Sub", "sub"), (Op::Mul, "Mul", "mul"),
+    (Op::Div, "Div", "div"), (Op::Rem, "Rem", "rem"), (Op::Shl, "Shl", "shl"),
+    (Op::Shr, "Shr", "shr"), (Op::Neg, "Neg", "neg"),
+    (Op::AddAssign, "AddAssign", "add_assign"),
+    (Op::SubAssign, "SubAssign", "sub_assign"),
+    (Op::MulAssign, "MulAssign", "mul_assign"),
+    (Op::DivAssign, "DivAssign", "div_assign"),
+    (Op::RemAssign, "RemAssign", "rem_assign"),
+    (Op::ShlAssign, "ShlAssign", "shl_assign"),
+    (Op::ShrAssign, "ShrAssign", "shr_assign"),
+    (Op::Abs, "Abs", "abs"), (Op::Sum, "Sum", "sum"),
+    (Op::Product, "Product", "product"), (Op::Pow, "Pow", "pow"),
+];
+
+/// How to handle integer overflow in rewritten code
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Policy {
+    /// wrap around on overflow
+    Wrap,
+    /// panic on overflow
+    Panic,
+    /// saturate at the numeric bounds on overflow
+    Saturate,
+    /// panic on overflow and when float operations give infinity or NaN
+    /// from finite operands
+    Finite,
+    /// leave the code as it is
+    Default,
+    /// make functions generic over an `overflower::Policy` type parameter
+    /// named `OverflowPolicy`, which their operations follow
+    Inherit,
+}
+
+/// Parses the same names the attribute takes, e.g. `saturate`
+impl Parse for Policy {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        if ident == "wrap" {
+            Ok(Policy::Wrap)
+        } else if ident == "panic" {
+            Ok(Policy::Panic)
+        } else if ident == "saturate" {
+            Ok(Policy::Saturate)
+        } else if ident == "finite" {
+            Ok(Policy::Finite)
+        } else if ident == "default" {
+            Ok(Policy::Default)
+        } else if ident == "inherit" {
+            Ok(Policy::Inherit)
+        } else {
+            Err(Error::new(ident.span(),
+                "Usage: overflow(wrap|panic|saturate|finite|default|inherit[, crate = path]\
+                    [, div_by_zero = wrap|panic|saturate][, shifts = mask|zero_fill|rotate])"))
+        }
+    }
+}
+
+impl Policy {
+    fn name(self) -> &'static str {
+        match self {
+            Policy::Wrap => "Wrap",
+            Policy::Panic => "Panic",
+            Policy::Saturate => "Saturate",
+            Policy::Finite => "Finite",
+            Policy::Default => "Default",
+            Policy::Inherit => "Inherit",
+        }
+    }
+
+    // how integer overflow behaves in this mode, for documentation
+    fn description(self) -> &'static str {
+        match self {
+            Policy::Wrap => "wraps around",
+            Policy::Panic => "panics",
+            Policy::Saturate => "saturates",
+            Policy::Finite => "panics (as do float operations giving infinity or NaN \
+                from finite operands)",
+            Policy::Default => "uses the standard operators (which panic or wrap \
+                depending on the `overflow-checks` setting)",
+            Policy::Inherit => "follows the `OverflowPolicy` type parameter \
+                (`Wrap`, `Panic` or `Saturate`)",
+        }
+    }
+
+    // whether this mode can be combined with a policy for zero divisors or
+    // out-of-range shift amounts
+    fn combines(self) -> bool {
+        matches!(self, Policy::Wrap | Policy::Panic | Policy::Saturate)
+    }
+
+    // the modes whose policy traits an operation in this mode needs
+    fn trait_modes(self) -> &'static [Policy] {
+        match self {
+            Policy::Wrap => &[Policy::Wrap],
+            Policy::Panic => &[Policy::Panic],
+            Policy::Saturate => &[Policy::Saturate],
+            Policy::Finite => &[Policy::Finite],
+            Policy::Default => &[],
+            Policy::Inherit => &[Policy::Wrap, Policy::Panic, Policy::Saturate],
+        }
+    }
+
+    // the mode an operation follows in this mode, as there are no `*Finite`
+    // traits for the operations only integers have
+    fn op_mode(self, op: Op) -> Policy {
+        match (self, op) {
+            (Policy::Finite, Op::Shl | Op::Shr | Op::ShlAssign | Op::ShrAssign | Op::Abs
+                | Op::Pow) => Policy::Panic,
+            _ => self,
+        }
+    }
+}
+
+/// How to handle shift amounts that are negative or not lower than the width
+/// of the type in rewritten code (see `Overflower::shifts`)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Shifts {
+    /// shift by the amount modulo the width of the type
+    Mask,
+    /// shift all bits out of the value
+    ZeroFill,
+    /// rotate by the amount modulo the width of the type
+    Rotate,
+}
+
+/// Parses the same names the attribute takes, e.g. `zero_fill`
+impl Parse for Shifts {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        if ident == "mask" {
+            Ok(Shifts::Mask)
+        } else if ident == "zero_fill" {
+            Ok(Shifts::ZeroFill)
+        } else if ident == "rotate" {
+            Ok(Shifts::Rotate)
+        } else {
+            Err(Error::new(ident.span(), "expected `mask`, `zero_fill` or `rotate`"))
+        }
+    }
+}
+
+impl Shifts {
+    // the name of the `overflower` marker type and the attribute argument
+    fn names(self) -> (&'static str, &'static str) {
+        match self {
+            Shifts::Mask => ("Mask", "mask"),
+            Shifts::ZeroFill => ("ZeroFill", "zero_fill"),
+            Shifts::Rotate => ("Rotate", "rotate"),
+        }
+    }
+
+    // what out-of-range shift amounts do, for documentation
+    fn description(self) -> &'static str {
+        match self {
+            Shifts::Mask => "are masked",
+            Shifts::ZeroFill => "shift all bits out",
+            Shifts::Rotate => "rotate",
+        }
+    }
+}
+
+/// Is this the path of the attribute, i.e. `overflow` or
+/// `overflower::overflow`?
+pub fn is_overflow_path(path: &Path) -> bool {
+    match path.segments.len() {
+        1 => path.is_ident("overflow"),
+        2 => path.segments[0].ident == "overflower" && path.segments[1].ident == "overflow",
+        _ => false,
+    }
+}
+
+/// The arguments of an `#[overflow(..)]` attribute, however it is spelled:
+/// also as `#[overflower::overflow(..)]`, or within a `cfg_attr` (whose
+/// condition is taken to hold)
+pub fn overflow_attr_args(attr: &Attribute) -> Option<proc_macro2::TokenStream> {
+    if is_overflow_path(&attr.path) {
+        return attr.parse_args().ok();
+    }
+    if !attr.path.is_ident("cfg_attr") {
+        return None;
+    }
+    attr.parse_args_with(|input: ParseStream| {
+        input.parse::<NestedMeta>()?;
+        let mut args = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let path = input.call(Path::parse_mod_style)?;
+            if input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                let tokens = content.parse::<proc_macro2::TokenStream>()?;
+                if args.is_none() && is_overflow_path(&path) {
+                    args = Some(tokens);
+                }
+            } else if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                input.parse::<Lit>()?;
+            }
+        }
+        Ok(args)
+    }).ok().flatten()
+}
+
+fn is_overflow_attr(attr: &Attribute) -> bool {
+    overflow_attr_args(attr).is_some()
+}
+
+/// The policy an `#[overflow(..)]` attribute among `attrs` sets, if any
+///
+/// Items and expressions with such an attribute are left alone when
+/// rewriting an enclosing item, as the attribute takes care of them.
+pub fn attr_policy(attrs: &[Attribute]) -> Option<Policy> {
+    attrs.iter().find_map(overflow_attr_args).and_then(|args| {
+        (|input: ParseStream| {
+            let policy = input.parse::<Policy>()?;
+            // skip the crate path, if any
+            input.parse::<proc_macro2::TokenStream>()?;
+            Ok(policy)
+        }).parse2(args).ok()
+    })
+}
+
+/// Whether the function at `path`, called with `args` arguments, is an
+/// integer function the rewriting applies the policy traits to, like
+/// `i32::abs(x)` or `std::u8::pow(x, n)`
+pub fn is_int_fn(path: &Path, args: usize) -> bool {
+    int_fn_op(path, args).is_some()
+}
+
+/// The operation of a call of an integer function we have policy traits for,
+/// like `i32::abs(x)` or `std::u8::pow(x, n)`
+fn int_fn_op(path: &Path, args: usize) -> Option<Op> {
+    static FACADE: [&str; 2] = ["std", "core"];
+    static SIGNED: [&str; 6] = ["i8", "i16", "i32", "i64", "i128", "isize"];
+    static INTEGERS: [&str; 12] = ["u8", "u16", "u32", "u64", "u128", "usize",
+        "i8", "i16", "i32", "i64", "i128", "isize"];
+    let segments = &path.segments;
+    let (op, types, min_len): (Op, &[&str], usize) = match segments.last()?.ident.to_string().as_str() {
+        "abs" if args == 1 => (Op::Abs, &SIGNED, 1),
+        // a plain `pow(x, n)` could be any function
+        "pow" if args == 2 => (Op::Pow, &INTEGERS, 2),
+        _ => return None,
+    };
+    let matchers: [&[&str]; 2] = [&FACADE, types];
+    let len = segments.len();
+    let matches = len >= min_len && len <= 3 &&
+        segments.iter().all(|seg| seg.arguments.is_empty()) &&
+        segments.iter().zip(&matchers[3 - len..]).all(
+            |(seg, m)| m.iter().any(|s| seg.ident == s));
+    if matches { Some(op) } else { None }
+}
+
+/// The operation of a `std::ops` operator trait bound (or `std::iter::Sum`/
+/// `Product`), if it is one
+fn bound_op(bound: &TraitBound) -> Option<Op> {
+    let segments = &bound.path.segments;
+    let last = segments.last()?;
+    let &(op, _, _) = OPS.iter().find(|&&(op, t, _)| op != Op::Abs && op != Op::Pow && last.ident == t)?;
+    let module = if let Op::Sum | Op::Product = op { "iter" } else { "ops" };
+    if segments.len() > 1 && segments[segments.len() - 2].ident != module {
+        return None;
+    }
+    Some(op)
+}
+
+/// Do the generics have `Sum` or `Product` bounds?
+fn has_iter_bounds(generics: &Generics) -> bool {
+    let is_iter = |b: &TypeParamBound| match *b {
+        TypeParamBound::Trait(ref t) => matches!(bound_op(t), Some(Op::Sum) | Some(Op::Product)),
+        TypeParamBound::Lifetime(_) => false,
+    };
+    generics.type_params().any(|p| p.bounds.iter().any(is_iter)) ||
+        generics.where_clause.iter().flat_map(|w| &w.predicates).any(|p| match *p {
+            WherePredicate::Type(ref p) => p.bounds.iter().any(is_iter),
+            _ => false,
+        })
+}
+
+/// Remove parentheses that become redundant once an operand is passed as a
+/// function argument
+fn strip_parens(expr: Expr) -> Expr {
+    match expr {
+        Expr::Paren(p) if p.attrs.is_empty() => strip_parens(*p.expr),
+        expr => expr,
+    }
+}
+
+/// `receiver.method::<turbofish>(args)`
+fn method_call(receiver: Expr, method: Ident, turbofish: Option<MethodTurbofish>,
+               args: Vec<Expr>) -> Expr {
+    Expr::MethodCall(ExprMethodCall {
+        attrs: vec![],
+        receiver: Box::new(receiver),
+        dot_token: Default::default(),
+        method,
+        turbofish,
+        paren_token: Default::default(),
+        args: args.into_iter().collect(),
+    })
+}
+
+/// Is this a literal, possibly in parentheses?
+fn is_literal(expr: &Expr) -> bool {
+    match *expr {
+        Expr::Lit(_) => true,
+        Expr::Paren(ref p) => is_literal(&p.expr),
+        Expr::Group(ref g) => is_literal(&g.expr),
+        _ => false,
+    }
+}
+
+/// A function or inherent method that an item makes generic over the policy,
+/// by its path within the item
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum InheritFn {
+    /// a function in the module with the given path
+    Fn(Vec<Ident>, Ident),
+    /// a method of the type (as tokens) in the module with the given path
+    Method(Vec<Ident>, String, Ident),
+}
+
+/// Where we are within an item, to find the policy-generic functions that a
+/// call refers to
+#[derive(Clone, Default)]
+struct Scope {
+    // the modules within the item we are in
+    module: Vec<Ident>,
+    // the self type of the impl block we are in
+    self_ty: Option<String>,
+    // the names bound by patterns around us (e.g. by `let` or closure
+    // parameters), which shadow the functions of the module
+    locals: Vec<Ident>,
+    // the names that `use` declarations around us bring in, with the paths
+    // they stand for (or `None` for paths outside of the item, like `::m`)
+    imports: Vec<(Ident, Option<Vec<Ident>>)>,
+}
+
+impl Scope {
+    /// Go into a module, returning the scope to restore afterwards
+    fn enter_mod(&mut self, ident: &Ident) -> Scope {
+        let outer = self.clone();
+        self.module.push(ident.clone());
+        self.self_ty = None;
+        self.locals.clear();
+        self.imports.clear();
+        outer
+    }
+
+    // how many locals and imports there are, to drop those of an inner block
+    // or expression again when leaving it
+    fn mark(&self) -> (usize, usize) {
+        (self.locals.len(), self.imports.len())
+    }
+
+    fn reset(&mut self, (locals, imports): (usize, usize)) {
+        self.locals.truncate(locals);
+        self.imports.truncate(imports);
+    }
+
+    /// Record the names that the `use` declarations among the given items
+    /// bring in
+    fn import<'a, I: IntoIterator<Item = &'a Item>>(&mut self, items: I) {
+        for item in items {
+            if let Item::Use(ref u) = *item {
+                let prefix = if u.leading_colon.is_some() { None } else { Some(vec![]) };
+                self.import_tree(&u.tree, prefix);
+            }
+        }
+    }
+
+    fn import_tree(&mut self, tree: &UseTree, prefix: Option<Vec<Ident>>) {
+        let join = |ident: &Ident| prefix.clone().map(|mut path| {
+            // `use m::{self}` imports `m` itself
+            if ident != "self" {
+                path.push(ident.clone());
+            }
+            path
+        });
+        match *tree {
+            UseTree::Path(ref p) => self.import_tree(&p.tree, join(&p.ident)),
+            UseTree::Name(ref n) if n.ident == "self" => {
+                if let Some(last) = prefix.as_ref().and_then(|p| p.last()) {
+                    self.imports.push((last.clone(), prefix.clone()));
+                }
+            }
+            UseTree::Name(ref n) => self.imports.push((n.ident.clone(), join(&n.ident))),
+            UseTree::Rename(ref r) => self.imports.push((r.rename.clone(), join(&r.ident))),
+            UseTree::Glob(_) => {}
+            UseTree::Group(ref g) => {
+                for tree in &g.items {
+                    self.import_tree(tree, prefix.clone());
+                }
+            }
+        }
+    }
+
+    fn function(&self, ident: &Ident) -> InheritFn {
+        InheritFn::Fn(self.module.clone(), ident.clone())
+    }
+
+    fn method(&self, ident: &Ident) -> Option<InheritFn> {
+        let ty = self.self_ty.clone()?;
+        Some(InheritFn::Method(self.module.clone(), ty, ident.clone()))
+    }
+
+    /// The function or method a call of `path` refers to if it is within the
+    /// item, e.g. `scale`, `helpers::scale` or `super::scale` for a function
+    /// and `Self::scale` for a method
+    ///
+    /// Local bindings shadow functions (so calling a closure `scale` is left
+    /// alone), and names brought in by `use` stand for their paths.
+    fn resolve(&self, path: &Path) -> Option<InheritFn> {
+        if path.leading_colon.is_some() {
+            return None;
+        }
+        let mut segments = path.segments.iter().map(|s| s.ident.clone()).collect::<Vec<_>>();
+        let first = segments.first()?.clone();
+        if segments.len() == 1 && self.locals.contains(&first) {
+            return None;
+        }
+        if let Some((_, import)) = self.imports.iter().rev().find(|(name, _)| *name == first) {
+            let mut import = import.clone()?;
+            import.extend(segments.drain(1..));
+            segments = import;
+        }
+        let (last, init) = segments.split_last()?;
+        if init.len() == 1 && init[0] == "Self" {
+            return self.method(last);
+        }
+        let mut module = self.module.clone();
+        for (i, segment) in init.iter().enumerate() {
+            if segment == "super" {
+                module.pop()?;
+            } else if segment == "crate" || segment == "Self" || (segment == "self" && i > 0) {
+                return None;
+            } else if segment != "self" {
+                module.push(segment.clone());
+            }
+        }
+        Some(InheritFn::Fn(module, last.clone()))
+    }
+}
+
+// how an impl's self type is told apart from others in `InheritFn::Method`
+fn type_key(ty: &Type) -> String {
+    quote!(#ty).to_string()
+}
+
+/// Collects the functions and inherent methods that an item makes generic
+/// over the policy, with their number of type parameters, so that calls to
+/// them can pass the caller's policy
+struct InheritFns {
+    inherit: bool,
+    in_trait_impl: bool,
+    scope: Scope,
+    fns: HashMap<InheritFn, usize>,
+}
+
+impl InheritFns {
+    fn with_attrs<F: FnOnce(&mut Self)>(&mut self, attrs: &[Attribute], f: F) {
+        let outer = self.inherit;
+        if attrs.iter().any(is_overflow_attr) {
+            self.inherit = attr_policy(attrs) == Some(Policy::Inherit);
+        }
+        f(self);
+        self.inherit = outer;
+    }
+
+    fn record(&mut self, key: Option<InheritFn>, sig: &Signature) {
+        if let (true, Some(key)) = (self.inherit, key) {
+            self.fns.insert(key, sig.generics.type_params().count());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for InheritFns {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.with_attrs(&i.attrs, |v| {
+            v.record(Some(v.scope.function(&i.sig.ident)), &i.sig);
+            visit::visit_item_fn(v, i)
+        })
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast ItemMod) {
+        self.with_attrs(&i.attrs, |v| {
+            let outer = v.scope.enter_mod(&i.ident);
+            visit::visit_item_mod(v, i);
+            v.scope = outer;
+        })
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        let outer = self.in_trait_impl;
+        let outer_ty = self.scope.self_ty.replace(type_key(&i.self_ty));
+        self.in_trait_impl = i.trait_.is_some();
+        self.with_attrs(&i.attrs, |v| visit::visit_item_impl(v, i));
+        self.in_trait_impl = outer;
+        self.scope.self_ty = outer_ty;
+    }
+
+    fn visit_impl_item_method(&mut self, i: &'ast ImplItemMethod) {
+        self.with_attrs(&i.attrs, |v| {
+            if !v.in_trait_impl {
+                v.record(v.scope.method(&i.sig.ident), &i.sig);
+            }
+            visit::visit_impl_item_method(v, i)
+        })
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast ItemTrait) {
+        // trait methods never become generic over the policy
+        let outer = self.inherit;
+        self.inherit = false;
+        visit::visit_item_trait(self, i);
+        self.inherit = outer;
+    }
+}
+
+/// The rewriter for one policy
+///
+/// This also parses the arguments of the `#[overflow(..)]` attribute, i.e. a
+/// policy, optionally followed by `, crate = path`, `, div_by_zero = policy`
+/// (see `div_by_zero`) and `, shifts = mask|zero_fill|rotate` (see `shifts`).
+#[derive(Clone)]
+pub struct Overflower {
+    mode: Policy,
+    krate: Path,
+    // the policy for zero divisors, if it differs from `mode`'s
+    div_by_zero: Option<Policy>,
+    // the handling of out-of-range shift amounts, if it differs from `mode`'s
+    shifts: Option<Shifts>,
+    // the trait paths (one per mode in `mode.trait_modes()`) and the method
+    // path per `Op`, built once per attribute
+    paths: Vec<(Vec<Path>, ExprPath)>,
+    // the `Dispatch::<_, Marker>::new` path per `Op` (see `dispatch`), built
+    // once per attribute, too
+    dispatch_paths: Vec<ExprPath>,
+    // the attribute we put on macro invocations
+    attr: Attribute,
+    // the policy type we pass to policy-generic functions
+    policy: Type,
+    // the policy-generic functions and methods we know of
+    inherit_fns: HashMap<InheritFn, usize>,
+    // where we are within the item, to look up calls in `inherit_fns`
+    scope: Scope,
+    // whether we are within a function that is generic over the policy
+    in_policy_fn: bool,
+    in_trait_impl: bool,
+    // whether to rewrite macro arguments instead of putting the attribute on
+    // the invocation
+    macro_args: bool,
+    // whether to rewrite operations into method calls on a `Dispatch`
+    dispatch: bool,
+    // whether the generics of the items we are in have `Sum` or `Product`
+    // bounds
+    iter_bounds: bool,
+}
+
+impl Parse for Overflower {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Overflower::parse_args(input, None)
+    }
+}
+
+impl Overflower {
+    /// A parser for the arguments of the `#[overflow(..)]` attribute, with
+    /// generated code referring to the `overflower` crate by `krate` unless
+    /// they give a `crate = path`
+    ///
+    /// Parsing `Overflower` itself finds the default path with `crate_path`.
+    pub fn parser(krate: Path) -> impl Parser<Output = Self> {
+        move |input: ParseStream| Overflower::parse_args(input, Some(krate))
+    }
+
+    fn parse_args(input: ParseStream, default_krate: Option<Path>) -> Result<Self> {
+        let mode = input.parse::<Policy>()?;
+        let (mut krate, mut div_by_zero, mut shifts) = (None, None, None);
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.peek(Token![crate]) {
+                input.parse::<Token![crate]>()?;
+                input.parse::<Token![=]>()?;
+                krate = Some(input.call(Path::parse_mod_style)?);
+                continue;
+            }
+            let ident = input.parse::<Ident>()?;
+            if ident != "div_by_zero" && ident != "shifts" {
+                return Err(Error::new(ident.span(),
+                    "expected `crate`, `div_by_zero` or `shifts`"));
+            }
+            input.parse::<Token![=]>()?;
+            if ident == "shifts" {
+                shifts = Some(input.parse::<Shifts>()?);
+            } else {
+                let zero = input.parse::<Policy>()?;
+                if !zero.combines() {
+                    return Err(Error::new(ident.span(),
+                        "`div_by_zero` needs the `wrap`, `panic` or `saturate` policy"));
+                }
+                div_by_zero = Some(zero);
+            }
+            if !mode.combines() {
+                return Err(Error::new(ident.span(),
+                    format!("`{}` needs the `wrap`, `panic` or `saturate` policy", ident)));
+            }
+        }
+        let krate = krate.or(default_krate).unwrap_or_else(crate_path);
+        let mut overflower = Overflower::new(mode, krate);
+        if let Some(zero) = div_by_zero {
+            overflower = overflower.div_by_zero(zero);
+        }
+        if let Some(shifts) = shifts {
+            overflower = overflower.shifts(shifts);
+        }
+        Ok(overflower)
+    }
+}
+
+/// Find the path of the `overflower` crate from the manifest of the crate
+/// being compiled, so renaming the dependency doesn't break the generated
+/// code
+///
+/// Falls back to `::overflower` if the manifest doesn't list it.
+pub fn crate_path() -> Path {
+    found_crate_path(proc_macro_crate::crate_name("overflower").ok())
+}
+
+/// Find the path of the `overflower` crate from the given manifest (e.g. a
+/// `Cargo.toml` whose crate is rewritten outside of the compiler), as
+/// `crate_path` does for the crate being compiled
+///
+/// Falls back to `::overflower` if the manifest can't be read or doesn't list
+/// it.
+pub fn crate_path_in(manifest: &std::path::Path) -> Path {
+    let manifest = std::fs::read_to_string(manifest).ok()
+        .and_then(|toml| toml.parse::<toml_edit::Document>().ok());
+    found_crate_path(manifest.and_then(|m| find_crate(&m)))
+}
+
+fn found_crate_path(found: Option<FoundCrate>) -> Path {
+    match found {
+        Some(FoundCrate::Itself) => parse_quote!(crate),
+        Some(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, Span::call_site());
+            parse_quote!(::#name)
+        }
+        None => parse_quote!(::overflower),
+    }
+}
+
+// the `overflower` crate among the package and its (dev-)dependencies,
+// including target-specific ones
+fn find_crate(manifest: &toml_edit::Document) -> Option<FoundCrate> {
+    let package = manifest.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str());
+    if package == Some("overflower") {
+        return Some(FoundCrate::Itself);
+    }
+    let targets = manifest.get("target").and_then(|t| t.as_table()).into_iter()
+        .flat_map(|t| t.iter().filter_map(|(_, cfg)| cfg.as_table()));
+    std::iter::once(manifest.as_table()).chain(targets)
+        .flat_map(|t| ["dependencies", "dev-dependencies"].iter()
+            .filter_map(move |key| t.get(key)?.as_table_like()))
+        .flat_map(|deps| deps.iter())
+        .find(|&(name, dep)| {
+            dep.get("package").and_then(|p| p.as_str()).unwrap_or(name) == "overflower"
+        })
+        .map(|(name, _)| FoundCrate::Name(name.replace('-', "_")))
+}
+
+// the type parameter `#[overflow(inherit)]` adds to functions
+const POLICY_PARAM: &str = "OverflowPolicy";
+
+impl Overflower {
+    fn is_overflow(&self, attrs: &[Attribute]) -> bool {
+        if let Policy::Default = self.mode { return true; }
+        attrs.iter().any(is_overflow_attr)
+    }
+
+    // Should we leave this expression alone? In inherit mode, that's the case
+    // for everything outside of policy-generic functions (e.g. constants).
+    fn skip(&self, attrs: &[Attribute]) -> bool {
+        self.is_overflow(attrs) || (matches!(self.mode, Policy::Inherit) && !self.in_policy_fn)
+    }
+
+    /// Create a rewriter for a policy, with generated code referring to the
+    /// `overflower` crate by `krate` (e.g. `::overflower`)
+    pub fn new(mode: Policy, krate: Path) -> Self {
+        let policy_param = Ident::new(POLICY_PARAM, Span::call_site());
+        let paths = OPS.iter().map(|&(op, trait_stem, method_stem)| {
+            let op_mode = mode.op_mode(op);
+            let trait_paths = op_mode.trait_modes().iter().map(|m| {
+                let mut trait_path = krate.clone();
+                trait_path.segments.push(Ident::new(
+                    &format!("{}{}", trait_stem, m.name()), Span::call_site()).into());
+                trait_path
+            }).collect::<Vec<_>>();
+            let method_path = if let Policy::Inherit = mode {
+                let method_name = Ident::new(method_stem, Span::call_site());
+                parse_quote!(<#policy_param as #krate::Policy>::#method_name)
+            } else {
+                let mut method_path = trait_paths.first().unwrap_or(&krate).clone();
+                method_path.segments.push(Ident::new(&format!("{}_{}", method_stem,
+                    op_mode.name().to_lowercase()), Span::call_site()).into());
+                ExprPath { attrs: vec![], qself: None, path: method_path }
+            };
+            (trait_paths, method_path)
+        }).collect();
+        let mode_name = Ident::new(&mode.name().to_lowercase(), Span::call_site());
+        let attr = parse_quote!(#[overflow(#mode_name, crate = #krate)]);
+        let policy = if let Policy::Inherit = mode {
+            parse_quote!(#policy_param)
+        } else {
+            // `Finite` is no `Policy`, but panics on integer overflow all the same
+            let policy_name = Ident::new(mode.op_mode(Op::Shl).name(), Span::call_site());
+            parse_quote!(#krate::#policy_name)
+        };
+        let mut overflower = Overflower {
+            mode,
+            krate,
+            div_by_zero: None,
+            shifts: None,
+            paths,
+            dispatch_paths: vec![],
+            attr,
+            policy,
+            inherit_fns: HashMap::new(),
+            scope: Scope::default(),
+            in_policy_fn: false,
+            in_trait_impl: false,
+            macro_args: false,
+            dispatch: false,
+            iter_bounds: false,
+        };
+        overflower.update_dispatch_paths();
+        overflower
+    }
+
+    /// Handle zero divisors of divisions and remainders (including `/=` and
+    /// `%=`) as the given policy does, e.g. saturate them while wrapping on
+    /// overflow
+    ///
+    /// The operations are rewritten to the `DivWith` family of traits of the
+    /// `overflower` crate, which only integers, floats and `Duration`
+    /// implement (unless the `specialization` feature is on). Both policies
+    /// need to be `Wrap`, `Panic` or `Saturate`; otherwise, this does nothing.
+    /// Zero divisors panic with both `Wrap` and `Panic`.
+    pub fn div_by_zero(mut self, zero: Policy) -> Self {
+        if !self.mode.combines() || !zero.combines() {
+            return self;
+        }
+        let (krate, mode, zero_name) = (&self.krate, self.mode, zero.name());
+        let policy = Ident::new(mode.name(), Span::call_site());
+        let zero_policy = Ident::new(zero_name, Span::call_site());
+        for &(op, trait_stem, method_stem) in OPS.iter() {
+            if let Op::Div | Op::Rem | Op::DivAssign | Op::RemAssign = op {
+                let trait_name = Ident::new(&format!("{}With", trait_stem), Span::call_site());
+                let method = Ident::new(&format!("{}_with", method_stem), Span::call_site());
+                // the divisor may be of another type, e.g. `Duration / u32`
+                self.paths[op as usize].1 = parse_quote!(
+                    #krate::#trait_name::<#krate::#policy, #krate::#zero_policy, _>::#method);
+            }
+        }
+        self.div_by_zero = Some(zero);
+        self.update_dispatch_paths();
+        self.update_attr();
+        self
+    }
+
+    /// Handle shift amounts that are negative or not lower than the width of
+    /// the type (including those of `<<=` and `>>=`) as the given shift
+    /// policy does, e.g. rotate by them while panicking when bits are shifted
+    /// out of the value
+    ///
+    /// The operations are rewritten to the `ShlWith` family of traits of the
+    /// `overflower` crate, which only integers implement (unless the
+    /// `specialization` feature is on). The policy needs to be `Wrap`,
+    /// `Panic` or `Saturate`; otherwise, this does nothing.
+    pub fn shifts(mut self, shifts: Shifts) -> Self {
+        if !self.mode.combines() {
+            return self;
+        }
+        let krate = &self.krate;
+        let policy = Ident::new(self.mode.name(), Span::call_site());
+        let shift_policy = Ident::new(shifts.names().0, Span::call_site());
+        for &(op, trait_stem, method_stem) in OPS.iter() {
+            if let Op::Shl | Op::Shr | Op::ShlAssign | Op::ShrAssign = op {
+                let trait_name = Ident::new(&format!("{}With", trait_stem), Span::call_site());
+                let method = Ident::new(&format!("{}_with", method_stem), Span::call_site());
+                self.paths[op as usize].1 = parse_quote!(
+                    #krate::#trait_name::<#krate::#policy, #krate::#shift_policy, _>::#method);
+            }
+        }
+        self.shifts = Some(shifts);
+        self.update_dispatch_paths();
+        self.update_attr();
+        self
+    }
+
+    // the markers depend on `div_by_zero` and `shifts`
+    fn update_dispatch_paths(&mut self) {
+        let krate = &self.krate;
+        self.dispatch_paths = OPS.iter().map(|&(op, _, _)| {
+            let marker = self.dispatch_marker(op);
+            parse_quote!(#krate::dispatch::Dispatch::<_, #marker>::new)
+        }).collect();
+    }
+
+    // put the options on the attribute for macro invocations, too
+    fn update_attr(&mut self) {
+        let krate = &self.krate;
+        let mode_name = Ident::new(&self.mode.name().to_lowercase(), Span::call_site());
+        let zero = self.div_by_zero.map(|zero| {
+            let zero_name = Ident::new(&zero.name().to_lowercase(), Span::call_site());
+            quote!(, div_by_zero = #zero_name)
+        });
+        let shifts = self.shifts.map(|shifts| {
+            let shifts_name = Ident::new(shifts.names().1, Span::call_site());
+            quote!(, shifts = #shifts_name)
+        });
+        self.attr = parse_quote!(#[overflow(#mode_name, crate = #krate #zero #shifts)]);
+    }
+
+    /// Rewrite the arguments of macro invocations in place if they are
+    /// comma-separated expressions (as for `assert_eq!` or `println!`),
+    /// leaving other invocations as they are
+    ///
+    /// By default, macro invocations get the `#[overflow(..)]` attribute
+    /// instead, so their expansion is rewritten, too. However, attributes on
+    /// expressions need a nightly compiler.
+    pub fn rewrite_macro_args(mut self) -> Self {
+        self.macro_args = true;
+        self
+    }
+
+    /// Rewrite operations into method calls on a `Dispatch` of their operands
+    /// (see the `overflower::dispatch` module), e.g. `a + b` into
+    /// `Dispatch::<_, Wrap>::new((a, b)).add()`
+    ///
+    /// This way, operands whose types don't implement the policy traits use
+    /// their `std::ops` traits, as with the `specialization` feature of the
+    /// `overflower` crate, but on a stable compiler. Calls like `i32::abs(x)`
+    /// are rewritten as usual, as only integers have them.
+    pub fn dispatch(mut self) -> Self {
+        self.dispatch = true;
+        self
+    }
+
+    /// Rewrite code for a stable compiler, i.e. without the `specialization`
+    /// feature of the `overflower` crate, the same way the attribute does:
+    /// with `dispatch` and `rewrite_macro_args`
+    pub fn stable(self) -> Self {
+        self.dispatch().rewrite_macro_args()
+    }
+
+    /// Let rustdoc show how integer overflow is handled within an item
+    ///
+    /// Documented items get a line like "Integer overflow in this function:
+    /// saturates." appended. Undocumented ones are left as they are, so the
+    /// `missing_docs` lint still catches them. Where rustdoc allows it, there
+    /// is also a search alias like `overflow_saturate`, so all items with a
+    /// given policy can be looked up.
+    fn document(&self, attrs: &mut Vec<Attribute>, kind: &str, alias: bool) {
+        if attrs.iter().any(|a| a.path.is_ident("doc")) {
+            // with a leading space, like the lines of `///` comments
+            let mut notes = vec![];
+            if let Some(zero) = self.div_by_zero {
+                notes.push(format!("division by zero {}",
+                    if let Policy::Saturate = zero { "saturates" } else { "panics" }));
+            }
+            if let Some(shifts) = self.shifts {
+                notes.push(format!("out-of-range shift amounts {}", shifts.description()));
+            }
+            let line = if notes.is_empty() {
+                format!(" Integer overflow in this {}: {}.", kind, self.mode.description())
+            } else {
+                format!(" Integer overflow in this {}: {} ({}).", kind,
+                    self.mode.description(), notes.join(", "))
+            };
+            attrs.push(parse_quote!(#[doc = ""]));
+            attrs.push(parse_quote!(#[doc = #line]));
+        }
+        if alias {
+            let alias = format!("overflow_{}", self.mode.name().to_lowercase());
+            attrs.push(parse_quote!(#[doc(alias = #alias)]));
+        }
+    }
+
+    fn collect_inherit_fns<F: FnOnce(&mut InheritFns)>(&mut self, visit: F) {
+        let mut inherit_fns = InheritFns {
+            inherit: matches!(self.mode, Policy::Inherit),
+            in_trait_impl: false,
+            scope: Scope::default(),
+            fns: HashMap::new(),
+        };
+        visit(&mut inherit_fns);
+        self.inherit_fns = inherit_fns.fns;
+    }
+
+    /// Make a function generic over the policy in inherit mode
+    fn add_policy_param(&self, generics: &mut Generics) {
+        let policy_param = Ident::new(POLICY_PARAM, Span::call_site());
+        let krate = &self.krate;
+        let lifetimes = generics.lifetimes().count();
+        generics.params.insert(lifetimes, parse_quote!(#policy_param: #krate::Policy));
+    }
+
+    /// Pass our policy to a call of a policy-generic function of the item,
+    /// e.g. turn `scale(x)` into `scale::<overflower::Saturate>(x)`
+    fn pass_policy(&self, path: &mut Path) {
+        let type_params = match self.scope.resolve(path).and_then(|f| self.inherit_fns.get(&f)) {
+            Some(&n) => n,
+            None => return,
+        };
+        let policy = &self.policy;
+        let segment = path.segments.last_mut().unwrap();
+        match segment.arguments {
+            PathArguments::None => {
+                let infer = (0..type_params).map(|_| quote!(_));
+                segment.arguments = PathArguments::AngleBracketed(
+                    parse_quote!(::<#policy #(, #infer)*>));
+            }
+            PathArguments::AngleBracketed(ref mut a) => {
+                let lifetimes = a.args.iter().take_while(
+                    |a| matches!(a, GenericArgument::Lifetime(_))).count();
+                a.args.insert(lifetimes, GenericArgument::Type(policy.clone()));
+            }
+            PathArguments::Parenthesized(_) => {}
+        }
+    }
+
+    /// Map a `std::ops` operator trait bound (or `std::iter::Sum`/`Product`)
+    /// to the respective bounds on our policy traits, e.g. `Add<Output = T>`
+    /// to `overflower::AddWrap<T, Output = T>` (or all three of `AddWrap`,
+    /// `AddPanic` and `AddSaturate` in inherit mode)
+    ///
+    /// The right-hand side type is always spelled out, because the policy
+    /// shift traits default to `usize` where `std::ops` defaults to `Self`.
+    fn policy_bounds(&self, bounded_ty: &Type, bound: &TraitBound) -> Vec<TraitBound> {
+        let op = match bound_op(bound) {
+            Some(op) => op,
+            None => return vec![],
+        };
+        let last = bound.path.segments.last().unwrap();
+        let mut args = match last.arguments {
+            PathArguments::None => punctuated::Punctuated::new(),
+            PathArguments::AngleBracketed(ref a) => a.args.clone(),
+            PathArguments::Parenthesized(_) => return vec![],
+        };
+        if op != Op::Neg && !matches!(args.first(), Some(GenericArgument::Type(_))) {
+            args.insert(0, GenericArgument::Type(bounded_ty.clone()));
+        }
+        self.paths[op as usize].0.iter().map(|trait_path| {
+            let mut path = trait_path.clone();
+            if !args.is_empty() {
+                path.segments.last_mut().unwrap().arguments =
+                    PathArguments::AngleBracketed(parse_quote!(<#args>));
+            }
+            TraitBound {
+                paren_token: None,
+                modifier: TraitBoundModifier::None,
+                lifetimes: bound.lifetimes.clone(),
+                path,
+            }
+        }).collect()
+    }
+
+    /// Add policy trait bounds for every operator trait bound of the generics
+    ///
+    /// Without this, `fn sum<T: Add<Output = T>>(a: T, b: T) -> T { a + b }`
+    /// would not compile once the addition is rewritten to `AddWrap::add_wrap`
+    /// (unless the `specialization` feature supplies a blanket impl). The new
+    /// bounds go right next to the operator bound they were derived from.
+    fn add_policy_bounds(&self, generics: &mut Generics) {
+        let policy_bounds = |ty: &Type, bounds: &punctuated::Punctuated<TypeParamBound, Token![+]>| {
+            bounds.iter().flat_map(|b| match *b {
+                TypeParamBound::Trait(ref t) => self.policy_bounds(ty, t),
+                TypeParamBound::Lifetime(_) => vec![],
+            }).map(TypeParamBound::Trait).collect::<Vec<_>>()
+        };
+        for param in generics.type_params_mut() {
+            let ident = &param.ident;
+            let new_bounds = policy_bounds(&parse_quote!(#ident), &param.bounds);
+            param.bounds.extend(new_bounds);
+        }
+        if let Some(ref mut where_clause) = generics.where_clause {
+            for predicate in &mut where_clause.predicates {
+                if let WherePredicate::Type(ref mut p) = *predicate {
+                    let new_bounds = policy_bounds(&p.bounded_ty, &p.bounds);
+                    p.bounds.extend(new_bounds);
+                }
+            }
+        }
+    }
+
+    fn make_method(&self, op: Op, args: Vec<Expr>) -> Expr {
+        // sums and products are up to `make_iter_fold`
+        if self.dispatch && !matches!(op, Op::Abs | Op::Pow | Op::Sum | Op::Product) {
+            return self.make_dispatch(op, args, None);
+        }
+        Expr::Call(ExprCall {
+            attrs: vec![],
+            func: Box::new(Expr::Path(self.paths[op as usize].1.clone())),
+            paren_token: Default::default(),
+            args: args.into_iter().map(strip_parens).collect(),
+        })
+    }
+
+    /// `Dispatch::<_, Marker>::new(operands).method()`, with the operands in
+    /// a tuple if there are two of them
+    fn make_dispatch(&self, op: Op, args: Vec<Expr>, ty: Option<Type>) -> Expr {
+        let mut args = args.into_iter().map(strip_parens);
+        let operands = match (args.next(), args.next()) {
+            (Some(l), Some(r)) => Expr::Tuple(ExprTuple {
+                attrs: vec![],
+                paren_token: Default::default(),
+                elems: vec![l, r].into_iter().collect(),
+            }),
+            (operand, _) => operand.unwrap(),
+        };
+        let turbofish = ty.map(|ty| MethodTurbofish {
+            colon2_token: Default::default(),
+            lt_token: Default::default(),
+            args: vec![GenericMethodArgument::Type(ty)].into_iter().collect(),
+            gt_token: Default::default(),
+        });
+        let method = Ident::new(OPS[op as usize].2, Span::call_site());
+        method_call(self.make_dispatch_new(op, operands), method, turbofish, vec![])
+    }
+
+    /// `x.pow(n)`, which only takes the `Pow*` traits for numbers, so other
+    /// types keep calling their own `pow` methods (this goes through a
+    /// `Dispatch` with or without dispatch mode, as there is no `core` trait
+    /// for the `Pow*` traits to fall back to)
+    fn make_pow_dispatch(&self, base: Expr, exponent: Expr) -> Expr {
+        let base = self.make_dispatch_new(Op::Pow, strip_parens(base));
+        let pow_base = method_call(base, Ident::new("pow_base", Span::call_site()), None, vec![]);
+        method_call(pow_base, Ident::new("pow", Span::call_site()), None, vec![exponent])
+    }
+
+    // `Dispatch::<_, Marker>::new(operands)`
+    fn make_dispatch_new(&self, op: Op, operands: Expr) -> Expr {
+        Expr::Call(ExprCall {
+            attrs: vec![],
+            func: Box::new(Expr::Path(self.dispatch_paths[op as usize].clone())),
+            paren_token: Default::default(),
+            args: vec![operands].into_iter().collect(),
+        })
+    }
+
+    // the second type parameter of `Dispatch` for an operation
+    fn dispatch_marker(&self, op: Op) -> Type {
+        let krate = &self.krate;
+        let policy = &self.policy;
+        let with = match op {
+            Op::Div | Op::Rem | Op::DivAssign | Op::RemAssign => self.div_by_zero.map(Policy::name),
+            Op::Shl | Op::Shr | Op::ShlAssign | Op::ShrAssign => self.shifts.map(|s| s.names().0),
+            _ => None,
+        };
+        match (self.mode, with) {
+            (Policy::Inherit, _) => parse_quote!(#krate::dispatch::Inherit<#policy>),
+            (_, Some(with)) => {
+                let with = Ident::new(with, Span::call_site());
+                parse_quote!(#krate::dispatch::With<#policy, #krate::#with>)
+            }
+            (mode, _) if mode.op_mode(op) == Policy::Finite => parse_quote!(#krate::Finite),
+            _ => policy.clone(),
+        }
+    }
+
+    fn make_unary(&mut self, u: ExprUnary) -> Expr {
+        if self.skip(&u.attrs) {
+            return Expr::Unary(u);
+        }
+        match u.op {
+            // negative literals like `-128i8` must stay literals, both
+            // because the positive value may not fit the type and because
+            // they are allowed in patterns
+            syn::UnOp::Neg(_) if is_literal(&u.expr) => Expr::Unary(u),
+            syn::UnOp::Neg(_) => {
+                let operand = self.fold_expr(*u.expr);
+                self.make_method(Op::Neg, vec![operand])
+            }
+            _ => Expr::Unary(fold::fold_expr_unary(self, u)),
+        }
+    }
+
+    fn make_assign_op(&mut self, a: ExprAssignOp) -> Expr {
+        if self.skip(&a.attrs) {
+            return Expr::AssignOp(a);
+        }
+        let ExprAssignOp {
+            attrs,
+            left,
+            op,
+            right,
+        } = a;
+        let (left, right) = (self.fold_expr(*left), self.fold_expr(*right));
+        let op = match op {
+            syn::BinOp::AddEq(_) => Op::AddAssign,
+            syn::BinOp::SubEq(_) => Op::SubAssign,
+            syn::BinOp::MulEq(_) => Op::MulAssign,
+            syn::BinOp::DivEq(_) => Op::DivAssign,
+            syn::BinOp::RemEq(_) => Op::RemAssign,
+            syn::BinOp::ShlEq(_) => Op::ShlAssign,
+            syn::BinOp::ShrEq(_) => Op::ShrAssign,
+            op => {
+                return Expr::AssignOp(ExprAssignOp {
+                    attrs,
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                });
+            }
+        };
+        // Like the primitive compound assignments, we evaluate the right-hand
+        // side before the place. This also keeps e.g. `v[i] += v[j]` from
+        // borrowing `v` mutably while `v[j]` is still being read.
+        let rhs = Ident::new("__overflower_rhs", Span::mixed_site());
+        let call = self.make_method(op, vec![
+            Expr::Reference(ExprReference {
+                attrs: vec![],
+                and_token: Default::default(),
+                raw: Default::default(),
+                mutability: Some(Default::default()),
+                expr: Box::new(left),
+            }),
+            Expr::Path(ExprPath { attrs: vec![], qself: None, path: rhs.clone().into() }),
+        ]);
+        let local = Local {
+            attrs: vec![],
+            let_token: Default::default(),
+            pat: Pat::Ident(PatIdent {
+                attrs: vec![],
+                by_ref: None,
+                mutability: None,
+                ident: rhs,
+                subpat: None,
+            }),
+            init: Some((Default::default(), Box::new(right))),
+            semi_token: Default::default(),
+        };
+        Expr::Block(ExprBlock {
+            attrs: vec![],
+            label: None,
+            block: Block {
+                brace_token: Default::default(),
+                stmts: vec![Stmt::Local(local), Stmt::Expr(call)],
+            },
+        })
+    }
+
+    fn make_binary(&mut self, b: ExprBinary) -> Expr {
+        if self.skip(&b.attrs) {
+            return Expr::Binary(b);
+        }
+        let ExprBinary {
+            attrs,
+            left,
+            op,
+            right,
+        } = b;
+        let mut args = vec![self.fold_expr(*left), self.fold_expr(*right)];
+        match op {
+            syn::BinOp::Add(_) => self.make_method(Op::Add, args),
+            syn::BinOp::Sub(_) => self.make_method(Op::Sub, args),
+            syn::BinOp::Mul(_) => self.make_method(Op::Mul, args),
+            syn::BinOp::Div(_) => self.make_method(Op::Div, args),
+            syn::BinOp::Rem(_) => self.make_method(Op::Rem, args),
+            syn::BinOp::Shl(_) => self.make_method(Op::Shl, args),
+            syn::BinOp::Shr(_) => self.make_method(Op::Shr, args),
+            op => {
+                let (r, l) = (args.pop().unwrap(), args.pop().unwrap());
+                Expr::Binary(ExprBinary {
+                    attrs,
+                    left: Box::new(l),
+                    op,
+                    right: Box::new(r),
+                })
+            }
+        }
+    }
+
+    fn make_call(&mut self, c: ExprCall) -> Expr {
+        if self.skip(&c.attrs) {
+            return Expr::Call(c);
+        }
+        let mut c = fold::fold_expr_call(self, c);
+        let op = match *c.func {
+            Expr::Path(ref p) if p.qself.is_none() => int_fn_op(&p.path, c.args.len()),
+            _ => None,
+        };
+        if let Some(op) = op {
+            c.func = Box::new(Expr::Path(self.paths[op as usize].1.clone()));
+        } else if let Expr::Path(ExprPath { qself: None, ref mut path, .. }) = *c.func {
+            self.pass_policy(path);
+        }
+        Expr::Call(c)
+    }
+
+    fn make_method_call(&mut self, c: ExprMethodCall) -> Expr {
+        if self.skip(&c.attrs) {
+            return Expr::MethodCall(c);
+        }
+        let mut c = fold::fold_expr_method_call(self, c);
+        // only methods called on `self` are known to be those of the item
+        let inherit_fn = match *c.receiver {
+            Expr::Path(ref p) if p.qself.is_none() && p.path.is_ident("self") => {
+                self.scope.method(&c.method).and_then(|f| self.inherit_fns.get(&f)).copied()
+            }
+            _ => None,
+        };
+        if inherit_fn.is_none() && c.method == "pow" && c.args.len() == 1
+            && c.turbofish.is_none()
+        {
+            let exponent = c.args.pop().unwrap().into_value();
+            return self.make_pow_dispatch(*c.receiver, exponent);
+        }
+        if inherit_fn.is_none() && c.args.is_empty() {
+            let op = if c.method == "sum" {
+                Some(Op::Sum)
+            } else if c.method == "product" {
+                Some(Op::Product)
+            } else {
+                None
+            };
+            let ty = match c.turbofish {
+                None => Some(None),
+                Some(ref t) if t.args.len() == 1 => match t.args[0] {
+                    GenericMethodArgument::Type(ref ty) => Some(Some(ty.clone())),
+                    GenericMethodArgument::Const(_) => None,
+                },
+                Some(_) => None,
+            };
+            if let (Some(op), Some(ty)) = (op, ty) {
+                return self.make_iter_fold(op, *c.receiver, ty);
+            }
+        }
+        if let Some(type_params) = inherit_fn {
+            let policy = GenericMethodArgument::Type(self.policy.clone());
+            match c.turbofish {
+                Some(ref mut t) => t.args.insert(0, policy),
+                None => {
+                    let mut args = punctuated::Punctuated::new();
+                    args.push(policy);
+                    args.extend((0..type_params).map(|_| {
+                        GenericMethodArgument::Type(parse_quote!(_))
+                    }));
+                    c.turbofish = Some(MethodTurbofish {
+                        colon2_token: Default::default(),
+                        lt_token: Default::default(),
+                        args,
+                        gt_token: Default::default(),
+                    });
+                }
+            }
+        }
+        Expr::MethodCall(c)
+    }
+
+    /// Rewrite `iter.sum()` or `iter.sum::<T>()` (and the same for
+    /// `product`), keeping the explicit result type if there is one
+    ///
+    /// In dispatch mode, whether a sum uses the policy traits depends on the
+    /// type of the elements, which is unknown for type parameters. So within
+    /// items with `Sum` or `Product` bounds, sums are rewritten to the policy
+    /// traits their added bounds require instead.
+    fn make_iter_fold(&self, op: Op, receiver: Expr, ty: Option<Type>) -> Expr {
+        if self.dispatch && !self.iter_bounds {
+            return self.make_dispatch(op, vec![receiver], ty);
+        }
+        let mut call = self.make_method(op, vec![receiver]);
+        if let (Some(ty), Expr::Call(ref mut c)) = (ty, &mut call) {
+            if let Expr::Path(ref mut p) = *c.func {
+                if let Policy::Inherit = self.mode {
+                    p.path.segments.last_mut().unwrap().arguments =
+                        PathArguments::AngleBracketed(parse_quote!(::<#ty, _>));
+                } else {
+                    let trait_path = &self.paths[op as usize].0[0];
+                    let method = &p.path.segments.last().unwrap().ident;
+                    *p = parse_quote!(<#ty as #trait_path<_>>::#method);
+                }
+            }
+        }
+        call
+    }
+
+    // `let pat = expr` in `if` or `while`, binding `pat` after `expr`
+    fn make_let(&mut self, l: ExprLet) -> ExprLet {
+        let expr = Box::new(self.fold_expr(*l.expr));
+        ExprLet { pat: self.fold_pat(l.pat), expr, ..l }
+    }
+
+    fn make_macro(&mut self, m: ExprMacro) -> Expr {
+        if self.skip(&m.attrs) {
+            return Expr::Macro(m);
+        }
+        let mut m = m;
+        if self.macro_args {
+            self.fold_macro_args(&mut m.mac);
+        } else {
+            m.attrs.push(self.attr.clone());
+        }
+        Expr::Macro(m)
+    }
+
+    fn fold_macro_args(&mut self, mac: &mut Macro) {
+        type Args = punctuated::Punctuated<Expr, Token![,]>;
+        if let Ok(args) = mac.parse_body_with(Args::parse_terminated) {
+            let args = args.into_pairs().map(|pair| {
+                let (arg, comma) = pair.into_tuple();
+                punctuated::Pair::new(self.fold_expr(arg), comma)
+            }).collect::<Args>();
+            mac.tokens = quote!(#args);
+        }
+    }
+}
+
+impl Fold for Overflower {
+    fn fold_impl_item_method(&mut self, i: ImplItemMethod) -> ImplItemMethod {
+        if self.is_overflow(&i.attrs) { return i; }
+        let (outer, outer_bounds) = (self.in_policy_fn, self.iter_bounds);
+        self.in_policy_fn = !self.in_trait_impl;
+        self.iter_bounds |= has_iter_bounds(&i.sig.generics);
+        let mark = self.scope.mark();
+        let mut i = fold::fold_impl_item_method(self, i);
+        self.scope.reset(mark);
+        self.in_policy_fn = outer;
+        self.iter_bounds = outer_bounds;
+        if let Policy::Inherit = self.mode {
+            if self.in_trait_impl { return i; }
+            self.add_policy_param(&mut i.sig.generics);
+        }
+        self.add_policy_bounds(&mut i.sig.generics);
+        self.document(&mut i.attrs, "method", true);
+        i
+    }
+
+    fn fold_item_fn(&mut self, i: ItemFn) -> ItemFn {
+        if self.is_overflow(&i.attrs) { return i; }
+        let (outer, outer_bounds) = (self.in_policy_fn, self.iter_bounds);
+        self.in_policy_fn = true;
+        self.iter_bounds |= has_iter_bounds(&i.sig.generics);
+        let mark = self.scope.mark();
+        let mut i = fold::fold_item_fn(self, i);
+        self.scope.reset(mark);
+        self.in_policy_fn = outer;
+        self.iter_bounds = outer_bounds;
+        if let Policy::Inherit = self.mode {
+            self.add_policy_param(&mut i.sig.generics);
+        }
+        self.add_policy_bounds(&mut i.sig.generics);
+        self.document(&mut i.attrs, "function", true);
+        i
+    }
+
+    fn fold_item_impl(&mut self, i: ItemImpl) -> ItemImpl {
+        if self.is_overflow(&i.attrs) { return i; }
+        let (outer, outer_bounds) = (self.in_trait_impl, self.iter_bounds);
+        let outer_ty = self.scope.self_ty.replace(type_key(&i.self_ty));
+        self.in_trait_impl = i.trait_.is_some();
+        self.iter_bounds |= has_iter_bounds(&i.generics);
+        let mut i = fold::fold_item_impl(self, i);
+        self.in_trait_impl = outer;
+        self.iter_bounds = outer_bounds;
+        self.scope.self_ty = outer_ty;
+        self.add_policy_bounds(&mut i.generics);
+        self.document(&mut i.attrs, "impl block", false);
+        i
+    }
+
+    fn fold_item_const(&mut self, i: ItemConst) -> ItemConst {
+        if self.is_overflow(&i.attrs) { return i; }
+        let outer = self.in_policy_fn;
+        self.in_policy_fn = false;
+        let i = fold::fold_item_const(self, i);
+        self.in_policy_fn = outer;
+        i
+    }
+
+    fn fold_item_static(&mut self, i: ItemStatic) -> ItemStatic {
+        if self.is_overflow(&i.attrs) { return i; }
+        let outer = self.in_policy_fn;
+        self.in_policy_fn = false;
+        let i = fold::fold_item_static(self, i);
+        self.in_policy_fn = outer;
+        i
+    }
+
+    fn fold_item_macro(&mut self, i: ItemMacro) -> ItemMacro {
+        // macros in statement position, e.g. `assert!(a + b > c);`
+        let mut i = i;
+        if self.macro_args && i.ident.is_none() && !self.skip(&i.attrs) {
+            self.fold_macro_args(&mut i.mac);
+        }
+        i
+    }
+
+    fn fold_item_mod(&mut self, i: ItemMod) -> ItemMod {
+        if self.is_overflow(&i.attrs) { return i; }
+        let outer = self.scope.enter_mod(&i.ident);
+        if let Some((_, ref items)) = i.content {
+            self.scope.import(items);
+        }
+        let mut i = fold::fold_item_mod(self, i);
+        self.scope = outer;
+        self.document(&mut i.attrs, "module", true);
+        i
+    }
+
+    fn fold_item_trait(&mut self, i: ItemTrait) -> ItemTrait {
+        if self.is_overflow(&i.attrs) { return i; }
+        let outer_bounds = self.iter_bounds;
+        self.iter_bounds |= has_iter_bounds(&i.generics);
+        let mut i = fold::fold_item_trait(self, i);
+        self.iter_bounds = outer_bounds;
+        self.add_policy_bounds(&mut i.generics);
+        self.document(&mut i.attrs, "trait", true);
+        i
+    }
+
+    fn fold_trait_item_method(&mut self, i: TraitItemMethod) -> TraitItemMethod {
+        if self.is_overflow(&i.attrs) { return i; }
+        let (outer, outer_bounds) = (self.in_policy_fn, self.iter_bounds);
+        self.in_policy_fn = false;
+        self.iter_bounds |= has_iter_bounds(&i.sig.generics);
+        let mark = self.scope.mark();
+        let mut i = fold::fold_trait_item_method(self, i);
+        self.scope.reset(mark);
+        self.in_policy_fn = outer;
+        self.iter_bounds = outer_bounds;
+        self.add_policy_bounds(&mut i.sig.generics);
+        // default methods aren't made policy-generic in inherit mode
+        if i.default.is_some() && !matches!(self.mode, Policy::Inherit) {
+            self.document(&mut i.attrs, "method", true);
+        }
+        i
+    }
+
+    fn fold_block(&mut self, b: Block) -> Block {
+        let mark = self.scope.mark();
+        self.scope.import(b.stmts.iter().filter_map(|stmt| match *stmt {
+            Stmt::Item(ref item) => Some(item),
+            _ => None,
+        }));
+        let b = fold::fold_block(self, b);
+        self.scope.reset(mark);
+        b
+    }
+
+    fn fold_local(&mut self, l: Local) -> Local {
+        // the bindings are only in scope after the initializer
+        let init = l.init.map(|(eq, e)| (eq, Box::new(self.fold_expr(*e))));
+        Local { pat: self.fold_pat(l.pat), init, ..l }
+    }
+
+    fn fold_arm(&mut self, a: Arm) -> Arm {
+        let mark = self.scope.mark();
+        let a = fold::fold_arm(self, a);
+        self.scope.reset(mark);
+        a
+    }
+
+    fn fold_pat_ident(&mut self, p: PatIdent) -> PatIdent {
+        self.scope.locals.push(p.ident.clone());
+        fold::fold_pat_ident(self, p)
+    }
+
+    fn fold_expr(&mut self, e: Expr) -> Expr {
+        macro_rules! foldexpr {
+            ($s:expr, $ty:path, $t:ident, $f:path) => {
+                $ty(if self.is_overflow(& $t . attrs) {
+                    $t
+                } else {
+                    $f($s, $t)
+                })
+            }
+        }
+        // drop the bindings of closure parameters, `for` loops and the like
+        let mark = self.scope.mark();
+        let e = match e {
+            Expr::Box(b) => foldexpr!(self, Expr::Box, b, fold::fold_expr_box),
+            Expr::Array(a) => foldexpr!(self, Expr::Array, a, fold::fold_expr_array),
+            Expr::Call(c) => self.make_call(c),
+            Expr::MethodCall(c) => self.make_method_call(c),
+            Expr::Tuple(t) => foldexpr!(self, Expr::Tuple, t, fold::fold_expr_tuple),
+            Expr::Binary(b) => self.make_binary(b),
+            Expr::Unary(u) => self.make_unary(u),
+            Expr::Cast(c) => foldexpr!(self, Expr::Cast, c, fold::fold_expr_cast),
+            Expr::Type(t) => foldexpr!(self, Expr::Type, t, fold::fold_expr_type),
+            // the bindings of `if let` and `while let` stay in scope until
+            // we leave the `if` or `while`
+            Expr::Let(l) => return foldexpr!(self, Expr::Let, l, Self::make_let),
+            Expr::If(i) => foldexpr!(self, Expr::If, i, fold::fold_expr_if),
+            Expr::While(w) => foldexpr!(self, Expr::While, w, fold::fold_expr_while),
+            Expr::ForLoop(f) => foldexpr!(self, Expr::ForLoop, f, fold::fold_expr_for_loop),
+            Expr::Loop(l) => foldexpr!(self, Expr::Loop, l, fold::fold_expr_loop),
+            Expr::Match(m) => foldexpr!(self, Expr::Match, m, fold::fold_expr_match),
+            Expr::Closure(c) => foldexpr!(self, Expr::Closure, c, fold::fold_expr_closure),
+            Expr::Unsafe(u) => foldexpr!(self, Expr::Unsafe, u, fold::fold_expr_unsafe),
+            Expr::Block(b) => foldexpr!(self, Expr::Block, b, fold::fold_expr_block),
+            Expr::Assign(a) => foldexpr!(self, Expr::Assign, a, fold::fold_expr_assign),
+            Expr::AssignOp(o) => self.make_assign_op(o),
+            Expr::Field(f) => foldexpr!(self, Expr::Field, f, fold::fold_expr_field),
+            Expr::Index(i) => foldexpr!(self, Expr::Index, i, fold::fold_expr_index),
+            Expr::Range(r) => foldexpr!(self, Expr::Range, r, fold::fold_expr_range),
+            Expr::Path(p) => foldexpr!(self, Expr::Path, p, fold::fold_expr_path),
+            Expr::Reference(r) => foldexpr!(self, Expr::Reference, r, fold::fold_expr_reference),
+            Expr::Break(b) => foldexpr!(self, Expr::Break, b, fold::fold_expr_break),
+            Expr::Return(r) => foldexpr!(self, Expr::Return, r, fold::fold_expr_return),
+            Expr::Macro(m) => self.make_macro(m),
+            Expr::Struct(s) => foldexpr!(self, Expr::Struct, s, fold::fold_expr_struct),
+            Expr::Repeat(r) => foldexpr!(self, Expr::Repeat, r, fold::fold_expr_repeat),
+            Expr::Paren(p) => foldexpr!(self, Expr::Paren, p, fold::fold_expr_paren),
+            Expr::Try(t) => foldexpr!(self, Expr::Try, t, fold::fold_expr_try),
+            Expr::Async(a) => foldexpr!(self, Expr::Async, a, fold::fold_expr_async),
+            Expr::TryBlock(t) => foldexpr!(self, Expr::TryBlock, t, fold::fold_expr_try_block),
+            Expr::Yield(y) => foldexpr!(self, Expr::Yield, y, fold::fold_expr_yield),
+            x => x,
+        };
+        self.scope.reset(mark);
+        e
+    }
+}
+
+impl Overflower {
+    /// Rewrite the arithmetic within an item
+    pub fn rewrite_item(&self, item: &Item) -> Item {
+        let mut overflow = self.clone();
+        overflow.collect_inherit_fns(|v| v.visit_item(item));
+        let mut item = overflow.fold_item(item.clone());
+        if let Policy::Default = self.mode {
+            // nothing within gets folded, so document the item itself here
+            match item {
+                Item::Fn(ref mut f) => self.document(&mut f.attrs, "function", true),
+                Item::Mod(ref mut m) => self.document(&mut m.attrs, "module", true),
+                Item::Impl(ref mut i) => self.document(&mut i.attrs, "impl block", false),
+                Item::Trait(ref mut t) => self.document(&mut t.attrs, "trait", true),
+                _ => {}
+            }
+        }
+        item
+    }
+
+    /// Rewrite the arithmetic within an expression
+    ///
+    /// In inherit mode, the expression is taken to be part of a function
+    /// that is generic over the policy.
+    pub fn rewrite_expr(&self, expr: &Expr) -> Expr {
+        let mut overflow = self.clone();
+        overflow.collect_inherit_fns(|v| v.visit_expr(expr));
+        overflow.in_policy_fn = true;
+        overflow.fold_expr(expr.clone())
+    }
+
+    /// Rewrite the arithmetic within a block
+    ///
+    /// In inherit mode, the block is taken to be part of a function that is
+    /// generic over the policy.
+    pub fn rewrite_block(&self, block: &Block) -> Block {
+        let mut overflow = self.clone();
+        overflow.collect_inherit_fns(|v| v.visit_block(block));
+        overflow.in_policy_fn = true;
+        overflow.fold_block(block.clone())
+    }
+}
+
+/// Rewrite the arithmetic within an item under a policy
+pub fn rewrite_item(item: &Item, policy: Policy) -> Item {
+    Overflower::new(policy, crate_path()).rewrite_item(item)
+}
+
+/// Rewrite the arithmetic within an expression under a policy
+pub fn rewrite_expr(expr: &Expr, policy: Policy) -> Expr {
+    Overflower::new(policy, crate_path()).rewrite_expr(expr)
+}
+
+/// Rewrite the arithmetic within a block under a policy
+pub fn rewrite_block(block: &Block, policy: Policy) -> Block {
+    Overflower::new(policy, crate_path()).rewrite_block(block)
+}
@@ -0,0 +1,79 @@
+//! Check the documentation that `#[overflow(..)]` adds to annotated items
+
+extern crate overflower_rewrite;
+extern crate syn;
+
+use overflower_rewrite::Overflower;
+
+fn expand(attr: &str, code: &str) -> syn::File {
+    let overflower = syn::parse_str::<Overflower>(attr).unwrap();
+    let file = syn::parse_str::<syn::File>(code).unwrap();
+    syn::File {
+        items: file.items.iter().map(|i| overflower.rewrite_item(i)).collect(),
+        ..file
+    }
+}
+
+fn docs(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs.iter().filter(|a| a.path.is_ident("doc")).map(|a| {
+        a.tokens.to_string()
+    }).collect()
+}
+
+#[test]
+fn test_documented_fn() {
+    let file = expand("saturate, crate = ::overflower", "
+        /// Scale a value
+        pub fn scale(x: u32) -> u32 { x * 3 }
+    ");
+    let f = match file.items[0] {
+        syn::Item::Fn(ref f) => f,
+        _ => unreachable!(),
+    };
+    assert_eq!(vec![
+        "= \" Scale a value\"",
+        "= \"\"",
+        "= \" Integer overflow in this function: saturates.\"",
+        "(alias = \"overflow_saturate\")",
+    ], docs(&f.attrs));
+}
+
+#[test]
+fn test_undocumented_items() {
+    let file = expand("panic, crate = ::overflower", "
+        mod m {
+            fn f(x: u8) -> u8 { x + 1 }
+            impl S { fn g(&self) {} }
+        }
+    ");
+    let m = match file.items[0] {
+        syn::Item::Mod(ref m) => m,
+        _ => unreachable!(),
+    };
+    assert_eq!(vec!["(alias = \"overflow_panic\")"], docs(&m.attrs));
+    let content = &m.content.as_ref().unwrap().1;
+    match content[0] {
+        syn::Item::Fn(ref f) => {
+            assert_eq!(vec!["(alias = \"overflow_panic\")"], docs(&f.attrs));
+        }
+        _ => unreachable!(),
+    }
+    match content[1] {
+        syn::Item::Impl(ref i) => assert!(docs(&i.attrs).is_empty()),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_default_mode() {
+    let file = expand("default, crate = ::overflower", "
+        /// Add one
+        fn add_one(x: u8) -> u8 { x + 1 }
+    ");
+    let f = match file.items[0] {
+        syn::Item::Fn(ref f) => f,
+        _ => unreachable!(),
+    };
+    assert_eq!(4, docs(&f.attrs).len());
+    assert!(docs(&f.attrs)[2].starts_with("= \" Integer overflow in this function: uses the standard"));
+}
@@ -0,0 +1,289 @@
+//! Check the public rewriting API on expressions and blocks
+
+extern crate overflower_rewrite;
+extern crate quote;
+extern crate syn;
+
+use overflower_rewrite::{attr_policy, Overflower, Policy, Shifts};
+use quote::ToTokens;
+use syn::parse::Parser;
+
+fn overflower(policy: Policy) -> Overflower {
+    Overflower::new(policy, syn::parse_str("::overflower").unwrap())
+}
+
+fn tokens<T: ToTokens>(t: T) -> String {
+    t.into_token_stream().to_string()
+}
+
+#[test]
+fn test_rewrite_expr() {
+    let expr = syn::parse_str("a * (b + 1)").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Expr>(
+            "::overflower::MulWrap::mul_wrap(a, ::overflower::AddWrap::add_wrap(b, 1))"
+        ).unwrap()),
+        tokens(overflower(Policy::Wrap).rewrite_expr(&expr)));
+    assert_eq!(tokens(&expr), tokens(overflower(Policy::Default).rewrite_expr(&expr)));
+}
+
+#[test]
+fn test_rewrite_inherit_calls() {
+    // only calls of the item's own policy-generic functions and methods get
+    // the policy, not those of unrelated ones of the same name (including
+    // closures and imports)
+    let item = syn::parse_str("mod m {
+            #[overflow(inherit)]
+            fn scale(x: u8) -> u8 { x * 2 }
+            mod inner { fn f(x: u8) -> (u8, u8) { (super::scale(x), other::scale(x)) } }
+            fn g(o: Other, x: u8) -> (u8, u8, u8) { (scale(x), self::scale(x), o.scale(x)) }
+            mod imported { use other::scale; fn i(x: u8) -> u8 { scale(x) } }
+            fn j(x: u8) -> (u8, u8) {
+                let y = scale(x);
+                let scale = |x: u8| x;
+                (scale(y), { use self::scale as s; s(x) })
+            }
+            impl S {
+                #[overflow(inherit)]
+                fn scale(&self, x: u8) -> u8 { x }
+                fn h(&self, o: &Other) -> (u8, u8, u8) {
+                    (self.scale(1), Self::scale(self, 1), o.scale(1))
+                }
+            }
+        }").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Item>("#[doc(alias = \"overflow_saturate\")]
+        mod m {
+            #[overflow(inherit)]
+            fn scale(x: u8) -> u8 { x * 2 }
+            #[doc(alias = \"overflow_saturate\")]
+            mod inner {
+                #[doc(alias = \"overflow_saturate\")]
+                fn f(x: u8) -> (u8, u8) {
+                    (super::scale::<::overflower::Saturate>(x), other::scale(x))
+                }
+            }
+            #[doc(alias = \"overflow_saturate\")]
+            fn g(o: Other, x: u8) -> (u8, u8, u8) {
+                (scale::<::overflower::Saturate>(x), self::scale::<::overflower::Saturate>(x),
+                    o.scale(x))
+            }
+            #[doc(alias = \"overflow_saturate\")]
+            mod imported {
+                use other::scale;
+                #[doc(alias = \"overflow_saturate\")]
+                fn i(x: u8) -> u8 { scale(x) }
+            }
+            #[doc(alias = \"overflow_saturate\")]
+            fn j(x: u8) -> (u8, u8) {
+                let y = scale::<::overflower::Saturate>(x);
+                let scale = |x: u8| x;
+                (scale(y), { use self::scale as s; s::<::overflower::Saturate>(x) })
+            }
+            impl S {
+                #[overflow(inherit)]
+                fn scale(&self, x: u8) -> u8 { x }
+                #[doc(alias = \"overflow_saturate\")]
+                fn h(&self, o: &Other) -> (u8, u8, u8) {
+                    (self.scale::<::overflower::Saturate>(1),
+                        Self::scale::<::overflower::Saturate>(self, 1), o.scale(1))
+                }
+            }
+        }").unwrap()),
+        tokens(overflower(Policy::Saturate).rewrite_item(&item)));
+}
+
+#[test]
+fn test_rewrite_pow() {
+    let expr = syn::parse_str("(x.pow(2), u8::pow(x, n), pow(x, n), x.pow::<u8>(2))").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Expr>("(
+            ::overflower::dispatch::Dispatch::<_, ::overflower::Panic>::new(x).pow_base().pow(2),
+            ::overflower::PowPanic::pow_panic(x, n),
+            pow(x, n),
+            x.pow::<u8>(2)
+        )").unwrap()),
+        tokens(overflower(Policy::Panic).rewrite_expr(&expr)));
+}
+
+#[test]
+fn test_rewrite_block() {
+    let block = syn::parse_str("{ x -= 1; -x }").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Block>("{
+            {
+                let __overflower_rhs = 1;
+                <OverflowPolicy as ::overflower::Policy>::sub_assign(&mut x, __overflower_rhs)
+            };
+            <OverflowPolicy as ::overflower::Policy>::neg(x)
+        }").unwrap()),
+        tokens(overflower(Policy::Inherit).rewrite_block(&block)));
+}
+
+#[test]
+fn test_rewrite_div_by_zero() {
+    let expr = syn::parse_str("(a / b + 1, a % b)").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Expr>("(
+            ::overflower::AddWrap::add_wrap(
+                ::overflower::DivWith::<::overflower::Wrap, ::overflower::Saturate, _>
+                    ::div_with(a, b),
+                1),
+            ::overflower::RemWith::<::overflower::Wrap, ::overflower::Saturate, _>::rem_with(a, b)
+        )").unwrap()),
+        tokens(overflower(Policy::Wrap).div_by_zero(Policy::Saturate).rewrite_expr(&expr)));
+    assert_eq!(tokens(&expr),
+        tokens(overflower(Policy::Default).div_by_zero(Policy::Saturate).rewrite_expr(&expr)));
+}
+
+#[test]
+fn test_rewrite_shifts() {
+    let block = syn::parse_str("{ x <<= 9; (x >> n, x / 2) }").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Block>("{
+            {
+                let __overflower_rhs = 9;
+                ::overflower::ShlAssignWith::<::overflower::Panic, ::overflower::Rotate, _>
+                    ::shl_assign_with(&mut x, __overflower_rhs)
+            };
+            (
+                ::overflower::ShrWith::<::overflower::Panic, ::overflower::Rotate, _>::shr_with(x, n),
+                ::overflower::DivPanic::div_panic(x, 2)
+            )
+        }").unwrap()),
+        tokens(overflower(Policy::Panic).shifts(Shifts::Rotate).rewrite_block(&block)));
+}
+
+#[test]
+fn test_rewrite_dispatch() {
+    let block = syn::parse_str("{ x /= 2; (-x << n, v.iter().sum::<f64>(), x.pow(2)) }").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Block>("{
+            {
+                let __overflower_rhs = 2;
+                ::overflower::dispatch::Dispatch::<_, ::overflower::dispatch::With<
+                    ::overflower::Wrap, ::overflower::Panic>>::new((&mut x, __overflower_rhs))
+                    .div_assign()
+            };
+            (
+                ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new((
+                    ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new(x).neg(),
+                    n)).shl(),
+                ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new(v.iter()).sum::<f64>(),
+                ::overflower::dispatch::Dispatch::<_, ::overflower::Wrap>::new(x).pow_base().pow(2)
+            )
+        }").unwrap()),
+        tokens(overflower(Policy::Wrap).div_by_zero(Policy::Panic).dispatch().rewrite_block(&block)));
+
+    let item = syn::parse_str("fn f<T: Sum<T>>(v: Vec<T>) -> T { v.into_iter().sum() }")
+        .unwrap();
+    // sums of type parameters keep the policy traits their bounds require
+    assert_eq!(tokens(syn::parse_str::<syn::Item>("#[doc(alias = \"overflow_inherit\")]
+        fn f<OverflowPolicy: ::overflower::Policy,
+            T: Sum<T> + ::overflower::SumWrap<T> + ::overflower::SumPanic<T>
+                + ::overflower::SumSaturate<T>>(v: Vec<T>) -> T {
+            <OverflowPolicy as ::overflower::Policy>::sum(v.into_iter())
+        }").unwrap()),
+        tokens(overflower(Policy::Inherit).dispatch().rewrite_item(&item)));
+}
+
+#[test]
+fn test_rewrite_finite() {
+    // there are no `*Finite` traits for integer-only operations
+    let expr = syn::parse_str("(-x * 2.0, n << 1, m.pow(2))").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Expr>("(
+            ::overflower::MulFinite::mul_finite(::overflower::NegFinite::neg_finite(x), 2.0),
+            ::overflower::ShlPanic::shl_panic(n, 1),
+            ::overflower::dispatch::Dispatch::<_, ::overflower::Panic>::new(m).pow_base().pow(2)
+        )").unwrap()),
+        tokens(overflower(Policy::Finite).rewrite_expr(&expr)));
+    assert_eq!(tokens(syn::parse_str::<syn::Expr>("(
+            ::overflower::dispatch::Dispatch::<_, ::overflower::Finite>::new((
+                ::overflower::dispatch::Dispatch::<_, ::overflower::Finite>::new(x).neg(),
+                2.0)).mul(),
+            ::overflower::dispatch::Dispatch::<_, ::overflower::Panic>::new((n, 1)).shl(),
+            ::overflower::dispatch::Dispatch::<_, ::overflower::Panic>::new(m).pow_base().pow(2)
+        )").unwrap()),
+        tokens(overflower(Policy::Finite).dispatch().rewrite_expr(&expr)));
+}
+
+#[test]
+fn test_rewrite_core_only() {
+    // `no_std` crates have no `std` to refer to
+    let item = syn::parse_str("fn f<T: Add<Output = T>>(a: T, b: T, x: i32, v: &[i32]) -> T {
+            let mut y = -x.pow(2) * x / 3 % 4 << 5 >> 6;
+            y += 1; y -= 1; y *= 2; y /= 2; y %= 2; y <<= 1; y >>= 1;
+            let _ = (i32::abs(y), v.iter().sum::<i32>(), v.iter().product::<i32>());
+            a + b
+        }").unwrap();
+    for attr in &["wrap", "panic", "saturate", "finite", "inherit",
+            "wrap, div_by_zero = panic, shifts = rotate"] {
+        let overflower = syn::parse_str::<Overflower>(attr).unwrap();
+        for overflower in [overflower.clone(), overflower.dispatch()] {
+            let rewritten = tokens(overflower.rewrite_item(&item));
+            assert!(!rewritten.contains("std"), "{}: {}", attr, rewritten);
+        }
+    }
+}
+
+#[test]
+fn test_parse_attribute_arguments() {
+    assert!(syn::parse_str::<Overflower>("saturate").is_ok());
+    assert!(syn::parse_str::<Overflower>("panic, crate = my::overflower").is_ok());
+    assert!(syn::parse_str::<Overflower>("explode").is_err());
+    assert!(syn::parse_str::<Overflower>("wrap, div_by_zero = saturate, crate = o").is_ok());
+    assert!(syn::parse_str::<Overflower>("default, div_by_zero = saturate").is_err());
+    assert!(syn::parse_str::<Overflower>("wrap, div_by_zero = inherit").is_err());
+    assert!(syn::parse_str::<Overflower>("wrap, zero = panic").is_err());
+    assert!(syn::parse_str::<Overflower>("panic, shifts = zero_fill, div_by_zero = wrap").is_ok());
+    assert!(syn::parse_str::<Overflower>("inherit, shifts = mask").is_err());
+    assert!(syn::parse_str::<Overflower>("wrap, shifts = saturate").is_err());
+    assert!(syn::parse_str::<Overflower>("finite").is_ok());
+    assert_eq!(Policy::Inherit, syn::parse_str::<Policy>("inherit").unwrap());
+}
+
+#[test]
+fn test_attribute_spellings() {
+    let attrs = |code: &str| syn::parse_str::<syn::ItemFn>(code).unwrap().attrs;
+    assert_eq!(Some(Policy::Wrap), attr_policy(&attrs("#[overflow(wrap)] fn f() {}")));
+    assert_eq!(Some(Policy::Default),
+        attr_policy(&attrs("#[overflower::overflow(default)] fn f() {}")));
+    assert_eq!(Some(Policy::Panic),
+        attr_policy(&attrs("#[cfg_attr(feature = \"overflower\", inline, overflow(panic))] fn f() {}")));
+    assert_eq!(None, attr_policy(&attrs("#[cfg_attr(test, inline)] #[other::overflow(wrap)] fn f() {}")));
+}
+
+#[test]
+fn test_nested_attribute_spellings() {
+    let block = syn::parse_str("{
+        let a = #[overflower::overflow(default)] (x + 1);
+        let b = #[cfg_attr(feature = \"overflower\", overflow(wrap))] (x + 1);
+        x + 1
+    }").unwrap();
+    assert_eq!(tokens(syn::parse_str::<syn::Block>("{
+            let a = #[overflower::overflow(default)] (x + 1);
+            let b = #[cfg_attr(feature = \"overflower\", overflow(wrap))] (x + 1);
+            ::overflower::AddSaturate::add_saturate(x, 1)
+        }").unwrap()),
+        tokens(overflower(Policy::Saturate).rewrite_block(&block)));
+}
+
+#[test]
+fn test_crate_path_in() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("crate_path_in");
+    std::fs::create_dir_all(&dir).unwrap();
+    let crate_path = |manifest: &str| {
+        let path = dir.join("Cargo.toml");
+        std::fs::write(&path, manifest).unwrap();
+        tokens(overflower_rewrite::crate_path_in(&path))
+    };
+    assert_eq!("crate", crate_path("[package]\nname = \"overflower\"\n"));
+    assert_eq!(":: of", crate_path("[package]\nname = \"user\"\n\
+        [dependencies]\nof = { package = \"overflower\", version = \"0.9\" }\n"));
+    assert_eq!(":: over_flower", crate_path("[package]\nname = \"user\"\n\
+        [target.'cfg(unix)'.dev-dependencies.over-flower]\npackage = \"overflower\"\n"));
+    assert_eq!(":: overflower", crate_path("[package]\nname = \"user\"\n"));
+    assert_eq!(":: overflower", tokens(overflower_rewrite::crate_path_in(&dir.join("missing"))));
+
+    // the attribute's own `crate = path` takes precedence
+    let parse = |args: &str| {
+        let krate = syn::parse_str("::of").unwrap();
+        let overflower = Overflower::parser(krate).parse_str(args).unwrap();
+        overflower.rewrite_expr(&syn::parse_str("-x").unwrap())
+    };
+    assert_eq!(":: of :: NegWrap :: neg_wrap (x)", tokens(parse("wrap")));
+    assert_eq!(":: ov :: NegWrap :: neg_wrap (x)", tokens(parse("wrap, crate = ::ov")));
+}
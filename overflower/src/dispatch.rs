@@ -0,0 +1,442 @@
+//! Dispatch of operations in annotated code without specialization
+//!
+//! Without the `specialization` feature, only the types the policy traits are
+//! implemented for (the integer and float types and those `impls!` was used
+//! on) can be passed to them. To have other types (e.g. `String`) use their
+//! `core::ops` traits instead, `#[overflow(..)]` rewrites operations into method calls on
+//! a `Dispatch` of the operands, e.g. `a + b` into
+//! `Dispatch::<_, Wrap>::new((a, b)).add()`. The methods of `Dispatch` apply
+//! the policy traits, and if the operands don't implement them, method
+//! resolution goes on to the methods of `Fallback` (which `Dispatch`
+//! dereferences to by way of `NoFallback`), which apply the `core::ops`
+//! traits. This is the same as the blanket impls of the `specialization`
+//! feature do, as long as the types are known where the operation is written
+//! (e.g. not within generic code without bounds on the policy traits).
+//!
+//! Numbers (see `Number`) never fall back, as that would silently drop the
+//! policy: if the policy traits aren't implemented for an operation on them,
+//! the methods of `NoFallback` are found first and fail to compile.
+
+// The methods are named after the operations, but are no impls of them.
+#![allow(clippy::should_implement_trait)]
+
+use super::*;
+use core::convert::Infallible;
+use core::iter::{Product, Sum};
+use core::marker::PhantomData;
+
+/// The operands of an operation under the policy `M`, whose methods apply
+/// the policy traits
+///
+/// The operands are a tuple of the left- and right-hand side for binary
+/// operations (with a mutable reference to the left-hand side for
+/// assignments), the operand of negations or the iterator to sum or
+/// multiply. Besides `Wrap`, `Panic`, `Saturate` and `Finite`, `M` can be
+/// `Inherit<P>` to apply `Policy` `P` or `With<P, X>` for the `DivWith` and
+/// `ShlWith` families of traits.
+pub struct Dispatch<T, M>(NoFallback<T>, PhantomData<M>);
+
+/// The operands of an operation, whose methods fail to compile for numbers
+/// (see the module docs)
+pub struct NoFallback<T>(Fallback<T>);
+
+/// The operands of an operation, whose methods apply the `core::ops` traits
+/// (or `core::iter::Sum` and `Product`)
+pub struct Fallback<T>(Option<T>);
+
+/// Apply the policy `P` of a policy-generic function (see `Dispatch`)
+pub struct Inherit<P>(PhantomData<P>);
+
+/// Combine the policy `P` with the zero divisor handling of the policy `X`
+/// or the out-of-range shift handling `X` (see `Dispatch`)
+pub struct With<P, X>(PhantomData<(P, X)>);
+
+/// The integer and float types and `Duration`, and references to them, whose
+/// iterators the `Sum*` and `Product*` traits handle, and whose operations
+/// never fall back to the `core::ops` traits
+pub trait Number {}
+
+/// Not implemented for any type, to reject operations on numbers that the
+/// policy traits don't cover (see `NoFallback`)
+#[diagnostic::on_unimplemented(
+    message = "the overflow policy traits are not implemented for `{Self}`",
+    note = "operations on numbers don't fall back to `core::ops` in annotated code")]
+pub trait PolicyTraits {}
+
+impl<T, M> Dispatch<T, M> {
+    /// dispatch an operation on the given operands
+    #[inline]
+    pub fn new(operands: T) -> Self {
+        Dispatch(NoFallback(Fallback(Some(operands))), PhantomData)
+    }
+
+    #[inline]
+    fn into_inner(self) -> T {
+        ((self.0).0).0.expect("operands already taken")
+    }
+}
+
+impl<T> Fallback<T> {
+    #[inline]
+    fn take(&mut self) -> T {
+        self.0.take().expect("operands already taken")
+    }
+}
+
+impl<T, M> Deref for Dispatch<T, M> {
+    type Target = NoFallback<T>;
+
+    fn deref(&self) -> &NoFallback<T> {
+        &self.0
+    }
+}
+
+impl<T, M> DerefMut for Dispatch<T, M> {
+    fn deref_mut(&mut self) -> &mut NoFallback<T> {
+        &mut self.0
+    }
+}
+
+impl<T> Deref for NoFallback<T> {
+    type Target = Fallback<T>;
+
+    fn deref(&self) -> &Fallback<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NoFallback<T> {
+    fn deref_mut(&mut self) -> &mut Fallback<T> {
+        &mut self.0
+    }
+}
+
+macro_rules! dispatch_bin {
+    ($($fn_name:ident: $trait_name:ident, $tw:ident::$fw:ident, $tp:ident::$fp:ident,
+            $ts:ident::$fs:ident $(, $tf:ident::$ff:ident)?;)*) => {
+        $(
+            dispatch_bin!(policy $fn_name, Wrap, $tw::$fw);
+            dispatch_bin!(policy $fn_name, Panic, $tp::$fp);
+            dispatch_bin!(policy $fn_name, Saturate, $ts::$fs);
+            $(dispatch_bin!(policy $fn_name, Finite, $tf::$ff);)?
+
+            impl<P: Policy, L, R> Dispatch<(L, R), Inherit<P>>
+            where L: $tw<R> + $tp<R, Output = <L as $tw<R>>::Output>
+                     + $ts<R, Output = <L as $tw<R>>::Output> {
+                #[doc = concat!("apply `", stringify!($fn_name), "` under the policy `P`")]
+                #[inline]
+                pub fn $fn_name(self) -> <L as $tw<R>>::Output {
+                    let (l, r) = self.into_inner();
+                    P::$fn_name(l, r)
+                }
+            }
+
+            impl<L: Number, R: Number> NoFallback<(L, R)> {
+                #[doc = concat!("reject `", stringify!($fn_name), "` without the policy traits")]
+                pub fn $fn_name(&mut self) -> Infallible where (L, R): PolicyTraits {
+                    unreachable!()
+                }
+            }
+
+            impl<L: $trait_name<R>, R> Fallback<(L, R)> {
+                #[doc = concat!("apply `", stringify!($trait_name), "`")]
+                #[inline]
+                pub fn $fn_name(&mut self) -> L::Output {
+                    let (l, r) = self.take();
+                    $trait_name::$fn_name(l, r)
+                }
+            }
+        )*
+    };
+    (policy $fn_name:ident, $policy:ident, $t:ident::$f:ident) => {
+        impl<L: $t<R>, R> Dispatch<(L, R), $policy> {
+            #[doc = concat!("apply `", stringify!($t), "`")]
+            #[inline]
+            pub fn $fn_name(self) -> L::Output {
+                let (l, r) = self.into_inner();
+                $t::$f(l, r)
+            }
+        }
+    };
+}
+
+dispatch_bin! {
+    add: Add, AddWrap::add_wrap, AddPanic::add_panic, AddSaturate::add_saturate,
+        AddFinite::add_finite;
+    sub: Sub, SubWrap::sub_wrap, SubPanic::sub_panic, SubSaturate::sub_saturate,
+        SubFinite::sub_finite;
+    mul: Mul, MulWrap::mul_wrap, MulPanic::mul_panic, MulSaturate::mul_saturate,
+        MulFinite::mul_finite;
+    div: Div, DivWrap::div_wrap, DivPanic::div_panic, DivSaturate::div_saturate,
+        DivFinite::div_finite;
+    rem: Rem, RemWrap::rem_wrap, RemPanic::rem_panic, RemSaturate::rem_saturate,
+        RemFinite::rem_finite;
+    shl: Shl, ShlWrap::shl_wrap, ShlPanic::shl_panic, ShlSaturate::shl_saturate;
+    shr: Shr, ShrWrap::shr_wrap, ShrPanic::shr_panic, ShrSaturate::shr_saturate;
+}
+
+macro_rules! dispatch_assign {
+    ($($fn_name:ident: $trait_name:ident, $tw:ident::$fw:ident, $tp:ident::$fp:ident,
+            $ts:ident::$fs:ident $(, $tf:ident::$ff:ident)?;)*) => {
+        $(
+            dispatch_assign!(policy $fn_name, Wrap, $tw::$fw);
+            dispatch_assign!(policy $fn_name, Panic, $tp::$fp);
+            dispatch_assign!(policy $fn_name, Saturate, $ts::$fs);
+            $(dispatch_assign!(policy $fn_name, Finite, $tf::$ff);)?
+
+            impl<'a, P: Policy, L, R> Dispatch<(&'a mut L, R), Inherit<P>>
+            where L: $tw<R> + $tp<R> + $ts<R> {
+                #[doc = concat!("apply `", stringify!($fn_name), "` under the policy `P`")]
+                #[inline]
+                pub fn $fn_name(self) {
+                    let (l, r) = self.into_inner();
+                    P::$fn_name(l, r)
+                }
+            }
+
+            impl<'a, L: Number, R: Number> NoFallback<(&'a mut L, R)> {
+                #[doc = concat!("reject `", stringify!($fn_name), "` without the policy traits")]
+                pub fn $fn_name(&mut self) where (L, R): PolicyTraits {
+                    unreachable!()
+                }
+            }
+
+            impl<'a, L: $trait_name<R>, R> Fallback<(&'a mut L, R)> {
+                #[doc = concat!("apply `", stringify!($trait_name), "`")]
+                #[inline]
+                pub fn $fn_name(&mut self) {
+                    let (l, r) = self.take();
+                    $trait_name::$fn_name(l, r)
+                }
+            }
+        )*
+    };
+    (policy $fn_name:ident, $policy:ident, $t:ident::$f:ident) => {
+        impl<'a, L: $t<R>, R> Dispatch<(&'a mut L, R), $policy> {
+            #[doc = concat!("apply `", stringify!($t), "`")]
+            #[inline]
+            pub fn $fn_name(self) {
+                let (l, r) = self.into_inner();
+                $t::$f(l, r)
+            }
+        }
+    };
+}
+
+dispatch_assign! {
+    add_assign: AddAssign, AddAssignWrap::add_assign_wrap, AddAssignPanic::add_assign_panic,
+        AddAssignSaturate::add_assign_saturate, AddAssignFinite::add_assign_finite;
+    sub_assign: SubAssign, SubAssignWrap::sub_assign_wrap, SubAssignPanic::sub_assign_panic,
+        SubAssignSaturate::sub_assign_saturate, SubAssignFinite::sub_assign_finite;
+    mul_assign: MulAssign, MulAssignWrap::mul_assign_wrap, MulAssignPanic::mul_assign_panic,
+        MulAssignSaturate::mul_assign_saturate, MulAssignFinite::mul_assign_finite;
+    div_assign: DivAssign, DivAssignWrap::div_assign_wrap, DivAssignPanic::div_assign_panic,
+        DivAssignSaturate::div_assign_saturate, DivAssignFinite::div_assign_finite;
+    rem_assign: RemAssign, RemAssignWrap::rem_assign_wrap, RemAssignPanic::rem_assign_panic,
+        RemAssignSaturate::rem_assign_saturate, RemAssignFinite::rem_assign_finite;
+    shl_assign: ShlAssign, ShlAssignWrap::shl_assign_wrap, ShlAssignPanic::shl_assign_panic,
+        ShlAssignSaturate::shl_assign_saturate;
+    shr_assign: ShrAssign, ShrAssignWrap::shr_assign_wrap, ShrAssignPanic::shr_assign_panic,
+        ShrAssignSaturate::shr_assign_saturate;
+}
+
+macro_rules! dispatch_neg {
+    ($($policy:ident, $t:ident::$f:ident;)*) => {
+        $(
+            impl<T: $t> Dispatch<T, $policy> {
+                #[doc = concat!("apply `", stringify!($t), "`")]
+                #[inline]
+                pub fn neg(self) -> T::Output {
+                    $t::$f(self.into_inner())
+                }
+            }
+        )*
+    };
+}
+
+dispatch_neg! {
+    Wrap, NegWrap::neg_wrap;
+    Panic, NegPanic::neg_panic;
+    Saturate, NegSaturate::neg_saturate;
+    Finite, NegFinite::neg_finite;
+}
+
+impl<P: Policy, T> Dispatch<T, Inherit<P>>
+where T: NegWrap + NegPanic<Output = <T as NegWrap>::Output>
+         + NegSaturate<Output = <T as NegWrap>::Output> {
+    /// apply `neg` under the policy `P`
+    #[inline]
+    pub fn neg(self) -> <T as NegWrap>::Output {
+        P::neg(self.into_inner())
+    }
+}
+
+impl<T: Number> NoFallback<T> {
+    /// reject `neg` without the policy traits
+    pub fn neg(&mut self) -> Infallible where T: PolicyTraits {
+        unreachable!()
+    }
+}
+
+impl<T: Neg> Fallback<T> {
+    /// apply `Neg`
+    #[inline]
+    pub fn neg(&mut self) -> T::Output {
+        -self.take()
+    }
+}
+
+/// The base of a `pow` method call on a number, whose `pow` method applies
+/// the `Pow*` traits under the policy `M`
+///
+/// Only the integer types have `pow` methods in `core`, so other types need
+/// no fallback: `Fallback::pow_base` returns them as they are, to call their
+/// own `pow` methods on.
+pub struct PowBase<T, M>(T, PhantomData<M>);
+
+impl<T: Number, M> Dispatch<T, M> {
+    /// take the base of a `pow` call, to raise it to a power under `M`
+    #[inline]
+    pub fn pow_base(self) -> PowBase<T, M> {
+        PowBase(self.into_inner(), PhantomData)
+    }
+}
+
+impl<T> Fallback<T> {
+    /// take the base of a `pow` call as it is
+    #[inline]
+    pub fn pow_base(&mut self) -> T {
+        self.take()
+    }
+}
+
+macro_rules! dispatch_pow {
+    ($($policy:ident, $t:ident::$f:ident;)*) => {
+        $(
+            impl<T> PowBase<T, $policy> {
+                #[doc = concat!("apply `", stringify!($t), "`")]
+                #[inline]
+                pub fn pow<R>(self, exp: R) -> T::Output where T: $t<R> {
+                    $t::$f(self.0, exp)
+                }
+            }
+        )*
+    };
+}
+
+dispatch_pow! {
+    Wrap, PowWrap::pow_wrap;
+    Panic, PowPanic::pow_panic;
+    Saturate, PowSaturate::pow_saturate;
+}
+
+impl<P: Policy, T> PowBase<T, Inherit<P>> {
+    /// apply `pow` under the policy `P`
+    #[inline]
+    pub fn pow<R>(self, exp: R) -> <T as PowWrap<R>>::Output
+    where T: PowWrap<R> + PowPanic<R, Output = <T as PowWrap<R>>::Output>
+             + PowSaturate<R, Output = <T as PowWrap<R>>::Output> {
+        P::pow(self.0, exp)
+    }
+}
+
+// Whether the policy traits apply to a sum depends on its type, which often
+// is only inferred later, so this goes by the type of the elements instead.
+macro_rules! dispatch_iter {
+    ($($fn_name:ident: $trait_name:ident, $tw:ident::$fw:ident, $tp:ident::$fp:ident,
+            $ts:ident::$fs:ident, $tf:ident::$ff:ident;)*) => {
+        $(
+            dispatch_iter!(policy $fn_name, Wrap, $tw::$fw);
+            dispatch_iter!(policy $fn_name, Panic, $tp::$fp);
+            dispatch_iter!(policy $fn_name, Saturate, $ts::$fs);
+            dispatch_iter!(policy $fn_name, Finite, $tf::$ff);
+
+            impl<P: Policy, I: Iterator> Dispatch<I, Inherit<P>> where I::Item: Number {
+                #[doc = concat!("compute the ", stringify!($fn_name), " under the policy `P`")]
+                #[inline]
+                pub fn $fn_name<S>(self) -> S
+                where S: $tw<I::Item> + $tp<I::Item> + $ts<I::Item> {
+                    P::$fn_name(self.into_inner())
+                }
+            }
+
+            impl<I: Iterator> Fallback<I> {
+                #[doc = concat!("apply `", stringify!($trait_name), "`")]
+                #[inline]
+                pub fn $fn_name<S: $trait_name<I::Item>>(&mut self) -> S {
+                    $trait_name::$fn_name(self.take())
+                }
+            }
+        )*
+    };
+    (policy $fn_name:ident, $policy:ident, $t:ident::$f:ident) => {
+        impl<I: Iterator> Dispatch<I, $policy> where I::Item: Number {
+            #[doc = concat!("apply `", stringify!($t), "`")]
+            #[inline]
+            pub fn $fn_name<S: $t<I::Item>>(self) -> S {
+                $t::$f(self.into_inner())
+            }
+        }
+    };
+}
+
+dispatch_iter! {
+    sum: Sum, SumWrap::sum_wrap, SumPanic::sum_panic, SumSaturate::sum_saturate,
+        SumFinite::sum_finite;
+    product: Product, ProductWrap::product_wrap, ProductPanic::product_panic,
+        ProductSaturate::product_saturate, ProductFinite::product_finite;
+}
+
+macro_rules! dispatch_with {
+    ($($fn_name:ident: $t:ident::$f:ident;)*) => {
+        $(
+            impl<P, X, L: $t<P, X, R>, R> Dispatch<(L, R), With<P, X>> {
+                #[doc = concat!("apply `", stringify!($t), "`")]
+                #[inline]
+                pub fn $fn_name(self) -> L::Output {
+                    let (l, r) = self.into_inner();
+                    $t::$f(l, r)
+                }
+            }
+        )*
+    };
+    (assign $($fn_name:ident: $t:ident::$f:ident;)*) => {
+        $(
+            impl<'a, P, X, L: $t<P, X, R>, R> Dispatch<(&'a mut L, R), With<P, X>> {
+                #[doc = concat!("apply `", stringify!($t), "`")]
+                #[inline]
+                pub fn $fn_name(self) {
+                    let (l, r) = self.into_inner();
+                    $t::$f(l, r)
+                }
+            }
+        )*
+    };
+}
+
+dispatch_with! {
+    div: DivWith::div_with;
+    rem: RemWith::rem_with;
+    shl: ShlWith::shl_with;
+    shr: ShrWith::shr_with;
+}
+
+dispatch_with! {
+    assign
+    div_assign: DivAssignWith::div_assign_with;
+    rem_assign: RemAssignWith::rem_assign_with;
+    shl_assign: ShlAssignWith::shl_assign_with;
+    shr_assign: ShrAssignWith::shr_assign_with;
+}
+
+macro_rules! number {
+    ($($ty:ty),*) => {
+        $(
+            impl Number for $ty {}
+            impl<'a> Number for &'a $ty {}
+        )*
+    };
+}
+
+number!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64,
+    core::time::Duration);
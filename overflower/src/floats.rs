@@ -0,0 +1,544 @@
+//! Impls for `f32` and `f64`, and the `*Finite` traits
+//!
+//! Floats don't overflow, they become infinite instead. The `*Wrap`, `*Panic`
+//! and `*Saturate` traits (and the `DivWith` family) are plain IEEE
+//! operations for them, as with the blanket impls of the `specialization`
+//! feature. The `*Finite` traits (used by `#[overflow(finite)]`) panic if a
+//! float operation gives infinity or NaN from finite operands, and on integer
+//! overflow like the `*Panic` traits.
+
+use super::*;
+
+/// Panic on integer overflow and on float operations that give infinity or
+/// NaN from finite operands, as a type-level marker (see `dispatch::Dispatch`)
+///
+/// This is no `Policy`, as there are no `*Finite` traits for shifts, `abs`
+/// and `pow`, which only integers have.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Finite;
+
+/// Add two values, panicking on integer overflow or if finite floats add up
+/// to infinity
+///
+/// This does the same as `AddPanic` for integers and the `std::ops::Add`
+/// trait for most other types.
+pub trait AddFinite<RHS = Self> {
+    /// The output type of the addition
+    type Output;
+    /// add two values, panicking on overflow or a non-finite result
+    fn add_finite(self, rhs: RHS) -> Self::Output;
+}
+
+/// Subtract two values, panicking on integer overflow or if the difference of
+/// finite floats is infinite
+///
+/// This does the same as `SubPanic` for integers and the `std::ops::Sub`
+/// trait for most other types.
+pub trait SubFinite<RHS = Self> {
+    /// The output type of the subtraction
+    type Output;
+    /// subtract two values, panicking on overflow or a non-finite result
+    fn sub_finite(self, rhs: RHS) -> Self::Output;
+}
+
+/// Multiply two values, panicking on integer overflow or if the product of
+/// finite floats is infinite
+///
+/// This does the same as `MulPanic` for integers and the `std::ops::Mul`
+/// trait for most other types.
+pub trait MulFinite<RHS = Self> {
+    /// The output type of the multiplication
+    type Output;
+    /// multiply two values, panicking on overflow or a non-finite result
+    fn mul_finite(self, rhs: RHS) -> Self::Output;
+}
+
+/// Divide two values, panicking on integer overflow or if the quotient of
+/// finite floats is infinite or NaN (e.g. when dividing by zero)
+///
+/// This does the same as `DivPanic` for integers and the `std::ops::Div`
+/// trait for most other types.
+pub trait DivFinite<RHS = Self> {
+    /// The output type of the division
+    type Output;
+    /// divide two values, panicking on overflow or a non-finite result
+    fn div_finite(self, rhs: RHS) -> Self::Output;
+}
+
+/// Divide two values and get the remainder, panicking on integer overflow or
+/// if the remainder of finite floats is NaN (i.e. when dividing by zero)
+///
+/// This does the same as `RemPanic` for integers and the `std::ops::Rem`
+/// trait for most other types.
+pub trait RemFinite<RHS = Self> {
+    /// The output type of the division remainder
+    type Output;
+    /// divide two values and get the remainder, panicking on overflow or a
+    /// non-finite result
+    fn rem_finite(self, rhs: RHS) -> Self::Output;
+}
+
+/// Negate a value, panicking on integer overflow
+///
+/// This does the same as `NegPanic` for integers and the `std::ops::Neg`
+/// trait for most other types. Negated floats are finite if they were before.
+pub trait NegFinite {
+    /// The output type of the negation
+    type Output;
+    /// negate a value, panicking on overflow
+    fn neg_finite(self) -> Self::Output;
+}
+
+/// Add a value to a given value in-place, panicking like `AddFinite`
+///
+/// This does the same as `AddAssignPanic` for integers and the
+/// `std::ops::AddAssign` trait for most other types.
+pub trait AddAssignFinite<RHS = Self> {
+    /// add a value to a given value in-place, panicking on overflow or a
+    /// non-finite result
+    fn add_assign_finite(&mut self, rhs: RHS);
+}
+
+/// Subtract a value from a given value in-place, panicking like `SubFinite`
+///
+/// This does the same as `SubAssignPanic` for integers and the
+/// `std::ops::SubAssign` trait for most other types.
+pub trait SubAssignFinite<RHS = Self> {
+    /// subtract a value from a given value in-place, panicking on overflow or
+    /// a non-finite result
+    fn sub_assign_finite(&mut self, rhs: RHS);
+}
+
+/// Multiply a given value by a value in-place, panicking like `MulFinite`
+///
+/// This does the same as `MulAssignPanic` for integers and the
+/// `std::ops::MulAssign` trait for most other types.
+pub trait MulAssignFinite<RHS = Self> {
+    /// multiply a given value by a value in-place, panicking on overflow or a
+    /// non-finite result
+    fn mul_assign_finite(&mut self, rhs: RHS);
+}
+
+/// Divide a given value by a value in-place, panicking like `DivFinite`
+///
+/// This does the same as `DivAssignPanic` for integers and the
+/// `std::ops::DivAssign` trait for most other types.
+pub trait DivAssignFinite<RHS = Self> {
+    /// divide a given value by a value in-place, panicking on overflow or a
+    /// non-finite result
+    fn div_assign_finite(&mut self, rhs: RHS);
+}
+
+/// Replace a given value by the remainder of dividing it by a value in-place,
+/// panicking like `RemFinite`
+///
+/// This does the same as `RemAssignPanic` for integers and the
+/// `std::ops::RemAssign` trait for most other types.
+pub trait RemAssignFinite<RHS = Self> {
+    /// replace a given value by the division remainder in-place, panicking on
+    /// overflow or a non-finite result
+    fn rem_assign_finite(&mut self, rhs: RHS);
+}
+
+/// Sum up an iterator, panicking on integer overflow or if finite floats add
+/// up to infinity
+///
+/// This does the same as `SumPanic` for integers and the `std::iter::Sum`
+/// trait for most other types.
+pub trait SumFinite<A = Self>: Sized {
+    /// sum up the elements of an iterator, panicking on overflow or a
+    /// non-finite result
+    fn sum_finite<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Multiply the elements of an iterator, panicking on integer overflow or if
+/// the product of finite floats is infinite
+///
+/// This does the same as `ProductPanic` for integers and the
+/// `std::iter::Product` trait for most other types.
+pub trait ProductFinite<A = Self>: Sized {
+    /// multiply the elements of an iterator, panicking on overflow or a
+    /// non-finite result
+    fn product_finite<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+macro_rules! finite_blankets {
+    ($(bin $trait_name:ident, $fn_name:ident, $t:ident::$f:ident;)*
+            $(assign $assign_name:ident, $assign_fn:ident, $ta:ident::$fa:ident;)*
+            $(iter $iter_name:ident, $iter_fn:ident, $ti:ident::$fi:ident;)*) => {
+        $(
+            #[cfg(feature = "specialization")]
+            impl<T, R> $t<R> for T where T: $trait_name<R> {
+                type Output = <T as $trait_name<R>>::Output;
+                default fn $f(self, rhs: R) -> Self::Output {
+                    $trait_name::$fn_name(self, rhs)
+                }
+            }
+        )*
+        $(
+            #[cfg(feature = "specialization")]
+            impl<T, R> $ta<R> for T where T: $assign_name<R> {
+                default fn $fa(&mut self, rhs: R) {
+                    $assign_name::$assign_fn(self, rhs)
+                }
+            }
+        )*
+        $(
+            #[cfg(feature = "specialization")]
+            impl<T, A> $ti<A> for T where T: core::iter::$iter_name<A> {
+                default fn $fi<I: Iterator<Item = A>>(iter: I) -> Self {
+                    core::iter::$iter_name::$iter_fn(iter)
+                }
+            }
+        )*
+    };
+}
+
+finite_blankets! {
+    bin Add, add, AddFinite::add_finite;
+    bin Sub, sub, SubFinite::sub_finite;
+    bin Mul, mul, MulFinite::mul_finite;
+    bin Div, div, DivFinite::div_finite;
+    bin Rem, rem, RemFinite::rem_finite;
+    assign AddAssign, add_assign, AddAssignFinite::add_assign_finite;
+    assign SubAssign, sub_assign, SubAssignFinite::sub_assign_finite;
+    assign MulAssign, mul_assign, MulAssignFinite::mul_assign_finite;
+    assign DivAssign, div_assign, DivAssignFinite::div_assign_finite;
+    assign RemAssign, rem_assign, RemAssignFinite::rem_assign_finite;
+    iter Sum, sum, SumFinite::sum_finite;
+    iter Product, product, ProductFinite::product_finite;
+}
+
+#[cfg(feature = "specialization")]
+impl<T> NegFinite for T where T: Neg {
+    type Output = <T as Neg>::Output;
+    default fn neg_finite(self) -> Self::Output {
+        -self
+    }
+}
+
+macro_rules! finite_int {
+    ($ty:ty) => {
+        finite_int!(bin $ty, AddFinite, add_finite, AddPanic::add_panic);
+        finite_int!(bin $ty, SubFinite, sub_finite, SubPanic::sub_panic);
+        finite_int!(bin $ty, MulFinite, mul_finite, MulPanic::mul_panic);
+        finite_int!(bin $ty, DivFinite, div_finite, DivPanic::div_panic);
+        finite_int!(bin $ty, RemFinite, rem_finite, RemPanic::rem_panic);
+        finite_int!(assign $ty, AddAssignFinite, add_assign_finite,
+            AddAssignPanic::add_assign_panic);
+        finite_int!(assign $ty, SubAssignFinite, sub_assign_finite,
+            SubAssignPanic::sub_assign_panic);
+        finite_int!(assign $ty, MulAssignFinite, mul_assign_finite,
+            MulAssignPanic::mul_assign_panic);
+        finite_int!(assign $ty, DivAssignFinite, div_assign_finite,
+            DivAssignPanic::div_assign_panic);
+        finite_int!(assign $ty, RemAssignFinite, rem_assign_finite,
+            RemAssignPanic::rem_assign_panic);
+        finite_int!(iter $ty, SumFinite, sum_finite, SumPanic::sum_panic);
+        finite_int!(iter $ty, ProductFinite, product_finite, ProductPanic::product_panic);
+    };
+    (signed $ty:ty) => {
+        finite_int!($ty);
+
+        impl NegFinite for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_finite(self) -> $ty {
+                NegPanic::neg_panic(self)
+            }
+        }
+
+        impl NegFinite for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_finite(self) -> $ty {
+                NegPanic::neg_panic(self)
+            }
+        }
+    };
+    (bin $ty:ty, $trait_name:ident, $fn_name:ident, $t:ident::$f:ident) => {
+        impl $trait_name<$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: $ty) -> $ty {
+                $t::$f(self, rhs)
+            }
+        }
+
+        impl $trait_name<$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: $ty) -> $ty {
+                $t::$f(self, rhs)
+            }
+        }
+
+        impl $trait_name<&$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: &$ty) -> $ty {
+                $t::$f(self, rhs)
+            }
+        }
+
+        impl $trait_name<&$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: &$ty) -> $ty {
+                $t::$f(self, rhs)
+            }
+        }
+    };
+    (assign $ty:ty, $trait_name:ident, $fn_name:ident, $t:ident::$f:ident) => {
+        impl $trait_name<$ty> for $ty {
+            fn $fn_name(&mut self, rhs: $ty) {
+                $t::$f(self, rhs)
+            }
+        }
+    };
+    (iter $ty:ty, $trait_name:ident, $fn_name:ident, $t:ident::$f:ident) => {
+        impl $trait_name for $ty {
+            fn $fn_name<I: Iterator<Item = $ty>>(iter: I) -> Self {
+                $t::$f(iter)
+            }
+        }
+
+        impl<'a> $trait_name<&'a $ty> for $ty {
+            fn $fn_name<I: Iterator<Item = &'a $ty>>(iter: I) -> Self {
+                $t::$f(iter)
+            }
+        }
+    };
+}
+
+finite_int!(u8);
+finite_int!(u16);
+finite_int!(u32);
+finite_int!(u64);
+finite_int!(u128);
+finite_int!(usize);
+finite_int!(signed i8);
+finite_int!(signed i16);
+finite_int!(signed i32);
+finite_int!(signed i64);
+finite_int!(signed i128);
+finite_int!(signed isize);
+
+macro_rules! floats {
+    ($ty:ty) => {
+        impls!($ty; add sub mul div rem neg add_assign sub_assign mul_assign div_assign
+            rem_assign sum product);
+        impls!(&'a $ty : 'a; add sub mul div rem neg);
+
+        floats!(abs $ty, AbsWrap, abs_wrap);
+        floats!(abs $ty, AbsPanic, abs_panic);
+        floats!(abs $ty, AbsSaturate, abs_saturate);
+
+        floats!(with $ty, $ty);
+        floats!(with $ty, &$ty);
+
+        floats!(finite $ty, AddFinite, add_finite, +);
+        floats!(finite $ty, SubFinite, sub_finite, -);
+        floats!(finite $ty, MulFinite, mul_finite, *);
+        floats!(finite $ty, DivFinite, div_finite, /);
+        floats!(finite $ty, RemFinite, rem_finite, %);
+
+        impl NegFinite for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_finite(self) -> $ty {
+                -self
+            }
+        }
+
+        impl NegFinite for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_finite(self) -> $ty {
+                -*self
+            }
+        }
+
+        floats!(assign $ty, AddAssignFinite, add_assign_finite, AddFinite::add_finite);
+        floats!(assign $ty, SubAssignFinite, sub_assign_finite, SubFinite::sub_finite);
+        floats!(assign $ty, MulAssignFinite, mul_assign_finite, MulFinite::mul_finite);
+        floats!(assign $ty, DivAssignFinite, div_assign_finite, DivFinite::div_finite);
+        floats!(assign $ty, RemAssignFinite, rem_assign_finite, RemFinite::rem_finite);
+
+        // like `std`, an empty sum is -0.0 (so summing up -0.0 gives -0.0)
+        floats!(iter $ty, SumFinite, sum_finite, AddFinite::add_finite, -0.0);
+        floats!(iter $ty, ProductFinite, product_finite, MulFinite::mul_finite, 1.0);
+    };
+    (abs $ty:ty, $trait_name:ident, $fn_name:ident) => {
+        impl $trait_name for $ty {
+            fn $fn_name(self) -> $ty {
+                self.abs()
+            }
+        }
+    };
+    (with $ty:ty, $rty:ty) => {
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> DivWith<P, Z, $rty> for $ty {
+            type Output = $ty;
+
+            fn div_with(self, rhs: $rty) -> $ty {
+                self / rhs
+            }
+        }
+
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> RemWith<P, Z, $rty> for $ty {
+            type Output = $ty;
+
+            fn rem_with(self, rhs: $rty) -> $ty {
+                self % rhs
+            }
+        }
+
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> DivWith<P, Z, $rty> for &$ty {
+            type Output = $ty;
+
+            fn div_with(self, rhs: $rty) -> $ty {
+                self / rhs
+            }
+        }
+
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> RemWith<P, Z, $rty> for &$ty {
+            type Output = $ty;
+
+            fn rem_with(self, rhs: $rty) -> $ty {
+                self % rhs
+            }
+        }
+
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> DivAssignWith<P, Z, $rty> for $ty {
+            fn div_assign_with(&mut self, rhs: $rty) {
+                *self /= rhs;
+            }
+        }
+
+        #[cfg(not(feature = "specialization"))]
+        impl<P: Policy, Z: Policy> RemAssignWith<P, Z, $rty> for $ty {
+            fn rem_assign_with(&mut self, rhs: $rty) {
+                *self %= rhs;
+            }
+        }
+    };
+    (finite $ty:ty, $trait_name:ident, $fn_name:ident, $op:tt) => {
+        impl $trait_name<$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: $ty) -> $ty {
+                let x = self $op rhs;
+                if x.is_finite() || !self.is_finite() || !rhs.is_finite() { x }
+                else { panic!("non-finite float result") }
+            }
+        }
+
+        impl $trait_name<$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: $ty) -> $ty {
+                $trait_name::$fn_name(*self, rhs)
+            }
+        }
+
+        impl $trait_name<&$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: &$ty) -> $ty {
+                $trait_name::$fn_name(self, *rhs)
+            }
+        }
+
+        impl $trait_name<&$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn $fn_name(self, rhs: &$ty) -> $ty {
+                $trait_name::$fn_name(*self, *rhs)
+            }
+        }
+    };
+    (assign $ty:ty, $trait_name:ident, $fn_name:ident, $t:ident::$f:ident) => {
+        impl $trait_name<$ty> for $ty {
+            fn $fn_name(&mut self, rhs: $ty) {
+                *self = $t::$f(*self, rhs);
+            }
+        }
+
+        impl $trait_name<&$ty> for $ty {
+            fn $fn_name(&mut self, rhs: &$ty) {
+                *self = $t::$f(*self, *rhs);
+            }
+        }
+    };
+    (iter $ty:ty, $trait_name:ident, $fn_name:ident, $t:ident::$f:ident, $init:expr) => {
+        impl $trait_name for $ty {
+            fn $fn_name<I: Iterator<Item = $ty>>(iter: I) -> Self {
+                iter.fold($init, |acc: $ty, x| $t::$f(acc, x))
+            }
+        }
+
+        impl<'a> $trait_name<&'a $ty> for $ty {
+            fn $fn_name<I: Iterator<Item = &'a $ty>>(iter: I) -> Self {
+                iter.fold($init, |acc: $ty, x| $t::$f(acc, *x))
+            }
+        }
+    };
+}
+
+floats!(f32);
+floats!(f64);
+
+// `x * 2^n`, rounded once even if the result is subnormal (as `scalbn` in
+// musl does it)
+macro_rules! scale {
+    ($name:ident, $ty:ty, $bits:ty) => {
+        fn $name(x: $ty, n: u32, left: bool) -> $ty {
+            const MAX: i32 = <$ty>::MAX_EXP - 1;
+            const MIN: i32 = <$ty>::MIN_EXP - 1;
+            const DIGITS: i32 = <$ty>::MANTISSA_DIGITS as i32;
+            let pow2 = |k: i32| <$ty>::from_bits(((MAX + k) as $bits) << (DIGITS - 1));
+            let n = i32::try_from(n).unwrap_or(i32::MAX);
+            let (mut x, mut n) = (x, if left { n } else { -n });
+            if n > MAX {
+                x *= pow2(MAX);
+                n -= MAX;
+                if n > MAX {
+                    x *= pow2(MAX);
+                    n = (n - MAX).min(MAX);
+                }
+            } else if n < MIN {
+                // stay above the subnormal range until the last step
+                x *= pow2(MIN + DIGITS);
+                n -= MIN + DIGITS;
+                if n < MIN {
+                    x *= pow2(MIN + DIGITS);
+                    n = (n - MIN - DIGITS).max(MIN);
+                }
+            }
+            x * pow2(n)
+        }
+    };
+}
+
+scale!(scale_f32, f32, u32);
+scale!(scale_f64, f64, u64);
+
+arith!(f32, |x| P::neg(x), |x, n| scale_f32(x, n, true), |x, n| scale_f32(x, n, false));
+arith!(f64, |x| P::neg(x), |x, n| scale_f64(x, n, true), |x, n| scale_f64(x, n, false));
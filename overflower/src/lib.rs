@@ -6,22 +6,126 @@
 //! * AddWrap, SubWrap, Mulwrap, DivWrap, RemWrap, ShlWrap, ShrWrap, NegWrap
 //! * AddSaturate, SubSaturate, MulSaturate, DivSaturate, RemSaturate, ShlSaturate,
 //!   ShrSaturate, NegSaturate
+//! * SumPanic, SumWrap, SumSaturate, ProductPanic, ProductWrap, ProductSaturate
+//! * PowPanic, PowWrap, PowSaturate, PowChecked
+//! * AddOverflowing, SubOverflowing, MulOverflowing, ShlOverflowing, ShrOverflowing,
+//!   NegOverflowing
+//! * Arith, generic over the `Policy` markers Wrap, Panic and Saturate
+//! * AddFinite, SubFinite, MulFinite, DivFinite, RemFinite, NegFinite, SumFinite, ProductFinite
 //!
 //! The `*Panic` traits all panic on overflow, the `*Wrap` traits wrap around and the
-//! `*Saturate` traits saturate.
+//! `*Saturate` traits saturate. `PowChecked` returns `None` on overflow instead, and the
+//! `*Overflowing` traits (with their `*AssignOverflowing` counterparts) return the wrapped
+//! result along with whether it overflowed, leaving the decision to the caller.
 //!
-//! Note: You can use this with a stable Rust version, but if you have any annotated code
-//! containing an arithmetic operation over any custom type, you'll have to use the `impls!`
-//! macro to get the traits implemented for the type or use a nightly Rust and add the
-//! `specialization` feature.
+//! `f32` and `f64` implement the `*Wrap`, `*Panic` and `*Saturate` traits (and the `DivWith`
+//! family) as plain IEEE arithmetic, as floats become infinite instead of overflowing. The
+//! `*Finite` traits (used by `#[overflow(finite)]`) panic if a float operation gives infinity
+//! or NaN from finite operands, and on integer overflow like the `*Panic` traits.
+//!
+//! `core::time::Duration` implements the `Add*`, `Sub*`, `Mul*` and `Div*` traits (multiplied
+//! and divided by `u32`) and the `Sum*` traits, and with the `std` feature, `Instant` and
+//! `SystemTime` implement the `Add*` and `Sub*` traits with a `Duration`, so e.g. `deadline +
+//! timeout` in `#[overflow(saturate)]` code saturates at the latest representable point in time.
+//! They don't wrap around, so the `*Wrap` traits panic on overflow like `std` does.
+//!
+//! Division and remainder follow this table in every form (`Div*`, `Rem*`, their `*Assign`
+//! counterparts, `Policy`, `Arith` and the wrapper types, with `checked` meaning `Checked`):
+//!
+//! | policy   | `x / 0`                                    | `x % 0`    | `MIN / -1` | `MIN % -1` |
+//! |----------|--------------------------------------------|------------|------------|------------|
+//! | wrap     | panics                                     | panics     | `MIN`      | 0          |
+//! | panic    | panics                                     | panics     | panics     | 0          |
+//! | saturate | `MAX` if `x > 0`, `MIN` if `x < 0`, else 0 | `x`        | `MAX`      | 0          |
+//! | checked  | overflowed                                 | overflowed | overflowed | 0          |
+//!
+//! `MIN % -1` is 0 everywhere, as the result fits, even though `std` panics on it. A zero divisor
+//! isn't an overflow, so wrapping has no value for it. The `DivWith` family of traits (used by
+//! `#[overflow(.., div_by_zero = ..)]`) combines the zero divisor handling of one policy with
+//! another one for everything else.
+//!
+//! Shifts take the number of bits as any integer type. It is out of range if it is negative or not
+//! lower than the width of the type (e.g. `x << 8` for `u8`). Shifts follow this table in every
+//! form:
+//!
+//! | policy   | bits shifted out of `x << n`       | `n` out of range                                 |
+//! |----------|------------------------------------|--------------------------------------------------|
+//! | wrap     | dropped                            | shifts by `n` modulo the width                   |
+//! | panic    | panics                             | panics                                           |
+//! | saturate | `MAX` if `x > 0`, `MIN` if `x < 0` | shifts all bits out (so `<<` saturates unless 0) |
+//! | checked  | overflowed                         | overflowed                                       |
+//!
+//! Right shifts never shift bits out of the value that matter. The `ShlWith` family of traits
+//! (used by `#[overflow(.., shifts = ..)]`) combines one policy for bits shifted out with masking
+//! (`Mask`), shifting all bits out (`ZeroFill`) or rotating (`Rotate`) for out-of-range numbers
+//! of bits.
+//!
+//! Besides the integer types, the unsigned `NonZero*` types implement the `Add*` and `Mul*`
+//! traits (and unsigned integers can be divided by them), and the signed ones the `Neg*` and
+//! `Abs*` traits. As they can't be zero, wrapping goes around modulo `MAX` (so `MAX + 1` is 1),
+//! and panicking happens whenever the result would be zero or too large. Without `std` impls of
+//! `Add` and `Mul` to specialize, the `specialization` feature leaves those out.
+//!
+//! For values whose overflow behavior should not depend on the code they are used in (e.g.
+//! struct fields), there are the `Panicking<T>`, `Saturating<T>` and `Checked<T>` wrappers,
+//! which complement `core::num::Wrapping<T>`. Their operators apply their policy even within
+//! code annotated with a different one.
+//!
+//! The `#[overflow(..)]` attribute (with the `proc_macro` feature) rewrites the operations within
+//! a module or function to these traits. It takes a policy:
+//! * `wrap`, `panic` or `saturate` use the respective traits
+//! * `finite` uses the `*Finite` traits
+//! * `default` leaves the code alone, e.g. for an item within an annotated one
+//! * `inherit` makes functions and methods generic over an `OverflowPolicy: Policy` type
+//!   parameter. Calls to them within the same annotated item pass the caller's policy along if
+//!   they name the function by its path from the caller's module (`scale(x)`, `super::scale(x)`,
+//!   `Self::scale(..)`) or by a name brought in by `use`, unless a local binding of the same
+//!   name shadows it, or call the method on `self`. Trait impls are left alone.
+//!
+//! The policy can be followed by `, crate = path` to set the path of this crate in the
+//! generated code (taken from the caller's `Cargo.toml` by default, so renaming the dependency
+//! works). With `wrap`, `panic` or `saturate`, `, div_by_zero = wrap|panic|saturate` handles zero
+//! divisors as another policy does, and `, shifts = mask|zero_fill|rotate` handles out-of-range
+//! shift amounts as in the tables above, e.g. `#[overflow(panic, shifts = rotate)]` makes
+//! `1u8 << 9` rotate to 2, but `3u8 << 7` panics. Items and expressions within an annotated item
+//! can carry an attribute of their own.
+//!
+//! Besides the operators, argument-less `.sum()` and `.product()` calls (keeping a result type
+//! like `.sum::<u64>()`), `.pow(n)` and integer functions like `i32::abs(x)` and `u32::pow(x, n)`
+//! are rewritten, with `.pow(n)` on types other than numbers calling their own `pow` method. For
+//! every operator bound of an annotated generic item (e.g. `T: Add<Output = T>`), the respective
+//! policy trait bound is added alongside it. Documented items get a line like "Integer overflow
+//! in this function: saturates." appended to their docs, and a search alias like
+//! `overflow_saturate`.
+//!
+//! The crate is `no_std`. Its `alloc` feature adds the impls for `String` and `Cow<str>`, and
+//! its `std` feature (on by default) implements `std::error::Error` for `OverflowError` and
+//! adds the impls for `Instant` and `SystemTime`.
+//!
+//! Note: You can use this with a stable Rust version. Without the `specialization` feature,
+//! the attribute rewrites operations into method calls on a `dispatch::Dispatch` of their
+//! operands, so custom types in annotated code use their `std::ops` traits, as long as their
+//! types are known where the operation is written. Macro invocations then get their
+//! comma-separated arguments rewritten (as for `assert!` or `vec![..]`) instead of their whole
+//! expansion, which would need a nightly compiler. Numbers never fall back to `std::ops`, so
+//! the policy can't be dropped silently. Within generic code, the traits are only
+//! implemented for integers, floats and the types you use the `impls!` macro on,
+//! unless you use a nightly Rust and add the `specialization` feature.
 
 #![cfg_attr(feature = "specialization", feature(specialization))]
-#![cfg_attr(feature = "specialization", feature(specialization))]
+#![cfg_attr(feature = "specialization", allow(incomplete_features))]
 #![cfg_attr(feature = "wrapping_int_impl", feature(wrapping_int_impl))]
 #![deny(missing_docs, unsafe_code)]
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use std::ops::*;
-use std::cmp::*;
+use core::ops::*;
+use core::cmp::*;
+use core::convert::TryFrom;
 
 #[cfg(feature = "proc_macro")]
 pub use overflower_plugin::overflow;
@@ -87,7 +191,7 @@ macro_rules! panic_biself {
         impl<T, R> $trait_panic<R> for T where T: $trait_name<R> {
             type Output = <T as $trait_name<R>>::Output;
             default fn $fn_panic(self, rhs: R) -> Self::Output {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -95,11 +199,13 @@ macro_rules! panic_biself {
         panic_biself!($trait_panic, $fn_panic, $checked_fn, u16);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, u32);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, u64);
+        panic_biself!($trait_panic, $fn_panic, $checked_fn, u128);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, usize);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, i8);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, i16);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, i32);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, i64);
+        panic_biself!($trait_panic, $fn_panic, $checked_fn, i128);
         panic_biself!($trait_panic, $fn_panic, $checked_fn, isize);
     };
     ($trait_panic:ident, $fn_panic:ident, $checked_fn:ident, $ty:ty) => {
@@ -149,7 +255,7 @@ panic_biself!(Add, AddPanic, add, add_panic, checked_add);
 panic_biself!(Sub, SubPanic, sub, sub_panic, checked_sub);
 panic_biself!(Mul, MulPanic, mul, mul_panic, checked_mul);
 panic_biself!(Div, DivPanic, div, div_panic, checked_div);
-panic_biself!(Rem, RemPanic, rem, rem_panic, checked_rem);
+panic_biself!(Rem, RemPanic, rem, rem_panic, checked_remainder);
 
 /// Add a value to a given value in-place, panicking on overflow
 ///
@@ -201,7 +307,7 @@ macro_rules! panic_assign_biself {
         #[cfg(feature = "specialization")]
         impl<T, R> $trait_panic<R> for T where T: $trait_name<R> {
             default fn $fn_panic(&mut self, rhs: R) {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -209,11 +315,13 @@ macro_rules! panic_assign_biself {
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, u16);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, u32);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, u64);
+        panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, u128);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, usize);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, i8);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, i16);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, i32);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, i64);
+        panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, i128);
         panic_assign_biself!($trait_panic, $fn_panic, $checked_fn, isize);
     };
     ($trait_panic:ident, $fn_panic:ident, $checked_fn:ident, $ty:ty) => {
@@ -229,7 +337,7 @@ panic_assign_biself!(AddAssign, AddAssignPanic, add_assign, add_assign_panic, ch
 panic_assign_biself!(SubAssign, SubAssignPanic, sub_assign, sub_assign_panic, checked_sub);
 panic_assign_biself!(MulAssign, MulAssignPanic, mul_assign, mul_assign_panic, checked_mul);
 panic_assign_biself!(DivAssign, DivAssignPanic, div_assign, div_assign_panic, checked_div);
-panic_assign_biself!(RemAssign, RemAssignPanic, rem_assign, rem_assign_panic, checked_rem);
+panic_assign_biself!(RemAssign, RemAssignPanic, rem_assign, rem_assign_panic, checked_remainder);
 
 /// Add two values, wrapping on overflow
 ///
@@ -297,7 +405,7 @@ macro_rules! wrap_biself {
         impl<T, R> $trait_wrap<R> for T where T: $trait_name<R> {
             type Output = <T as $trait_name<R>>::Output;
             default fn $fn_wrap(self, rhs: R) -> Self::Output {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -305,11 +413,13 @@ macro_rules! wrap_biself {
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u16);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u32);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u64);
+        wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u128);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, usize);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i8);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i16);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i32);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i64);
+        wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i128);
         wrap_biself!($trait_wrap, $fn_wrap, $wrapped_fn, isize);
     };
     ($trait_wrap:ident, $fn_wrap:ident, $wrapped_fn:ident, $ty:ty) => {
@@ -407,7 +517,7 @@ macro_rules! wrap_assign_biself {
         #[cfg(feature = "specialization")]
         impl<T, R> $trait_wrap<R> for T where T: $trait_name<R> {
             default fn $fn_wrap(&mut self, rhs: R) {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -415,11 +525,13 @@ macro_rules! wrap_assign_biself {
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u16);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u32);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u64);
+        wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, u128);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, usize);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i8);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i16);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i32);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i64);
+        wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, i128);
         wrap_assign_biself!($trait_wrap, $fn_wrap, $wrapped_fn, isize);
     };
     ($trait_wrap:ident, $fn_wrap:ident, $wrapped_fn:ident, $ty:ty) => {
@@ -552,7 +664,7 @@ macro_rules! saturate_biself {
         impl<T, R> $trait_saturate<R> for T where T: $trait_name<R> {
             type Output = <T as $trait_name<R>>::Output;
             default fn $fn_saturate(self, rhs: R) -> Self::Output {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -560,11 +672,13 @@ macro_rules! saturate_biself {
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, u16);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, u32);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, u64);
+        saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, u128);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, usize);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, i8);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, i16);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, i32);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, i64);
+        saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, i128);
         saturate_biself!($trait_saturate, $fn_saturate, $saturated_fn, isize);
     };
     ($trait_saturate:ident, $fn_saturate:ident, $saturated_fn:ident, $ty:ty) => {
@@ -616,7 +730,7 @@ macro_rules! saturate_assign_biself {
         #[cfg(feature = "specialization")]
         impl<T, R> $trait_saturate<R> for T where T: $trait_name<R> {
             default fn $fn_saturate(&mut self, rhs: R) {
-                std::ops::$trait_name::$fn_name(self, rhs)
+                core::ops::$trait_name::$fn_name(self, rhs)
             }
         }
 
@@ -624,11 +738,13 @@ macro_rules! saturate_assign_biself {
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, u16);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, u32);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, u64);
+        saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, u128);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, usize);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, i8);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, i16);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, i32);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, i64);
+        saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, i128);
         saturate_assign_biself!($trait_saturate, $fn_saturate, $saturated_fn, isize);
     };
     ($trait_saturate:ident, $fn_saturate:ident, $saturated_fn:ident, $ty:ty) => {
@@ -643,541 +759,374 @@ macro_rules! saturate_assign_biself {
 saturate_assign_biself!(AddAssign, AddAssignSaturate, add_assign, add_assign_saturate, saturating_add);
 saturate_assign_biself!(SubAssign, SubAssignSaturate, sub_assign, sub_assign_saturate, saturating_sub);
 saturate_assign_biself!(MulAssign, MulAssignSaturate, mul_assign, mul_assign_saturate, saturating_mul);
-// TODO: by zero → min/max value depending on sign
-saturate_assign_biself!(DivAssign, DivAssignSaturate, div_assign, div_assign_saturate, div);
-saturate_assign_biself!(RemAssign, RemAssignSaturate, rem_assign, rem_assign_saturate, rem);
+saturate_assign_biself!(DivAssign, DivAssignSaturate, div_assign, div_assign_saturate, saturating_quotient);
+saturate_assign_biself!(RemAssign, RemAssignSaturate, rem_assign, rem_assign_saturate, saturating_remainder);
 
+saturate_biself!(Div, DivSaturate, div, div_saturate, saturating_quotient);
+saturate_biself!(Rem, RemSaturate, rem, rem_saturate, saturating_remainder);
 
-#[cfg(feature = "specialization")]
-impl<R, T: Div<R>> DivSaturate<R> for T {
-    type Output = <T as Div<R>>::Output;
-    default fn div_saturate(self, rhs: R) -> Self::Output {
-        Div::div(self, rhs)
-    }
-}
-
-#[cfg(feature = "specialization")]
-impl<R, T: Rem<R>> RemSaturate<R> for T {
-    type Output = <T as Rem<R>>::Output;
-    default fn rem_saturate(self, rhs: R) -> Self::Output {
-        Rem::rem(self, rhs)
-    }
+// The parts of the division semantics (see the crate docs) that `std` has no
+// methods for
+trait Division: Sized {
+    // the remainder, `None` for a zero divisor (`MIN % -1` is 0)
+    fn checked_remainder(self, rhs: Self) -> Option<Self>;
+    // the quotient, with zero divisors giving the bound of the dividend's sign
+    fn saturating_quotient(self, rhs: Self) -> Self;
+    // the remainder, with zero divisors leaving the dividend as it is
+    fn saturating_remainder(self, rhs: Self) -> Self;
 }
 
-macro_rules! saturate_signed {
-    ($ty:ty, $min:path, $max:path) => {
-        #[allow(unused_comparisons)]
-        impl DivSaturate for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
+macro_rules! division {
+    ($ty:ty, $min:expr, $max:expr) => {
+        impl Division for $ty {
+            fn checked_remainder(self, rhs: $ty) -> Option<$ty> {
+                if rhs == 0 { None } else { Some(self.wrapping_rem(rhs)) }
+            }
 
-            fn div_saturate(self, rhs: $ty) -> $ty {
-                match rhs {
-                    0 => match self.cmp(&0) {
-                        Ordering::Greater => $max,
-                        Ordering::Equal => 0,
-                        Ordering::Less => $min
-                    },
-                    -1 => if self == $min { $max } else { -self },
-                    _ => self / rhs
+            #[allow(unused_comparisons)]
+            fn saturating_quotient(self, rhs: $ty) -> $ty {
+                if rhs != 0 {
+                    self.saturating_div(rhs)
+                } else if self > 0 {
+                    $max
+                } else if self < 0 {
+                    $min
+                } else {
+                    0
                 }
             }
-        }
-
-        impl RemSaturate for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
 
-            fn rem_saturate(self, rhs: $ty) -> $ty {
-                if rhs == 0 { if self == 0 { 0 } else { $max }
-                } else { self % rhs }
+            fn saturating_remainder(self, rhs: $ty) -> $ty {
+                if rhs == 0 { self } else { self.wrapping_rem(rhs) }
             }
         }
     };
 }
 
-macro_rules! saturate_unsigned {
-    ($ty:ty, $max:path) => {
-        #[allow(unused_comparisons)]
-        impl DivSaturate for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
-
-            fn div_saturate(self, rhs: $ty) -> $ty {
-                match rhs {
-                    0 => if self == 0 { 0 } else { $max },
-                    _ => self / rhs
-                }
-            }
-        }
-
-        impl RemSaturate for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
-
-            fn rem_saturate(self, rhs: $ty) -> $ty {
-                if rhs == 0 { if self == 0 { 0 } else { $max }
-                } else { self % rhs }
-            }
-        }
-    };
+division!(u8,    u8::MIN,    u8::MAX);
+division!(u16,   u16::MIN,   u16::MAX);
+division!(u32,   u32::MIN,   u32::MAX);
+division!(u64,   u64::MIN,   u64::MAX);
+division!(u128,  u128::MIN,  u128::MAX);
+division!(usize, usize::MIN, usize::MAX);
+division!(i8,    i8::MIN,    i8::MAX);
+division!(i16,   i16::MIN,   i16::MAX);
+division!(i32,   i32::MIN,   i32::MAX);
+division!(i64,   i64::MIN,   i64::MAX);
+division!(i128,  i128::MIN,  i128::MAX);
+division!(isize, isize::MIN, isize::MAX);
+
+/// Shift left, panic if the number of bits shifted is out of range or bits are
+/// shifted out of the value
+///
+/// This does the same as the `std::ops::Shl` trait for most types.
+/// It is specialized for integer types to panic if the number of bits is
+/// negative or not lower than the width of the type, or if the result doesn't
+/// fit.
+pub trait ShlPanic<RHS=usize> {
+    /// the result type of our left shift
+    type Output;
+    /// shift left, panic if the number of bits is out of range or bits are
+    /// shifted out of the value
+    fn shl_panic(self, rhs: RHS) -> Self::Output;
 }
 
-saturate_unsigned!(u8,    std::u8::MAX);
-saturate_unsigned!(u16,   std::u16::MAX);
-saturate_unsigned!(u32,   std::u32::MAX);
-saturate_unsigned!(u64,   std::u64::MAX);
-saturate_unsigned!(usize, std::usize::MAX);
-saturate_signed!(i8,    std::i8::MIN,    std::i8::MAX);
-saturate_signed!(i16,   std::i16::MIN,   std::i16::MAX);
-saturate_signed!(i32,   std::i32::MIN,   std::i32::MAX);
-saturate_signed!(i64,   std::i64::MIN,   std::i64::MAX);
-saturate_signed!(isize, std::isize::MIN, std::isize::MAX);
+/// Shift left in place, panic if the number of bits shifted is out of range or
+/// bits are shifted out of the value
+///
+/// This does the same as the `std::ops::ShlAssign` trait for most types.
+/// It is specialized for integer types like `ShlPanic`.
+pub trait ShlAssignPanic<RHS=usize> {
+    /// shift left in place, panic if the number of bits is out of range or
+    /// bits are shifted out of the value
+    fn shl_assign_panic(&mut self, rhs: RHS);
+}
 
-/// Shift right, panic if the number of bits shifted are higher than the width
-/// of the type
+/// Shift right, panic if the number of bits shifted is out of range
 ///
 /// This does the same as the `std::ops::Shr` trait for most types.
-/// it is specialized for integer types to panic on over- or underflow.
+/// It is specialized for integer types to panic if the number of bits is
+/// negative or not lower than the width of the type.
 pub trait ShrPanic<RHS=usize> {
-    /// THe output type of the shift operation
+    /// the result type of our right shift
     type Output;
-
-    /// shift right, panic if the number of bits shifted are higher than the
-    /// width of the type
+    /// shift right, panic if the number of bits is out of range
     fn shr_panic(self, rhs: RHS) -> Self::Output;
 }
 
-/// Shift right in place, panic if the number of bits shifted are higher than
-/// the width of the type
+/// Shift right in place, panic if the number of bits shifted is out of range
 ///
 /// This does the same as the `std::ops::ShrAssign` trait for most types.
-/// it is specialized for integer types to panic on over- or underflow.
+/// It is specialized for integer types like `ShrPanic`.
 pub trait ShrAssignPanic<RHS=usize> {
-    /// shift right in place, panic if the number of bits shifted are higher
-    /// than the width of the type
+    /// shift right in place, panic if the number of bits is out of range
     fn shr_assign_panic(&mut self, rhs: RHS);
 }
 
-macro_rules! panic_shifts {
-    (@$trait_name:ident,
-      $trait_assign_name:ident,
-      $trait_panic:ident,
-      $trait_assign_panic:ident,
-      $fn_name:ident,
-      $fn_assign_name:ident,
-      $fn_panic:ident,
-      $fn_assign_panic:ident,
-      $checked_fn:ident) => {
-        #[cfg(feature = "specialization")]
-        impl<T, R> $trait_panic<R> for T where T: $trait_name<R> {
-            type Output = <T as $trait_name<R>>::Output;
-            default fn $fn_panic(self, rhs: R) -> Self::Output {
-                std::ops::$trait_name::$fn_name(self, rhs)
-            }
-        }
-
-        #[cfg(feature = "specialization")]
-        impl<T, R> $trait_assign_panic<R> for T where T: $trait_assign_name<R> {
-            default fn $fn_assign_panic(&mut self, rhs: R) {
-                $trait_assign_name::$fn_assign_name(self, rhs)
-            }
-        }
-
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, u8);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, u16);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, u32);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, u64);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, usize);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, i8);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, i16);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, i32);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, i64);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, isize);
-    };
-    ($trait_panic:ident, $trait_assign_panic:ident, $fn_panic:ident, $fn_assign_panic:ident, $checked_fn:ident, $ty:ty) => {
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, u8);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, u16);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, u32);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, u64);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, usize);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, i8);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, i16);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, i32);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, i64);
-        panic_shifts!($trait_panic, $trait_assign_panic, $fn_panic, $fn_assign_panic, $checked_fn, $ty, isize);
-    };
-    ($trait_panic:ident, $trait_assign_panic:ident, $fn_panic:ident, $fn_assign_panic:ident, $checked_fn:ident, $ty:ty, $rty:ty) => {
-        impl $trait_panic<$rty> for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
-
-            fn $fn_panic(self, rhs: $rty) -> Self::Output {
-                if let Some(x) = self.$checked_fn(rhs as u32) { x } else
-                            { panic!("Arithmetic overflow") }
-            }
-        }
-
-        impl $trait_assign_panic<$rty> for $ty {
-            fn $fn_assign_panic(&mut self, rhs: $rty) {
-                *self = if let Some(x) = self.$checked_fn(rhs as u32) { x } else
-                            { panic!("Arithmetic overflow") }
-            }
-        }
-    }
-}
-
-panic_shifts!(@Shr, ShrAssign, ShrPanic, ShrAssignPanic, shr, shr_assign, shr_panic, shr_assign_panic, checked_shr);
-
-/// Shift left, return 0 if the number of bits shifted are higher than the
-/// width of the type
+/// Shift left, masking the number of bits shifted to the width of the type
 ///
 /// This does the same as the `std::ops::Shl` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types to shift by the number of bits modulo
+/// the width of the type (as `wrapping_shl` does), dropping the bits shifted
+/// out of the value.
 pub trait ShlWrap<RHS=usize> {
-    /// the return type of our shift operation
+    /// the result type of our left shift
     type Output;
-    /// shift left, return 0 if the number of bits shifted are higher than the
-    /// width of the type
+    /// shift left by the number of bits modulo the width of the type
     fn shl_wrap(self, rhs: RHS) -> Self::Output;
 }
 
-/// Shift left in place, set self to 0 if the number of bits shifted are higher
-/// than the width of the type
+/// Shift left in place, masking the number of bits shifted to the width of the
+/// type
 ///
 /// This does the same as the `std::ops::ShlAssign` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types like `ShlWrap`.
 pub trait ShlAssignWrap<RHS=usize> {
-    /// Shift left in place, set self to 0 if the number of bits shifted are
-    /// higher than the width of the type
+    /// shift left in place by the number of bits modulo the width of the type
     fn shl_assign_wrap(&mut self, rhs: RHS);
 }
 
-/// Shift right, return 0 if the number of bits shifted are higher than the
-/// width of the type
+/// Shift right, masking the number of bits shifted to the width of the type
 ///
 /// This does the same as the `std::ops::Shr` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types to shift by the number of bits modulo
+/// the width of the type (as `wrapping_shr` does).
 pub trait ShrWrap<RHS=usize> {
-    /// the return type of our shift operation
+    /// the result type of our right shift
     type Output;
-    /// shift right, return 0 if the number of bits shifted are higher than the
-    /// width of the type
+    /// shift right by the number of bits modulo the width of the type
     fn shr_wrap(self, rhs: RHS) -> Self::Output;
 }
 
-/// Shift right in place, set self to 0 if the number of bits shifted are
-/// higher than the width of the type
+/// Shift right in place, masking the number of bits shifted to the width of
+/// the type
 ///
 /// This does the same as the `std::ops::ShrAssign` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types like `ShrWrap`.
 pub trait ShrAssignWrap<RHS=usize> {
-    /// Shift right in place, set self to 0 if the number of bits shifted are
-    /// higher than the width of the type
+    /// shift right in place by the number of bits modulo the width of the
+    /// type
     fn shr_assign_wrap(&mut self, rhs: RHS);
 }
 
-macro_rules! wrap_shifts {
-    (@$trait_name:ident, $trait_assign_name:ident, $trait_wrap:ident, $trait_assign_wrap:ident, $fn_name:ident, $fn_assign_name:ident, $fn_wrap:ident, $fn_assign_wrap:ident, $wrapping_fn:ident) => {
-        #[cfg(feature = "specialization")]
-        impl<T, R> $trait_wrap<R> for T where T: $trait_name<R> {
-            type Output = <T as $trait_name<R>>::Output;
-            default fn $fn_wrap(self, rhs: R) -> Self::Output {
-                std::ops::$trait_name::$fn_name(self, rhs)
-            }
-        }
-
-        #[cfg(feature = "specialization")]
-        impl<T, R> $trait_assign_wrap<R> for T where T: $trait_assign_name<R> {
-            default fn $fn_assign_wrap(&mut self, rhs: R) {
-                $trait_assign_name::$fn_assign_name(self, rhs)
-            }
-        }
-
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, u8);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, u16);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, u32);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, u64);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, usize);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, i8);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, i16);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, i32);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, i64);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, isize);
-    };
-    ($trait_wrap:ident, $trait_assign_wrap:ident, $fn_wrap:ident, $fn_assign_wrap:ident, $wrapping_fn:ident, $ty:ty) => {
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, u8);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, u16);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, u32);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, u64);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, usize);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, i8);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, i16);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, i32);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, i64);
-        wrap_shifts!($trait_wrap, $trait_assign_wrap, $fn_wrap, $fn_assign_wrap, $wrapping_fn, $ty, isize);
-    };
-    ($trait_wrap:ident, $trait_assign_wrap:ident, $fn_wrap:ident, $fn_assign_wrap:ident, $wrapping_fn:ident, $ty:ty, $rty:ty) => {
-        impl $trait_wrap<$rty> for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
-
-            fn $fn_wrap(self, rhs: $rty) -> Self::Output {
-                self.$wrapping_fn(rhs as u32)
-            }
-        }
-
-        impl $trait_assign_wrap<$rty> for $ty {
-            fn $fn_assign_wrap(&mut self, rhs: $rty) {
-                *self = self.$wrapping_fn(rhs as u32)
-            }
-        }
-    }
-}
-
-wrap_shifts!(@Shl, ShlAssign, ShlWrap, ShlAssignWrap, shl, shl_assign, shl_wrap, shl_assign_wrap, wrapping_shl);
-wrap_shifts!(@Shr, ShrAssign, ShrWrap, ShrAssignWrap, shr, shr_assign, shr_wrap, shr_assign_wrap, wrapping_shr);
-
-/// Shift right, return 0 if the number of bits shifted are higher than the
-/// width of the type
-///
-/// This does the same as the `std::ops::Shr` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
-pub trait ShrSaturate<RHS=usize> {
-    /// the return type of our shift operation
-    type Output;
-    /// shift right, return 0 if the number of bits shifted are higher than the
-    /// width of the type
-    fn shr_saturate(self, rhs: RHS) -> Self::Output;
-}
-
-
-/// Shift right in place, set self to 0 if the number of bits shifted are
-/// higher than the width of the type
-///
-/// This does the same as the `std::ops::ShrAssign` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
-pub trait ShrSaturateAssign<RHS=usize> {
-    /// shift right in place, set self to 0 if the number of bits shifted are
-    /// higher than the width of the type
-    fn shr_assign_saturate(&mut self, rhs: RHS);
-}
-
-#[cfg(feature = "specialization")]
-impl<R, T: Shr<R>> ShrSaturate<R> for T {
-    type Output = <T as Shr<R>>::Output;
-    default fn shr_saturate(self, rhs: R) -> Self::Output { self >> rhs }
-}
-
-#[cfg(feature = "specialization")]
-impl<R, T: ShrAssign<R>> ShrSaturateAssign<R> for T {
-    default fn shr_assign_saturate(&mut self, rhs: R) { *self >>= rhs; }
-}
-
-/// Shift left, return 0 if the number of bits shifted are higher than the
-/// width of the type
+/// Shift left, saturate if bits are shifted out of the value
 ///
 /// This does the same as the `std::ops::Shl` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types to return `MAX` (or `MIN` for negative
+/// values) if the result doesn't fit. A number of bits that is negative or not
+/// lower than the width of the type shifts all bits out, so only zero stays
+/// as it is.
 pub trait ShlSaturate<RHS=usize> {
-    /// the return type of our shift operation
+    /// the result type of our left shift
     type Output;
-    /// shift left, return 0 if the number of bits shifted are higher than the
-    /// width of the type
+    /// shift left, saturate if bits are shifted out of the value
     fn shl_saturate(self, rhs: RHS) -> Self::Output;
 }
 
-/// Shift left in place, set self to 0 if the number of bits shifted are
-/// higher than the width of the type
+/// Shift left in place, saturate if bits are shifted out of the value
 ///
 /// This does the same as the `std::ops::ShlAssign` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// It is specialized for integer types like `ShlSaturate`.
 pub trait ShlAssignSaturate<RHS=usize> {
-    /// shift left in place, set self to 0 if the number of bits shifted are
-    /// higher than the width of the type
+    /// shift left in place, saturate if bits are shifted out of the value
     fn shl_assign_saturate(&mut self, rhs: RHS);
 }
 
-#[cfg(feature = "specialization")]
-impl<R, T: Shl<R>> ShlSaturate<R> for T {
-    type Output = <T as Shl<R>>::Output;
-    default fn shl_saturate(self, rhs: R) -> Self::Output { self << rhs }
-}
-
-#[cfg(feature = "specialization")]
-impl<R, T: ShlAssign<R>> ShlAssignSaturate<R> for T {
-    default fn shl_assign_saturate(&mut self, rhs: R) { *self <<= rhs; }
-}
-
-/// Shift left, panic if bits are shifted out of the value
+/// Shift right, shifting all bits out if the number of bits shifted is out of
+/// range
 ///
-/// This does the same as the `std::ops::Shl` trait for most types.
-/// it is specialized for integer types to panic on over- or underflow.
-pub trait ShlPanic<RHS=usize> {
-    /// the result type of our left shift
+/// This does the same as the `std::ops::Shr` trait for most types.
+/// It is specialized for integer types to return 0 (or -1 for negative values)
+/// if the number of bits is negative or not lower than the width of the type.
+pub trait ShrSaturate<RHS=usize> {
+    /// the result type of our right shift
     type Output;
-
-    /// shift left, panic if bits are shifted out of the value
-    fn shl_panic(self, rhs: RHS) -> Self::Output;
-}
-
-#[cfg(feature = "specialization")]
-impl<T, R> ShlPanic<R> for T where T: Shl<R> {
-    type Output = <T as Shl<R>>::Output;
-    default fn shl_panic(self, rhs: R) -> Self::Output {
-        std::ops::Shl::shl(self, rhs)
-    }
-}
-
-/// Shift left in place, panic if bits are shifted out of the value
-///
-/// This does the same as the `std::ops::Shl` trait for most types.
-/// it is specialized for integer types to panic on over- or underflow.
-pub trait ShlAssignPanic<RHS=usize> {
-    /// Shift left in place, panic if bits are shifted out of the value
-    fn shl_assign_panic(&mut self, rhs: RHS);
-}
-
-#[cfg(feature = "specialization")]
-impl<T, R> ShlAssignPanic<R> for T where T: ShlAssign<R> {
-    default fn shl_assign_panic(&mut self, rhs: R) {
-        ShlAssign::shl_assign(self, rhs)
-    }
+    /// shift right, shift all bits out if the number of bits is out of range
+    fn shr_saturate(self, rhs: RHS) -> Self::Output;
 }
 
-/// Shift right in place, set self to 0 if the number of bits shifted are
-/// higher than the width of the type
+/// Shift right in place, shifting all bits out if the number of bits shifted
+/// is out of range
 ///
-/// This does the same as the `std::ops::ShlAssign` trait for most types.
-/// it is specialized for integer types to return zero on over- or underflow.
+/// This does the same as the `std::ops::ShrAssign` trait for most types.
+/// It is specialized for integer types like `ShrSaturate`.
 pub trait ShrAssignSaturate<RHS=usize> {
-    /// shift right in place, set self to 0 if the number of bits shifted are
-    /// higher than the width of the type
+    /// shift right in place, shift all bits out if the number of bits is out
+    /// of range
     fn shr_assign_saturate(&mut self, rhs: RHS);
 }
 
-macro_rules! saturate_shl_unsigned {
-    ($ty:ty, $max:expr, $bits:expr) => {
-        saturate_shl_unsigned!($ty, $max, $bits, u8);
-        saturate_shl_unsigned!($ty, $max, $bits, u16);
-        saturate_shl_unsigned!($ty, $max, $bits, u32);
-        saturate_shl_unsigned!($ty, $max, $bits, u64);
-        saturate_shl_unsigned!($ty, $max, $bits, usize);
-        saturate_shl_unsigned!($ty, $max, $bits, i8);
-        saturate_shl_unsigned!($ty, $max, $bits, i16);
-        saturate_shl_unsigned!($ty, $max, $bits, i32);
-        saturate_shl_unsigned!($ty, $max, $bits, i64);
-        saturate_shl_unsigned!($ty, $max, $bits, isize);
-    };
-    ($ty:ty, $max:expr, $bits:expr, $rty:ty) => {
-        impl ShlSaturate<$rty> for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
-
-            fn shl_saturate(self, rhs: $rty) -> Self::Output {
-                if self == 0 { return 0; }
-                if rhs as usize >= $bits || ((!0) >> rhs) < self {
-                    $max
-                } else {
-                    self << rhs
+macro_rules! shift_blankets {
+    ($($trait_name:ident, $trait_assign_name:ident, $fn_name:ident, $fn_assign_name:ident,
+            $trait_op:ident, $trait_assign_op:ident, $fn_op:ident, $fn_assign_op:ident;)*) => {
+        $(
+            #[cfg(feature = "specialization")]
+            impl<T, R> $trait_op<R> for T where T: $trait_name<R> {
+                type Output = <T as $trait_name<R>>::Output;
+                default fn $fn_op(self, rhs: R) -> Self::Output {
+                    $trait_name::$fn_name(self, rhs)
                 }
             }
-        }
-
-        impl ShrSaturate<$rty> for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
 
-            fn shr_saturate(self, rhs: $rty) -> Self::Output {
-                if rhs as usize >= $bits { 0 } else { self >> rhs }
+            #[cfg(feature = "specialization")]
+            impl<T, R> $trait_assign_op<R> for T where T: $trait_assign_name<R> {
+                default fn $fn_assign_op(&mut self, rhs: R) {
+                    $trait_assign_name::$fn_assign_name(self, rhs)
+                }
             }
+        )*
+    };
+}
+
+shift_blankets! {
+    Shl, ShlAssign, shl, shl_assign, ShlPanic, ShlAssignPanic, shl_panic, shl_assign_panic;
+    Shr, ShrAssign, shr, shr_assign, ShrPanic, ShrAssignPanic, shr_panic, shr_assign_panic;
+    Shl, ShlAssign, shl, shl_assign, ShlWrap, ShlAssignWrap, shl_wrap, shl_assign_wrap;
+    Shr, ShrAssign, shr, shr_assign, ShrWrap, ShrAssignWrap, shr_wrap, shr_assign_wrap;
+    Shl, ShlAssign, shl, shl_assign, ShlSaturate, ShlAssignSaturate, shl_saturate,
+        shl_assign_saturate;
+    Shr, ShrAssign, shr, shr_assign, ShrSaturate, ShrAssignSaturate, shr_saturate,
+        shr_assign_saturate;
+}
+
+// The number of bits to shift by, which may be of any integer type
+trait ShiftAmount: Copy {
+    // `Ok` with the number of bits if it is lower than `bits`, otherwise `Err`
+    // with the number of bits modulo `bits` (a power of two, so this also
+    // holds for negative numbers)
+    fn bits_within(self, bits: u32) -> Result<u32, u32>;
+
+    // the number of bits modulo `bits`, and whether it was out of range
+    fn overflowing_bits(self, bits: u32) -> (u32, bool) {
+        match self.bits_within(bits) {
+            Ok(n) => (n, false),
+            Err(n) => (n, true),
         }
+    }
+}
 
-        impl ShlAssignSaturate<$rty> for $ty {
-            fn shl_assign_saturate(&mut self, rhs: $rty) {
-                if *self == 0 { return; }
-                *self = if rhs as usize >= $bits || (!0) >> rhs < *self {
-                    $max
-                } else {
-                    *self << rhs
+macro_rules! shift_amount {
+    ($rty:ty) => {
+        impl ShiftAmount for $rty {
+            fn bits_within(self, bits: u32) -> Result<u32, u32> {
+                match u32::try_from(self) {
+                    Ok(n) if n < bits => Ok(n),
+                    _ => Err(self as u32 & (bits - 1)),
                 }
             }
         }
+    };
+}
 
-        impl ShrAssignSaturate<$rty> for $ty {
-            fn shr_assign_saturate(&mut self, rhs: $rty) {
-                *self = self.checked_shr(rhs as u32).unwrap_or(0);
+shift_amount!(u8);
+shift_amount!(u16);
+shift_amount!(u32);
+shift_amount!(u64);
+shift_amount!(u128);
+shift_amount!(usize);
+shift_amount!(i8);
+shift_amount!(i16);
+shift_amount!(i32);
+shift_amount!(i64);
+shift_amount!(i128);
+shift_amount!(isize);
+
+// Left shifts that detect bits shifted out of the value
+trait ShiftLeft: Sized {
+    // the result, `None` if `n` is not lower than the width of the type or
+    // bits are shifted out of the value
+    fn checked_shift_left(self, n: u32) -> Option<Self>;
+    // the result, `MAX` (or `MIN` for negative values) if bits are shifted out
+    // of the value, with `n` lower than the width of the type
+    fn saturating_shift_left(self, n: u32) -> Self;
+}
+
+macro_rules! shifts {
+    ($ty:ty, $min:expr, $max:expr) => {
+        impl ShiftLeft for $ty {
+            fn checked_shift_left(self, n: u32) -> Option<$ty> {
+                // the arithmetic shift back also catches changes of the sign
+                self.checked_shl(n).filter(|x| x >> n == self)
+            }
+
+            #[allow(unused_comparisons)]
+            fn saturating_shift_left(self, n: u32) -> $ty {
+                match self.checked_shift_left(n) {
+                    Some(x) => x,
+                    None if self < 0 => $min,
+                    None => $max,
+                }
             }
         }
 
+        shifts!($ty, u8);
+        shifts!($ty, u16);
+        shifts!($ty, u32);
+        shifts!($ty, u64);
+        shifts!($ty, u128);
+        shifts!($ty, usize);
+        shifts!($ty, i8);
+        shifts!($ty, i16);
+        shifts!($ty, i32);
+        shifts!($ty, i64);
+        shifts!($ty, i128);
+        shifts!($ty, isize);
+    };
+    ($ty:ty, $rty:ty) => {
         impl ShlPanic<$rty> for $ty {
             #[cfg(not(feature = "specialization"))]
             type Output = $ty;
 
             fn shl_panic(self, rhs: $rty) -> Self::Output {
-                if self == 0 { return 0; }
-                if (rhs as usize >= $bits || ((!0) >> rhs) < self) && self != 0 {
-                    panic!("Arithmetic overflow");
+                match rhs.bits_within(<$ty>::BITS).ok().and_then(|n| self.checked_shift_left(n)) {
+                    Some(x) => x,
+                    None => panic!("arithmetic overflow"),
                 }
-                self << rhs
             }
         }
 
-        impl ShlAssignPanic<$rty> for $ty {
-            fn shl_assign_panic(&mut self, rhs: $rty) {
-                if *self == 0 { return; }
-                *self = if rhs as usize >= $bits || (!0) >> rhs < *self {
-                    panic!("Arithmetic overflow");
-                } else {
-                    *self << rhs
+        impl ShrPanic<$rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn shr_panic(self, rhs: $rty) -> Self::Output {
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) => self >> n,
+                    Err(_) => panic!("arithmetic overflow"),
                 }
             }
         }
-    };
-}
 
-#[cfg(target_pointer_width = "16")]
-const USIZE_BITS: usize = 16;
+        impl ShlWrap<$rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-#[cfg(target_pointer_width = "32")]
-const USIZE_BITS: usize = 16;
+            fn shl_wrap(self, rhs: $rty) -> Self::Output {
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) | Err(n) => self << n,
+                }
+            }
+        }
 
-#[cfg(target_pointer_width = "64")]
-const USIZE_BITS: usize = 64;
+        impl ShrWrap<$rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-saturate_shl_unsigned!(u8, std::u8::MAX, 8);
-saturate_shl_unsigned!(u16, std::u16::MAX, 16);
-saturate_shl_unsigned!(u32, std::u32::MAX, 32);
-saturate_shl_unsigned!(u64, std::u64::MAX, 64);
-saturate_shl_unsigned!(usize, std::usize::MAX, USIZE_BITS);
+            fn shr_wrap(self, rhs: $rty) -> Self::Output {
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) | Err(n) => self >> n,
+                }
+            }
+        }
 
-macro_rules! saturate_shl_signed {
-    ($ty:ty, $max:expr, $min:expr, $bits:expr) => {
-        saturate_shl_signed!($ty, $max, $min, $bits, u8);
-        saturate_shl_signed!($ty, $max, $min, $bits, u16);
-        saturate_shl_signed!($ty, $max, $min, $bits, u32);
-        saturate_shl_signed!($ty, $max, $min, $bits, u64);
-        saturate_shl_signed!($ty, $max, $min, $bits, usize);
-        saturate_shl_signed!($ty, $max, $min, $bits, i8);
-        saturate_shl_signed!($ty, $max, $min, $bits, i16);
-        saturate_shl_signed!($ty, $max, $min, $bits, i32);
-        saturate_shl_signed!($ty, $max, $min, $bits, i64);
-        saturate_shl_signed!($ty, $max, $min, $bits, isize);
-    };
-    ($ty:ty, $max:expr, $min:expr, $bits:expr, $rty:ty) => {
         impl ShlSaturate<$rty> for $ty {
             #[cfg(not(feature = "specialization"))]
             type Output = $ty;
 
             fn shl_saturate(self, rhs: $rty) -> Self::Output {
-                match self.cmp(&0) {
-                    Ordering::Equal => 0,
-                    Ordering::Greater => {
-                        if rhs as usize >= $bits || ($max >> rhs) < self { $max } else { self << rhs }
-                    }
-                    Ordering::Less => {
-                        if rhs as usize >= $bits || ($min >> rhs) > self { $min } else { self << rhs }
-                    }
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) => self.saturating_shift_left(n),
+                    Err(_) => self.saturating_shift_left(<$ty>::BITS - 1).saturating_shift_left(1),
                 }
             }
         }
@@ -1187,269 +1136,1738 @@ macro_rules! saturate_shl_signed {
             type Output = $ty;
 
             fn shr_saturate(self, rhs: $rty) -> Self::Output {
-                if rhs as usize >= $bits { 0 } else { self >> rhs }
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) => self >> n,
+                    Err(_) => self >> (<$ty>::BITS - 1) >> 1,
+                }
+            }
+        }
+
+        impl ShlAssignPanic<$rty> for $ty {
+            fn shl_assign_panic(&mut self, rhs: $rty) {
+                *self = ShlPanic::shl_panic(*self, rhs);
+            }
+        }
+
+        impl ShrAssignPanic<$rty> for $ty {
+            fn shr_assign_panic(&mut self, rhs: $rty) {
+                *self = ShrPanic::shr_panic(*self, rhs);
+            }
+        }
+
+        impl ShlAssignWrap<$rty> for $ty {
+            fn shl_assign_wrap(&mut self, rhs: $rty) {
+                *self = ShlWrap::shl_wrap(*self, rhs);
+            }
+        }
+
+        impl ShrAssignWrap<$rty> for $ty {
+            fn shr_assign_wrap(&mut self, rhs: $rty) {
+                *self = ShrWrap::shr_wrap(*self, rhs);
             }
         }
 
         impl ShlAssignSaturate<$rty> for $ty {
             fn shl_assign_saturate(&mut self, rhs: $rty) {
-                let s = *self;
-                *self = match s.cmp(&0) {
-                    Ordering::Equal => 0,
-                    Ordering::Greater => {
-                        if rhs as usize >= $bits || ($max >> rhs) < s { $max } else { s << rhs }
-                    }
-                    Ordering::Less => {
-                        if rhs as usize >= $bits || ($min >> rhs) > s { $min } else { s << rhs }
-                    }
-                }
+                *self = ShlSaturate::shl_saturate(*self, rhs);
             }
         }
 
         impl ShrAssignSaturate<$rty> for $ty {
             fn shr_assign_saturate(&mut self, rhs: $rty) {
-                *self = self.checked_shr(rhs as u32).unwrap_or(0);
+                *self = ShrSaturate::shr_saturate(*self, rhs);
             }
         }
 
-        impl ShlPanic<$rty> for $ty {
-            #[cfg(not(feature = "specialization"))]
-            type Output = $ty;
+        shifts!(refs $ty, $rty, ShlPanic::shl_panic, ShrPanic::shr_panic,
+            ShlWrap::shl_wrap, ShrWrap::shr_wrap,
+            ShlSaturate::shl_saturate, ShrSaturate::shr_saturate);
+        shifts!(assign_refs $ty, $rty, ShlAssignPanic::shl_assign_panic,
+            ShrAssignPanic::shr_assign_panic, ShlAssignWrap::shl_assign_wrap,
+            ShrAssignWrap::shr_assign_wrap, ShlAssignSaturate::shl_assign_saturate,
+            ShrAssignSaturate::shr_assign_saturate);
+    };
+    (refs $ty:ty, $rty:ty, $($trait_name:ident::$fn_name:ident),*) => {
+        $(
+            impl $trait_name<$rty> for &$ty {
+                #[cfg(not(feature = "specialization"))]
+                type Output = $ty;
+
+                fn $fn_name(self, rhs: $rty) -> $ty {
+                    $trait_name::$fn_name(*self, rhs)
+                }
+            }
 
-            fn shl_panic(self, rhs: $rty) -> Self::Output {
-                match self.cmp(&0) {
-                    Ordering::Equal => return 0,
-                    Ordering::Greater => {
-                        if rhs as usize >= $bits || ($max >> rhs) < self { panic!("Arithmetic overflow"); }
-                    }
-                    Ordering::Less => {
-                        if rhs as usize >= $bits || ($min >> rhs) > self { panic!("Arithmetic overflow"); }
-                    }
+            impl $trait_name<&$rty> for $ty {
+                #[cfg(not(feature = "specialization"))]
+                type Output = $ty;
+
+                fn $fn_name(self, rhs: &$rty) -> $ty {
+                    $trait_name::$fn_name(self, *rhs)
                 }
-                self << rhs
             }
-        }
 
-        impl ShlAssignPanic<$rty> for $ty {
-            fn shl_assign_panic(&mut self, rhs: $rty) {
-                let s = *self;
-                match s.cmp(&0) {
-                    Ordering::Equal => return,
-                    Ordering::Greater => {
-                        if rhs as usize >= $bits || ($max >> rhs) < s { panic!("Arithmetic overflow"); }
-                    }
-                    Ordering::Less => {
-                        if rhs as usize >= $bits || ($min >> rhs) > s { panic!("Arithmetic overflow"); }
-                    }
+            impl $trait_name<&$rty> for &$ty {
+                #[cfg(not(feature = "specialization"))]
+                type Output = $ty;
+
+                fn $fn_name(self, rhs: &$rty) -> $ty {
+                    $trait_name::$fn_name(*self, *rhs)
                 }
-                *self <<= rhs
             }
-        }
+        )*
+    };
+    (assign_refs $ty:ty, $rty:ty, $($trait_name:ident::$fn_name:ident),*) => {
+        $(
+            impl $trait_name<&$rty> for $ty {
+                fn $fn_name(&mut self, rhs: &$rty) {
+                    $trait_name::$fn_name(self, *rhs)
+                }
+            }
+        )*
     };
 }
 
-#[cfg(target_pointer_width = "16")]
-const ISIZE_BITS: usize = 15;
-
-#[cfg(target_pointer_width = "32")]
-const ISIZE_BITS: usize = 31;
+shifts!(u8,    u8::MIN,    u8::MAX);
+shifts!(u16,   u16::MIN,   u16::MAX);
+shifts!(u32,   u32::MIN,   u32::MAX);
+shifts!(u64,   u64::MIN,   u64::MAX);
+shifts!(u128,  u128::MIN,  u128::MAX);
+shifts!(usize, usize::MIN, usize::MAX);
+shifts!(i8,    i8::MIN,    i8::MAX);
+shifts!(i16,   i16::MIN,   i16::MAX);
+shifts!(i32,   i32::MIN,   i32::MAX);
+shifts!(i64,   i64::MIN,   i64::MAX);
+shifts!(i128,  i128::MIN,  i128::MAX);
+shifts!(isize, isize::MIN, isize::MAX);
 
-#[cfg(target_pointer_width = "64")]
-const ISIZE_BITS: usize = 63;
+/// Negate a value, panic on overflow
+///
+/// This does the same as the `std::ops::Neg` trait for most types.
+/// it is specialized for integer types to panic on overflow.
+pub trait NegPanic {
+    /// the result type of the negation
+    type Output;
+    /// negate a value, panic on overflow
+    fn neg_panic(self) -> Self::Output;
+}
+
+#[cfg(feature = "specialization")]
+impl<T> NegPanic for T where T: Neg {
+    type Output = <T as Neg>::Output;
+    default fn neg_panic(self) -> Self::Output {
+        -self
+    }
+}
+
+macro_rules! neg_panic {
+    ($ty:ty) => {
+        impl NegPanic for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_panic(self) -> Self::Output {
+                if let Some(x) = self.checked_neg() { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl NegPanic for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_panic(self) -> $ty {
+                NegPanic::neg_panic(*self)
+            }
+        }
+    }
+}
+
+neg_panic!(i8);
+neg_panic!(i16);
+neg_panic!(i32);
+neg_panic!(i64);
+neg_panic!(i128);
+neg_panic!(isize);
+
+/// Negate a value, wrap on overflow
+///
+/// This does the same as the `std::ops::Neg` trait for most types.
+/// it is specialized for integer types to wrap on overflow.
+pub trait NegWrap {
+    /// the result type of the negation
+    type Output;
+    /// negate a value, wrap on overflow
+    fn neg_wrap(self) -> Self::Output;
+}
+
+#[cfg(feature = "specialization")]
+impl<T> NegWrap for T where T: Neg {
+    type Output = <T as Neg>::Output;
+    default fn neg_wrap(self) -> Self::Output {
+        -self
+    }
+}
+
+macro_rules! neg_wrap {
+    ($ty:ty) => {
+        impl NegWrap for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_wrap(self) -> Self::Output {
+                self.wrapping_neg()
+            }
+        }
+
+        impl NegWrap for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_wrap(self) -> $ty {
+                self.wrapping_neg()
+            }
+        }
+    }
+}
+
+neg_wrap!(i8);
+neg_wrap!(i16);
+neg_wrap!(i32);
+neg_wrap!(i64);
+neg_wrap!(i128);
+neg_wrap!(isize);
+
+/// Negate a value, saturate on overflow
+///
+/// This does the same as the `std::ops::Neg` trait for most types.
+/// it is specialized for integer types to saturate on overflow.
+pub trait NegSaturate {
+    /// the output type of the negation
+    type Output;
+    /// negate a value, saturate on overflow
+    fn neg_saturate(self) -> Self::Output;
+}
+
+#[cfg(feature = "specialization")]
+impl<T> NegSaturate for T where T: Neg {
+    type Output = <T as Neg>::Output;
+    default fn neg_saturate(self) -> Self::Output {
+        -self
+    }
+}
+
+macro_rules! neg_saturate {
+    ($ty:ty, $min:expr, $max:expr) => {
+        impl NegSaturate for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = Self;
+
+            fn neg_saturate(self) -> Self {
+                if self == $min { $max } else { -self }
+            }
+        }
+
+        impl NegSaturate for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_saturate(self) -> $ty {
+                NegSaturate::neg_saturate(*self)
+            }
+        }
+    };
+}
+
+neg_saturate!(i8, i8::MIN, i8::MAX);
+neg_saturate!(i16, i16::MIN, i16::MAX);
+neg_saturate!(i32, i32::MIN, i32::MAX);
+neg_saturate!(i64, i64::MIN, i64::MAX);
+neg_saturate!(i128, i128::MIN, i128::MAX);
+neg_saturate!(isize, isize::MIN, isize::MAX);
+
+/// Compute the absolute value of `self`, panicking on overflow
+///
+/// This does the same as the `std::i*::abs(_)` methods, but panics on overflow
+pub trait AbsPanic {
+    /// compute the absolute value of `self`, panicking on overflow
+    fn abs_panic(self) -> Self;
+}
+
+/// Compute the absolute value of `self`, wrapping on overflow
+///
+/// This does the same as the `std::i*::abs(_)` methods, but wraps on overflow
+pub trait AbsWrap {
+    /// compute the absolute value of `self`, wrapping on overflow
+    fn abs_wrap(self) -> Self;
+}
+
+/// Compute the absolute value of `self`, saturating on overflow
+///
+/// This does the same as the `std::i*::abs(_)` methods, but saturates on
+/// overflow
+pub trait AbsSaturate {
+    /// compute the absolute value of `self`, saturating on overflow
+    fn abs_saturate(self) -> Self;
+}
+
+macro_rules! abs_unsigned {
+    ($ty:ty) => {
+        impl AbsPanic for $ty {
+            fn abs_panic(self) -> Self {
+                self
+            }
+        }
+
+        impl AbsWrap for $ty {
+            fn abs_wrap(self) -> Self {
+                self
+            }
+        }
+
+        impl AbsSaturate for $ty {
+            fn abs_saturate(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+abs_unsigned!(u8);
+abs_unsigned!(u16);
+abs_unsigned!(u32);
+abs_unsigned!(u64);
+abs_unsigned!(u128);
+abs_unsigned!(usize);
+
+macro_rules! abs_signed {
+    ($ty:ty) => {
+        impl AbsPanic for $ty {
+            fn abs_panic(self) -> Self {
+                if self < 0 { 0.sub_panic(self) } else { self }
+            }
+        }
+
+        impl AbsWrap for $ty {
+            fn abs_wrap(self) -> Self {
+                if self < 0 { 0.sub_wrap(self) } else { self }
+            }
+        }
+
+        impl AbsSaturate for $ty {
+            fn abs_saturate(self) -> Self {
+                if self < 0 { 0.sub_saturate(self) } else { self }
+            }
+        }
+    };
+}
+
+abs_signed!(i8);
+abs_signed!(i16);
+abs_signed!(i32);
+abs_signed!(i64);
+abs_signed!(i128);
+abs_signed!(isize);
+
+/// Raise a value to a power, panicking on overflow
+///
+/// This does the same as the `pow(_)` methods of the integer types, but
+/// panics on overflow regardless of the `overflow-checks` setting. The
+/// exponent may be of any integer type; negative exponents panic.
+pub trait PowPanic<RHS = u32> {
+    /// the result type of the exponentiation
+    type Output;
+    /// raise `self` to the power of `rhs`, panicking on overflow
+    fn pow_panic(self, rhs: RHS) -> Self::Output;
+}
+
+/// Raise a value to a power, wrapping on overflow
+///
+/// This does the same as the `wrapping_pow(_)` methods of the integer types.
+/// The exponent may be of any integer type; negative exponents panic.
+pub trait PowWrap<RHS = u32> {
+    /// the result type of the exponentiation
+    type Output;
+    /// raise `self` to the power of `rhs`, wrapping on overflow
+    fn pow_wrap(self, rhs: RHS) -> Self::Output;
+}
+
+/// Raise a value to a power, saturating on overflow
+///
+/// This does the same as the `saturating_pow(_)` methods of the integer
+/// types. The exponent may be of any integer type; negative exponents panic.
+pub trait PowSaturate<RHS = u32> {
+    /// the result type of the exponentiation
+    type Output;
+    /// raise `self` to the power of `rhs`, saturating on overflow
+    fn pow_saturate(self, rhs: RHS) -> Self::Output;
+}
+
+/// Raise a value to a power, returning `None` on overflow
+///
+/// This does the same as the `checked_pow(_)` methods of the integer types.
+/// The exponent may be of any integer type; negative exponents panic.
+pub trait PowChecked<RHS = u32> {
+    /// the result type of the exponentiation
+    type Output;
+    /// raise `self` to the power of `rhs`, returning `None` on overflow
+    fn pow_checked(self, rhs: RHS) -> Option<Self::Output>;
+}
+
+// The exponent as an unsigned integer
+fn pow_exponent<R>(rhs: R) -> u128 where u128: TryFrom<R> {
+    u128::try_from(rhs).unwrap_or_else(|_| panic!("negative exponent"))
+}
+
+// An exponent that fits the `pow` methods and leads to the same result if
+// there is no overflow: beyond `u32::MAX`, only the bases 0, 1 and -1 don't
+// overflow, so the parity is all that matters
+fn clamp_exponent(exp: u128) -> u32 {
+    u32::try_from(exp).unwrap_or(u32::MAX - 1 + (exp & 1) as u32)
+}
+
+macro_rules! pow_impls {
+    ($ty:ty) => {
+        pow_impls!($ty, u8);
+        pow_impls!($ty, u16);
+        pow_impls!($ty, u32);
+        pow_impls!($ty, u64);
+        pow_impls!($ty, u128);
+        pow_impls!($ty, usize);
+        pow_impls!($ty, i8);
+        pow_impls!($ty, i16);
+        pow_impls!($ty, i32);
+        pow_impls!($ty, i64);
+        pow_impls!($ty, i128);
+        pow_impls!($ty, isize);
+    };
+    ($ty:ty, $rty:ty) => {
+        impl PowChecked<$rty> for $ty {
+            type Output = $ty;
+
+            fn pow_checked(self, rhs: $rty) -> Option<$ty> {
+                self.checked_pow(clamp_exponent(pow_exponent(rhs)))
+            }
+        }
+
+        impl PowPanic<$rty> for $ty {
+            type Output = $ty;
+
+            fn pow_panic(self, rhs: $rty) -> $ty {
+                if let Some(x) = self.pow_checked(rhs) { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl PowWrap<$rty> for $ty {
+            type Output = $ty;
+
+            fn pow_wrap(self, rhs: $rty) -> $ty {
+                let exp = pow_exponent(rhs);
+                if let Ok(exp) = u32::try_from(exp) {
+                    return self.wrapping_pow(exp);
+                }
+                // square and multiply for exponents `wrapping_pow` can't take
+                let (mut result, mut base, mut exp) = (1 as $ty, self, exp);
+                while exp != 0 {
+                    if exp & 1 == 1 {
+                        result = result.wrapping_mul(base);
+                    }
+                    base = base.wrapping_mul(base);
+                    exp >>= 1;
+                }
+                result
+            }
+        }
+
+        impl PowSaturate<$rty> for $ty {
+            type Output = $ty;
+
+            fn pow_saturate(self, rhs: $rty) -> $ty {
+                self.saturating_pow(clamp_exponent(pow_exponent(rhs)))
+            }
+        }
+
+        impl PowChecked<$rty> for &$ty {
+            type Output = $ty;
+
+            fn pow_checked(self, rhs: $rty) -> Option<$ty> {
+                (*self).pow_checked(rhs)
+            }
+        }
+
+        impl PowPanic<$rty> for &$ty {
+            type Output = $ty;
+
+            fn pow_panic(self, rhs: $rty) -> $ty {
+                (*self).pow_panic(rhs)
+            }
+        }
+
+        impl PowWrap<$rty> for &$ty {
+            type Output = $ty;
+
+            fn pow_wrap(self, rhs: $rty) -> $ty {
+                (*self).pow_wrap(rhs)
+            }
+        }
+
+        impl PowSaturate<$rty> for &$ty {
+            type Output = $ty;
+
+            fn pow_saturate(self, rhs: $rty) -> $ty {
+                (*self).pow_saturate(rhs)
+            }
+        }
+    };
+}
+
+pow_impls!(u8);
+pow_impls!(u16);
+pow_impls!(u32);
+pow_impls!(u64);
+pow_impls!(u128);
+pow_impls!(usize);
+pow_impls!(i8);
+pow_impls!(i16);
+pow_impls!(i32);
+pow_impls!(i64);
+pow_impls!(i128);
+pow_impls!(isize);
+
+/// Add two values, returning the wrapped result along with whether it overflowed
+///
+/// Unlike the policy traits, this leaves deciding what to do on overflow to the
+/// caller, e.g. to carry into the next word.
+pub trait AddOverflowing<RHS = Self> {
+    /// The result type of the addition
+    type Output;
+
+    /// add two values, return the wrapped result and whether it overflowed
+    fn add_overflowing(self, rhs: RHS) -> (Self::Output, bool);
+}
+
+/// Subtract two values, returning the wrapped result along with whether it overflowed
+pub trait SubOverflowing<RHS = Self> {
+    /// The result type of the subtraction
+    type Output;
+
+    /// subtract two values, return the wrapped result and whether it overflowed
+    fn sub_overflowing(self, rhs: RHS) -> (Self::Output, bool);
+}
+
+/// Multiply two values, returning the wrapped result along with whether it overflowed
+pub trait MulOverflowing<RHS = Self> {
+    /// The result type of the multiplication
+    type Output;
+
+    /// multiply two values, return the wrapped result and whether it overflowed
+    fn mul_overflowing(self, rhs: RHS) -> (Self::Output, bool);
+}
+
+/// Add a value to a given value in-place, wrapping, and return whether it overflowed
+pub trait AddAssignOverflowing<RHS = Self> {
+    /// add a value to a given value in-place, wrapping, return whether it overflowed
+    fn add_assign_overflowing(&mut self, rhs: RHS) -> bool;
+}
+
+/// Subtract a value from a given value in-place, wrapping, and return whether it overflowed
+pub trait SubAssignOverflowing<RHS = Self> {
+    /// subtract a value from a given value in-place, wrapping, return whether it overflowed
+    fn sub_assign_overflowing(&mut self, rhs: RHS) -> bool;
+}
+
+/// Multiply a given value by a value in-place, wrapping, and return whether it overflowed
+pub trait MulAssignOverflowing<RHS = Self> {
+    /// multiply a given value by a value in-place, wrapping, return whether it overflowed
+    fn mul_assign_overflowing(&mut self, rhs: RHS) -> bool;
+}
+
+macro_rules! overflowing_biself {
+    ($trait_overflowing:ident, $trait_assign_overflowing:ident, $fn_overflowing:ident, $fn_assign_overflowing:ident, $overflowing_fn:ident) => {
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u8);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u16);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u32);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u64);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u128);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, usize);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i8);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i16);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i32);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i64);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i128);
+        overflowing_biself!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, isize);
+    };
+    ($trait_overflowing:ident, $trait_assign_overflowing:ident, $fn_overflowing:ident, $fn_assign_overflowing:ident, $overflowing_fn:ident, $ty:ty) => {
+        impl $trait_overflowing<$ty> for $ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: $ty) -> ($ty, bool) {
+                self.$overflowing_fn(rhs)
+            }
+        }
+
+        impl $trait_overflowing<&$ty> for $ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: &$ty) -> ($ty, bool) {
+                self.$overflowing_fn(*rhs)
+            }
+        }
+
+        impl $trait_overflowing<$ty> for &$ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: $ty) -> ($ty, bool) {
+                self.$overflowing_fn(rhs)
+            }
+        }
+
+        impl $trait_overflowing<&$ty> for &$ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: &$ty) -> ($ty, bool) {
+                self.$overflowing_fn(*rhs)
+            }
+        }
+
+        impl $trait_assign_overflowing<$ty> for $ty {
+            fn $fn_assign_overflowing(&mut self, rhs: $ty) -> bool {
+                let (result, overflowed) = self.$overflowing_fn(rhs);
+                *self = result;
+                overflowed
+            }
+        }
+
+        impl $trait_assign_overflowing<&$ty> for $ty {
+            fn $fn_assign_overflowing(&mut self, rhs: &$ty) -> bool {
+                self.$fn_assign_overflowing(*rhs)
+            }
+        }
+    }
+}
+
+overflowing_biself!(AddOverflowing, AddAssignOverflowing, add_overflowing, add_assign_overflowing, overflowing_add);
+overflowing_biself!(SubOverflowing, SubAssignOverflowing, sub_overflowing, sub_assign_overflowing, overflowing_sub);
+overflowing_biself!(MulOverflowing, MulAssignOverflowing, mul_overflowing, mul_assign_overflowing, overflowing_mul);
+
+/// Shift left, returning the result along with whether the number of bits
+/// shifted was not lower than the width of the type
+///
+/// As with `overflowing_shl`, the number of bits is masked to the width of the
+/// type then. Negative numbers of bits are out of range as well.
+pub trait ShlOverflowing<RHS=usize> {
+    /// the return type of our shift operation
+    type Output;
+    /// shift left, return the result and whether the number of bits shifted
+    /// was too high
+    fn shl_overflowing(self, rhs: RHS) -> (Self::Output, bool);
+}
+
+/// Shift left in place, returning whether the number of bits shifted was not
+/// lower than the width of the type
+pub trait ShlAssignOverflowing<RHS=usize> {
+    /// shift left in place, return whether the number of bits shifted was too
+    /// high
+    fn shl_assign_overflowing(&mut self, rhs: RHS) -> bool;
+}
+
+/// Shift right, returning the result along with whether the number of bits
+/// shifted was not lower than the width of the type
+///
+/// As with `overflowing_shr`, the number of bits is masked to the width of the
+/// type then. Negative numbers of bits are out of range as well.
+pub trait ShrOverflowing<RHS=usize> {
+    /// the return type of our shift operation
+    type Output;
+    /// shift right, return the result and whether the number of bits shifted
+    /// was too high
+    fn shr_overflowing(self, rhs: RHS) -> (Self::Output, bool);
+}
+
+/// Shift right in place, returning whether the number of bits shifted was not
+/// lower than the width of the type
+pub trait ShrAssignOverflowing<RHS=usize> {
+    /// shift right in place, return whether the number of bits shifted was too
+    /// high
+    fn shr_assign_overflowing(&mut self, rhs: RHS) -> bool;
+}
+
+macro_rules! overflowing_shifts {
+    (@$trait_overflowing:ident, $trait_assign_overflowing:ident, $fn_overflowing:ident, $fn_assign_overflowing:ident, $overflowing_fn:ident) => {
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u8);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u16);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u32);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u64);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, u128);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, usize);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i8);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i16);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i32);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i64);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, i128);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, isize);
+    };
+    ($trait_overflowing:ident, $trait_assign_overflowing:ident, $fn_overflowing:ident, $fn_assign_overflowing:ident, $overflowing_fn:ident, $ty:ty) => {
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, u8);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, u16);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, u32);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, u64);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, u128);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, usize);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, i8);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, i16);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, i32);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, i64);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, i128);
+        overflowing_shifts!($trait_overflowing, $trait_assign_overflowing, $fn_overflowing, $fn_assign_overflowing, $overflowing_fn, $ty, isize);
+    };
+    ($trait_overflowing:ident, $trait_assign_overflowing:ident, $fn_overflowing:ident, $fn_assign_overflowing:ident, $overflowing_fn:ident, $ty:ty, $rty:ty) => {
+        impl $trait_overflowing<$rty> for $ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: $rty) -> ($ty, bool) {
+                let (n, overflowed) = rhs.overflowing_bits(<$ty>::BITS);
+                (self.$overflowing_fn(n).0, overflowed)
+            }
+        }
+
+        impl $trait_overflowing<&$rty> for $ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: &$rty) -> ($ty, bool) {
+                $trait_overflowing::$fn_overflowing(self, *rhs)
+            }
+        }
+
+        impl $trait_overflowing<$rty> for &$ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: $rty) -> ($ty, bool) {
+                $trait_overflowing::$fn_overflowing(*self, rhs)
+            }
+        }
+
+        impl $trait_overflowing<&$rty> for &$ty {
+            type Output = $ty;
+
+            fn $fn_overflowing(self, rhs: &$rty) -> ($ty, bool) {
+                $trait_overflowing::$fn_overflowing(*self, *rhs)
+            }
+        }
+
+        impl $trait_assign_overflowing<$rty> for $ty {
+            fn $fn_assign_overflowing(&mut self, rhs: $rty) -> bool {
+                let (result, overflowed) = $trait_overflowing::$fn_overflowing(*self, rhs);
+                *self = result;
+                overflowed
+            }
+        }
+
+        impl $trait_assign_overflowing<&$rty> for $ty {
+            fn $fn_assign_overflowing(&mut self, rhs: &$rty) -> bool {
+                self.$fn_assign_overflowing(*rhs)
+            }
+        }
+    }
+}
+
+overflowing_shifts!(@ShlOverflowing, ShlAssignOverflowing, shl_overflowing, shl_assign_overflowing, overflowing_shl);
+overflowing_shifts!(@ShrOverflowing, ShrAssignOverflowing, shr_overflowing, shr_assign_overflowing, overflowing_shr);
+
+/// Negate a value, returning the wrapped result along with whether it overflowed
+///
+/// For unsigned integers, this overflows for every value but zero.
+pub trait NegOverflowing {
+    /// the result type of the negation
+    type Output;
+    /// negate a value, return the wrapped result and whether it overflowed
+    fn neg_overflowing(self) -> (Self::Output, bool);
+}
+
+macro_rules! neg_overflowing {
+    ($ty:ty) => {
+        impl NegOverflowing for $ty {
+            type Output = $ty;
+
+            fn neg_overflowing(self) -> ($ty, bool) {
+                self.overflowing_neg()
+            }
+        }
+
+        impl NegOverflowing for &$ty {
+            type Output = $ty;
+
+            fn neg_overflowing(self) -> ($ty, bool) {
+                self.overflowing_neg()
+            }
+        }
+    }
+}
+
+neg_overflowing!(u8);
+neg_overflowing!(u16);
+neg_overflowing!(u32);
+neg_overflowing!(u64);
+neg_overflowing!(u128);
+neg_overflowing!(usize);
+neg_overflowing!(i8);
+neg_overflowing!(i16);
+neg_overflowing!(i32);
+neg_overflowing!(i64);
+neg_overflowing!(i128);
+neg_overflowing!(isize);
+
+// Addition and multiplication of unsigned non-zero integers, wrapping around
+// modulo `MAX` (so `MAX` takes the place of zero). `std` doesn't implement
+// `Add` and `Mul` for them, so with the `specialization` feature, the blanket
+// impls are in the way of ours.
+macro_rules! nonzero_unsigned {
+    ($ty:ty, $int:ty) => {
+        nonzero_unsigned!($ty, $int, $ty);
+        nonzero_unsigned!($ty, $int, $int);
+
+        impl MulPanic for $ty {
+            type Output = $ty;
+
+            fn mul_panic(self, rhs: $ty) -> $ty {
+                if let Some(x) = self.checked_mul(rhs) { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl MulWrap for $ty {
+            type Output = $ty;
+
+            fn mul_wrap(self, rhs: $ty) -> $ty {
+                // double and add modulo `MAX`, starting from `MAX` (i.e. zero)
+                let (mut product, mut x, mut bits) = (<$ty>::MAX, self, rhs.get());
+                while bits != 0 {
+                    if bits & 1 == 1 {
+                        product = product.add_wrap(x);
+                    }
+                    x = x.add_wrap(x);
+                    bits >>= 1;
+                }
+                product
+            }
+        }
+
+        impl MulSaturate for $ty {
+            type Output = $ty;
+
+            fn mul_saturate(self, rhs: $ty) -> $ty {
+                self.saturating_mul(rhs)
+            }
+        }
+
+        impl MulAssignPanic for $ty {
+            fn mul_assign_panic(&mut self, rhs: $ty) {
+                *self = self.mul_panic(rhs);
+            }
+        }
+
+        impl MulAssignWrap for $ty {
+            fn mul_assign_wrap(&mut self, rhs: $ty) {
+                *self = self.mul_wrap(rhs);
+            }
+        }
+
+        impl MulAssignSaturate for $ty {
+            fn mul_assign_saturate(&mut self, rhs: $ty) {
+                *self = self.mul_saturate(rhs);
+            }
+        }
+    };
+    ($ty:ty, $int:ty, $rty:ty) => {
+        impl AddPanic<$rty> for $ty {
+            type Output = $ty;
+
+            fn add_panic(self, rhs: $rty) -> $ty {
+                if let Some(x) = self.checked_add(<$int>::from(rhs)) { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl AddWrap<$rty> for $ty {
+            type Output = $ty;
+
+            fn add_wrap(self, rhs: $rty) -> $ty {
+                // skip zero, going from `MAX` to 1
+                match self.get().overflowing_add(<$int>::from(rhs)) {
+                    (x, false) => <$ty>::new(x).unwrap(),
+                    (x, true) => <$ty>::new(x + 1).unwrap(),
+                }
+            }
+        }
+
+        impl AddSaturate<$rty> for $ty {
+            type Output = $ty;
+
+            fn add_saturate(self, rhs: $rty) -> $ty {
+                self.saturating_add(<$int>::from(rhs))
+            }
+        }
+
+        impl AddAssignPanic<$rty> for $ty {
+            fn add_assign_panic(&mut self, rhs: $rty) {
+                *self = self.add_panic(rhs);
+            }
+        }
+
+        impl AddAssignWrap<$rty> for $ty {
+            fn add_assign_wrap(&mut self, rhs: $rty) {
+                *self = self.add_wrap(rhs);
+            }
+        }
+
+        impl AddAssignSaturate<$rty> for $ty {
+            fn add_assign_saturate(&mut self, rhs: $rty) {
+                *self = self.add_saturate(rhs);
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "specialization"))]
+mod nonzero_unsigned_impls {
+    use super::*;
+
+    nonzero_unsigned!(core::num::NonZeroU8, u8);
+    nonzero_unsigned!(core::num::NonZeroU16, u16);
+    nonzero_unsigned!(core::num::NonZeroU32, u32);
+    nonzero_unsigned!(core::num::NonZeroU64, u64);
+    nonzero_unsigned!(core::num::NonZeroU128, u128);
+    nonzero_unsigned!(core::num::NonZeroUsize, usize);
+}
+
+// dividing an integer by a non-zero one can't overflow
+macro_rules! nonzero_div {
+    ($int:ty, $ty:ty) => {
+        nonzero_div!($int, $ty, DivPanic, div_panic, DivAssignPanic, div_assign_panic, Div, div);
+        nonzero_div!($int, $ty, DivWrap, div_wrap, DivAssignWrap, div_assign_wrap, Div, div);
+        nonzero_div!($int, $ty, DivSaturate, div_saturate, DivAssignSaturate, div_assign_saturate, Div, div);
+        nonzero_div!($int, $ty, RemPanic, rem_panic, RemAssignPanic, rem_assign_panic, Rem, rem);
+        nonzero_div!($int, $ty, RemWrap, rem_wrap, RemAssignWrap, rem_assign_wrap, Rem, rem);
+        nonzero_div!($int, $ty, RemSaturate, rem_saturate, RemAssignSaturate, rem_assign_saturate, Rem, rem);
+    };
+    ($int:ty, $ty:ty, $trait_name:ident, $fn_name:ident, $trait_assign:ident,
+     $fn_assign:ident, $std_trait:ident, $std_fn:ident) => {
+        impl $trait_name<$ty> for $int {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $int;
+
+            fn $fn_name(self, rhs: $ty) -> $int {
+                $std_trait::$std_fn(self, rhs)
+            }
+        }
+
+        impl $trait_assign<$ty> for $int {
+            fn $fn_assign(&mut self, rhs: $ty) {
+                *self = $std_trait::$std_fn(*self, rhs);
+            }
+        }
+    };
+}
+
+nonzero_div!(u8, core::num::NonZeroU8);
+nonzero_div!(u16, core::num::NonZeroU16);
+nonzero_div!(u32, core::num::NonZeroU32);
+nonzero_div!(u64, core::num::NonZeroU64);
+nonzero_div!(u128, core::num::NonZeroU128);
+nonzero_div!(usize, core::num::NonZeroUsize);
+
+macro_rules! nonzero_signed {
+    ($ty:ty) => {
+        impl NegPanic for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_panic(self) -> $ty {
+                if let Some(x) = self.checked_neg() { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl NegWrap for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_wrap(self) -> $ty {
+                self.wrapping_neg()
+            }
+        }
+
+        impl NegSaturate for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn neg_saturate(self) -> $ty {
+                self.saturating_neg()
+            }
+        }
+
+        impl AbsPanic for $ty {
+            fn abs_panic(self) -> Self {
+                if let Some(x) = self.checked_abs() { x }
+                else { panic!("arithmetic overflow") }
+            }
+        }
+
+        impl AbsWrap for $ty {
+            fn abs_wrap(self) -> Self {
+                self.wrapping_abs()
+            }
+        }
+
+        impl AbsSaturate for $ty {
+            fn abs_saturate(self) -> Self {
+                self.saturating_abs()
+            }
+        }
+    };
+}
+
+nonzero_signed!(core::num::NonZeroI8);
+nonzero_signed!(core::num::NonZeroI16);
+nonzero_signed!(core::num::NonZeroI32);
+nonzero_signed!(core::num::NonZeroI64);
+nonzero_signed!(core::num::NonZeroI128);
+nonzero_signed!(core::num::NonZeroIsize);
+
+/// Sum up an iterator, panicking on overflow
+///
+/// This does the same as the `std::iter::Sum` trait for most types.
+/// it is specialized for integer types to panic on overflow.
+pub trait SumPanic<A = Self>: Sized {
+    /// sum up the elements of an iterator, panicking on overflow
+    fn sum_panic<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Sum up an iterator, wrapping on overflow
+///
+/// This does the same as the `std::iter::Sum` trait for most types.
+/// it is specialized for integer types to wrap on overflow.
+pub trait SumWrap<A = Self>: Sized {
+    /// sum up the elements of an iterator, wrapping on overflow
+    fn sum_wrap<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Sum up an iterator, saturating on overflow
+///
+/// This does the same as the `std::iter::Sum` trait for most types.
+/// it is specialized for integer types to saturate on overflow. Like a
+/// sequence of saturating additions, the result depends on the order of the
+/// elements once the sum saturated, e.g. `[i8::MAX, 1, -1]` sums up to
+/// `i8::MAX - 1`.
+pub trait SumSaturate<A = Self>: Sized {
+    /// sum up the elements of an iterator, saturating on overflow
+    fn sum_saturate<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Multiply the elements of an iterator, panicking on overflow
+///
+/// This does the same as the `std::iter::Product` trait for most types.
+/// it is specialized for integer types to panic on overflow.
+pub trait ProductPanic<A = Self>: Sized {
+    /// multiply the elements of an iterator, panicking on overflow
+    fn product_panic<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Multiply the elements of an iterator, wrapping on overflow
+///
+/// This does the same as the `std::iter::Product` trait for most types.
+/// it is specialized for integer types to wrap on overflow.
+pub trait ProductWrap<A = Self>: Sized {
+    /// multiply the elements of an iterator, wrapping on overflow
+    fn product_wrap<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+/// Multiply the elements of an iterator, saturating on overflow
+///
+/// This does the same as the `std::iter::Product` trait for most types.
+/// it is specialized for integer types to saturate on overflow.
+pub trait ProductSaturate<A = Self>: Sized {
+    /// multiply the elements of an iterator, saturating on overflow
+    fn product_saturate<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+
+macro_rules! iter_fold {
+    ($trait_name:ident, $orig_trait:ident, $orig_fn:ident, $fn_name:ident, $op:path, $init:expr) => {
+        #[cfg(feature = "specialization")]
+        impl<T, A> $trait_name<A> for T where T: core::iter::$orig_trait<A> {
+            default fn $fn_name<I: Iterator<Item = A>>(iter: I) -> Self {
+                core::iter::$orig_trait::$orig_fn(iter)
+            }
+        }
+
+        iter_fold!($trait_name, $fn_name, $op, $init, u8);
+        iter_fold!($trait_name, $fn_name, $op, $init, u16);
+        iter_fold!($trait_name, $fn_name, $op, $init, u32);
+        iter_fold!($trait_name, $fn_name, $op, $init, u64);
+        iter_fold!($trait_name, $fn_name, $op, $init, u128);
+        iter_fold!($trait_name, $fn_name, $op, $init, usize);
+        iter_fold!($trait_name, $fn_name, $op, $init, i8);
+        iter_fold!($trait_name, $fn_name, $op, $init, i16);
+        iter_fold!($trait_name, $fn_name, $op, $init, i32);
+        iter_fold!($trait_name, $fn_name, $op, $init, i64);
+        iter_fold!($trait_name, $fn_name, $op, $init, i128);
+        iter_fold!($trait_name, $fn_name, $op, $init, isize);
+    };
+    ($trait_name:ident, $fn_name:ident, $op:path, $init:expr, $ty:ty) => {
+        impl $trait_name for $ty {
+            fn $fn_name<I: Iterator<Item = $ty>>(iter: I) -> Self {
+                iter.fold($init, |acc: $ty, x| $op(acc, x))
+            }
+        }
+
+        impl<'a> $trait_name<&'a $ty> for $ty {
+            fn $fn_name<I: Iterator<Item = &'a $ty>>(iter: I) -> Self {
+                iter.fold($init, |acc: $ty, x| $op(acc, *x))
+            }
+        }
+    };
+}
+
+iter_fold!(SumPanic, Sum, sum, sum_panic, AddPanic::add_panic, 0);
+iter_fold!(SumWrap, Sum, sum, sum_wrap, AddWrap::add_wrap, 0);
+iter_fold!(SumSaturate, Sum, sum, sum_saturate, AddSaturate::add_saturate, 0);
+iter_fold!(ProductPanic, Product, product, product_panic, MulPanic::mul_panic, 1);
+iter_fold!(ProductWrap, Product, product, product_wrap, MulWrap::mul_wrap, 1);
+iter_fold!(ProductSaturate, Product, product, product_saturate, MulSaturate::mul_saturate, 1);
+
+/// Wrap on overflow, as a type-level policy (see `Policy`)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Wrap;
+
+/// Panic on overflow, as a type-level policy (see `Policy`)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Panic;
+
+/// Saturate on overflow, as a type-level policy (see `Policy`)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Saturate;
+
+macro_rules! policy_fn {
+    (decl bin $fn_name:ident, $tw:ident, $tp:ident, $ts:ident) => {
+        #[doc = concat!("apply `", stringify!($fn_name), "` to two values under this policy")]
+        fn $fn_name<L, R>(l: L, r: R) -> <L as $tw<R>>::Output
+        where L: $tw<R> + $tp<R, Output = <L as $tw<R>>::Output>
+                 + $ts<R, Output = <L as $tw<R>>::Output>;
+    };
+    (impl bin $fn_name:ident, $tw:ident, $tp:ident, $ts:ident, $t:ident::$f:ident) => {
+        fn $fn_name<L, R>(l: L, r: R) -> <L as $tw<R>>::Output
+        where L: $tw<R> + $tp<R, Output = <L as $tw<R>>::Output>
+                 + $ts<R, Output = <L as $tw<R>>::Output> {
+            $t::$f(l, r)
+        }
+    };
+    (decl assign $fn_name:ident, $tw:ident, $tp:ident, $ts:ident) => {
+        #[doc = concat!("apply `", stringify!($fn_name), "` to a value in-place under this policy")]
+        fn $fn_name<L, R>(l: &mut L, r: R)
+        where L: $tw<R> + $tp<R> + $ts<R>;
+    };
+    (impl assign $fn_name:ident, $tw:ident, $tp:ident, $ts:ident, $t:ident::$f:ident) => {
+        fn $fn_name<L, R>(l: &mut L, r: R)
+        where L: $tw<R> + $tp<R> + $ts<R> {
+            $t::$f(l, r)
+        }
+    };
+    (decl neg $fn_name:ident, $tw:ident, $tp:ident, $ts:ident) => {
+        /// negate a value under this policy
+        fn $fn_name<T>(t: T) -> <T as $tw>::Output
+        where T: $tw + $tp<Output = <T as $tw>::Output>
+                 + $ts<Output = <T as $tw>::Output>;
+    };
+    (impl neg $fn_name:ident, $tw:ident, $tp:ident, $ts:ident, $t:ident::$f:ident) => {
+        fn $fn_name<T>(t: T) -> <T as $tw>::Output
+        where T: $tw + $tp<Output = <T as $tw>::Output>
+                 + $ts<Output = <T as $tw>::Output> {
+            $t::$f(t)
+        }
+    };
+    (decl iter $fn_name:ident, $tw:ident, $tp:ident, $ts:ident) => {
+        #[doc = concat!("compute the ", stringify!($fn_name), " of an iterator under this policy")]
+        fn $fn_name<S, I>(iter: I) -> S
+        where I: Iterator, S: $tw<I::Item> + $tp<I::Item> + $ts<I::Item>;
+    };
+    (impl iter $fn_name:ident, $tw:ident, $tp:ident, $ts:ident, $t:ident::$f:ident) => {
+        fn $fn_name<S, I>(iter: I) -> S
+        where I: Iterator, S: $tw<I::Item> + $tp<I::Item> + $ts<I::Item> {
+            $t::$f(iter)
+        }
+    };
+    (decl abs $fn_name:ident, $tw:ident, $tp:ident, $ts:ident) => {
+        /// compute the absolute value under this policy
+        fn $fn_name<T>(t: T) -> T where T: $tw + $tp + $ts;
+    };
+    (impl abs $fn_name:ident, $tw:ident, $tp:ident, $ts:ident, $t:ident::$f:ident) => {
+        fn $fn_name<T>(t: T) -> T where T: $tw + $tp + $ts {
+            $t::$f(t)
+        }
+    };
+}
+
+macro_rules! policies {
+    ($($kind:ident $fn_name:ident: $tw:ident::$fw:ident, $tp:ident::$fp:ident, $ts:ident::$fs:ident;)*) => {
+        /// An overflow policy as a type, so code can be generic over how
+        /// overflow is handled
+        ///
+        /// Each operation delegates to the respective `*Wrap`, `*Panic` or
+        /// `*Saturate` trait, so it works for all types that implement all
+        /// three of them. This is what `#[overflow(inherit)]` functions use.
+        pub trait Policy {
+            $(policy_fn!(decl $kind $fn_name, $tw, $tp, $ts);)*
+        }
+
+        impl Policy for Wrap {
+            $(policy_fn!(impl $kind $fn_name, $tw, $tp, $ts, $tw::$fw);)*
+        }
+
+        impl Policy for Panic {
+            $(policy_fn!(impl $kind $fn_name, $tw, $tp, $ts, $tp::$fp);)*
+        }
+
+        impl Policy for Saturate {
+            $(policy_fn!(impl $kind $fn_name, $tw, $tp, $ts, $ts::$fs);)*
+        }
+    };
+}
+
+policies! {
+    bin add: AddWrap::add_wrap, AddPanic::add_panic, AddSaturate::add_saturate;
+    bin sub: SubWrap::sub_wrap, SubPanic::sub_panic, SubSaturate::sub_saturate;
+    bin mul: MulWrap::mul_wrap, MulPanic::mul_panic, MulSaturate::mul_saturate;
+    bin div: DivWrap::div_wrap, DivPanic::div_panic, DivSaturate::div_saturate;
+    bin rem: RemWrap::rem_wrap, RemPanic::rem_panic, RemSaturate::rem_saturate;
+    bin shl: ShlWrap::shl_wrap, ShlPanic::shl_panic, ShlSaturate::shl_saturate;
+    bin shr: ShrWrap::shr_wrap, ShrPanic::shr_panic, ShrSaturate::shr_saturate;
+    bin pow: PowWrap::pow_wrap, PowPanic::pow_panic, PowSaturate::pow_saturate;
+    neg neg: NegWrap::neg_wrap, NegPanic::neg_panic, NegSaturate::neg_saturate;
+    abs abs: AbsWrap::abs_wrap, AbsPanic::abs_panic, AbsSaturate::abs_saturate;
+    iter sum: SumWrap::sum_wrap, SumPanic::sum_panic, SumSaturate::sum_saturate;
+    iter product: ProductWrap::product_wrap, ProductPanic::product_panic,
+        ProductSaturate::product_saturate;
+    assign add_assign: AddAssignWrap::add_assign_wrap, AddAssignPanic::add_assign_panic,
+        AddAssignSaturate::add_assign_saturate;
+    assign sub_assign: SubAssignWrap::sub_assign_wrap, SubAssignPanic::sub_assign_panic,
+        SubAssignSaturate::sub_assign_saturate;
+    assign mul_assign: MulAssignWrap::mul_assign_wrap, MulAssignPanic::mul_assign_panic,
+        MulAssignSaturate::mul_assign_saturate;
+    assign div_assign: DivAssignWrap::div_assign_wrap, DivAssignPanic::div_assign_panic,
+        DivAssignSaturate::div_assign_saturate;
+    assign rem_assign: RemAssignWrap::rem_assign_wrap, RemAssignPanic::rem_assign_panic,
+        RemAssignSaturate::rem_assign_saturate;
+    assign shl_assign: ShlAssignWrap::shl_assign_wrap, ShlAssignPanic::shl_assign_panic,
+        ShlAssignSaturate::shl_assign_saturate;
+    assign shr_assign: ShrAssignWrap::shr_assign_wrap, ShrAssignPanic::shr_assign_panic,
+        ShrAssignSaturate::shr_assign_saturate;
+}
+
+/// Arithmetic under the overflow policy `P`
+///
+/// This lets code be generic over the policy with a single bound, e.g.
+/// `T: Arith<P>` instead of requiring the `*Wrap`, `*Panic` and `*Saturate`
+/// traits of every operation. The methods delegate to `Policy`, so they
+/// behave the same as the per-policy traits. Shifts take the number of bits
+/// as `u32`, like the shift methods of the integer types. For unsigned types,
+/// negation is the same as subtracting from zero. Floats are plain IEEE
+/// operations under every policy (see `AddFinite` for a policy that isn't),
+/// and shifting them multiplies or divides by a power of two.
+///
+/// Where the `std::ops` traits are in scope as well, the method names are
+/// ambiguous, so call them as e.g. `Arith::<P>::add(a, b)`.
+pub trait Arith<P: Policy>: Sized {
+    /// add two values under the policy `P`
+    fn add(self, rhs: Self) -> Self;
+    /// subtract two values under the policy `P`
+    fn sub(self, rhs: Self) -> Self;
+    /// multiply two values under the policy `P`
+    fn mul(self, rhs: Self) -> Self;
+    /// divide two values under the policy `P`
+    fn div(self, rhs: Self) -> Self;
+    /// divide two values and get the remainder under the policy `P`
+    fn rem(self, rhs: Self) -> Self;
+    /// shift left under the policy `P`
+    fn shl(self, rhs: u32) -> Self;
+    /// shift right under the policy `P`
+    fn shr(self, rhs: u32) -> Self;
+    /// negate a value under the policy `P`
+    fn neg(self) -> Self;
+    /// compute the absolute value under the policy `P`
+    fn abs(self) -> Self;
+    /// add a value to a given value in-place under the policy `P`
+    fn add_assign(&mut self, rhs: Self);
+    /// subtract a value from a given value in-place under the policy `P`
+    fn sub_assign(&mut self, rhs: Self);
+    /// multiply a given value by a value in-place under the policy `P`
+    fn mul_assign(&mut self, rhs: Self);
+    /// divide a given value by a value in-place under the policy `P`
+    fn div_assign(&mut self, rhs: Self);
+    /// replace a given value by the remainder of dividing it by a value
+    /// in-place under the policy `P`
+    fn rem_assign(&mut self, rhs: Self);
+    /// shift left in-place under the policy `P`
+    fn shl_assign(&mut self, rhs: u32);
+    /// shift right in-place under the policy `P`
+    fn shr_assign(&mut self, rhs: u32);
+}
+
+macro_rules! arith {
+    ($ty:ty, |$x:ident| $neg:expr) => {
+        arith!($ty, |$x| $neg, |x, n| P::shl(x, n), |x, n| P::shr(x, n));
+    };
+    ($ty:ty, |$x:ident| $neg:expr, |$l:ident, $m:ident| $shl:expr, |$r:ident, $n:ident| $shr:expr) => {
+        impl<P: Policy> Arith<P> for $ty {
+            fn add(self, rhs: Self) -> Self { P::add(self, rhs) }
+            fn sub(self, rhs: Self) -> Self { P::sub(self, rhs) }
+            fn mul(self, rhs: Self) -> Self { P::mul(self, rhs) }
+            fn div(self, rhs: Self) -> Self { P::div(self, rhs) }
+            fn rem(self, rhs: Self) -> Self { P::rem(self, rhs) }
+            fn shl(self, rhs: u32) -> Self { let ($l, $m) = (self, rhs); $shl }
+            fn shr(self, rhs: u32) -> Self { let ($r, $n) = (self, rhs); $shr }
+            fn neg(self) -> Self { let $x = self; $neg }
+            fn abs(self) -> Self { P::abs(self) }
+            fn add_assign(&mut self, rhs: Self) { P::add_assign(self, rhs) }
+            fn sub_assign(&mut self, rhs: Self) { P::sub_assign(self, rhs) }
+            fn mul_assign(&mut self, rhs: Self) { P::mul_assign(self, rhs) }
+            fn div_assign(&mut self, rhs: Self) { P::div_assign(self, rhs) }
+            fn rem_assign(&mut self, rhs: Self) { P::rem_assign(self, rhs) }
+            fn shl_assign(&mut self, rhs: u32) { *self = Arith::<P>::shl(*self, rhs) }
+            fn shr_assign(&mut self, rhs: u32) { *self = Arith::<P>::shr(*self, rhs) }
+        }
+    };
+}
+
+arith!(u8, |x| P::sub(0, x));
+arith!(u16, |x| P::sub(0, x));
+arith!(u32, |x| P::sub(0, x));
+arith!(u64, |x| P::sub(0, x));
+arith!(u128, |x| P::sub(0, x));
+arith!(usize, |x| P::sub(0, x));
+arith!(i8, |x| P::neg(x));
+arith!(i16, |x| P::neg(x));
+arith!(i32, |x| P::neg(x));
+arith!(i64, |x| P::neg(x));
+arith!(i128, |x| P::neg(x));
+arith!(isize, |x| P::neg(x));
+
+/// Divide two values under the overflow policy `P`, handling zero divisors
+/// as the policy `Z` does
+///
+/// This is what `#[overflow(.., div_by_zero = ..)]` rewrites divisions to,
+/// e.g. `DivWith::<Wrap, Saturate>::div_with(x, y)` wraps `MIN / -1` but
+/// saturates `x / 0`.
+pub trait DivWith<P, Z, RHS = Self> {
+    /// The result type of the division
+    type Output;
+    /// divide two values under `P`, or `Z` for a zero divisor
+    fn div_with(self, rhs: RHS) -> Self::Output;
+}
+
+/// Divide two values and get the remainder under the overflow policy `P`,
+/// handling zero divisors as the policy `Z` does
+pub trait RemWith<P, Z, RHS = Self> {
+    /// The result type of the division remainder
+    type Output;
+    /// divide two values and get the remainder under `P`, or `Z` for a zero
+    /// divisor
+    fn rem_with(self, rhs: RHS) -> Self::Output;
+}
 
+/// Divide a given value by a value in-place under the overflow policy `P`,
+/// handling zero divisors as the policy `Z` does
+pub trait DivAssignWith<P, Z, RHS = Self> {
+    /// divide a given value by a value in-place under `P`, or `Z` for a zero
+    /// divisor
+    fn div_assign_with(&mut self, rhs: RHS);
+}
 
-saturate_shl_signed!(i8, std::i8::MAX, std::i8::MIN, 7);
-saturate_shl_signed!(i16, std::i16::MAX, std::i16::MIN, 15);
-saturate_shl_signed!(i32, std::i32::MAX, std::i32::MIN, 31);
-saturate_shl_signed!(i64, std::i64::MAX, std::i64::MIN, 64);
-saturate_shl_signed!(isize, std::isize::MAX, std::isize::MIN, ISIZE_BITS);
+/// Replace a given value by the remainder of dividing it by a value in-place
+/// under the overflow policy `P`, handling zero divisors as the policy `Z` does
+pub trait RemAssignWith<P, Z, RHS = Self> {
+    /// replace a given value by the division remainder in-place under `P`, or
+    /// `Z` for a zero divisor
+    fn rem_assign_with(&mut self, rhs: RHS);
+}
 
-/// Negate a value, panic on overflow
-///
-/// This does the same as the `std::ops::Neg` trait for most types.
-/// it is specialized for integer types to panic on overflow.
-pub trait NegPanic {
-    /// the result type of the negation
-    type Output;
-    /// negate a value, panic on overflow
-    fn neg_panic(self) -> Self::Output;
+#[cfg(feature = "specialization")]
+impl<P, Z, T, R> DivWith<P, Z, R> for T where T: Div<R> {
+    type Output = <T as Div<R>>::Output;
+    default fn div_with(self, rhs: R) -> Self::Output {
+        Div::div(self, rhs)
+    }
 }
 
 #[cfg(feature = "specialization")]
-impl<T> NegPanic for T where T: Neg {
-    type Output = <T as Neg>::Output;
-    default fn neg_panic(self) -> Self::Output {
-        -self
+impl<P, Z, T, R> RemWith<P, Z, R> for T where T: Rem<R> {
+    type Output = <T as Rem<R>>::Output;
+    default fn rem_with(self, rhs: R) -> Self::Output {
+        Rem::rem(self, rhs)
     }
 }
 
-macro_rules! neg_panic {
+#[cfg(feature = "specialization")]
+impl<P, Z, T, R> DivAssignWith<P, Z, R> for T where T: DivAssign<R> {
+    default fn div_assign_with(&mut self, rhs: R) {
+        DivAssign::div_assign(self, rhs)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<P, Z, T, R> RemAssignWith<P, Z, R> for T where T: RemAssign<R> {
+    default fn rem_assign_with(&mut self, rhs: R) {
+        RemAssign::rem_assign(self, rhs)
+    }
+}
+
+macro_rules! div_with {
     ($ty:ty) => {
-        impl NegPanic for $ty {
+        impl<P: Policy, Z: Policy> DivWith<P, Z> for $ty {
             #[cfg(not(feature = "specialization"))]
             type Output = $ty;
 
-            fn neg_panic(self) -> Self::Output {
-                if let Some(x) = self.checked_neg() { x }
-                else { panic!("arithmetic overflow") }
+            fn div_with(self, rhs: $ty) -> $ty {
+                if rhs == 0 { Z::div(self, rhs) } else { P::div(self, rhs) }
             }
         }
-    }
+
+        impl<P: Policy, Z: Policy> DivWith<P, Z, &$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn div_with(self, rhs: &$ty) -> $ty {
+                DivWith::<P, Z>::div_with(self, *rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemWith<P, Z> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn rem_with(self, rhs: $ty) -> $ty {
+                if rhs == 0 { Z::rem(self, rhs) } else { P::rem(self, rhs) }
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemWith<P, Z, &$ty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn rem_with(self, rhs: &$ty) -> $ty {
+                RemWith::<P, Z>::rem_with(self, *rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> DivWith<P, Z, $ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn div_with(self, rhs: $ty) -> $ty {
+                DivWith::<P, Z>::div_with(*self, rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> DivWith<P, Z, &$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn div_with(self, rhs: &$ty) -> $ty {
+                DivWith::<P, Z>::div_with(*self, *rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemWith<P, Z, $ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn rem_with(self, rhs: $ty) -> $ty {
+                RemWith::<P, Z>::rem_with(*self, rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemWith<P, Z, &$ty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn rem_with(self, rhs: &$ty) -> $ty {
+                RemWith::<P, Z>::rem_with(*self, *rhs)
+            }
+        }
+
+        impl<P: Policy, Z: Policy> DivAssignWith<P, Z> for $ty {
+            fn div_assign_with(&mut self, rhs: $ty) {
+                *self = DivWith::<P, Z>::div_with(*self, rhs);
+            }
+        }
+
+        impl<P: Policy, Z: Policy> DivAssignWith<P, Z, &$ty> for $ty {
+            fn div_assign_with(&mut self, rhs: &$ty) {
+                *self = DivWith::<P, Z>::div_with(*self, *rhs);
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemAssignWith<P, Z> for $ty {
+            fn rem_assign_with(&mut self, rhs: $ty) {
+                *self = RemWith::<P, Z>::rem_with(*self, rhs);
+            }
+        }
+
+        impl<P: Policy, Z: Policy> RemAssignWith<P, Z, &$ty> for $ty {
+            fn rem_assign_with(&mut self, rhs: &$ty) {
+                *self = RemWith::<P, Z>::rem_with(*self, *rhs);
+            }
+        }
+    };
 }
 
-neg_panic!(i8);
-neg_panic!(i16);
-neg_panic!(i32);
-neg_panic!(i64);
-neg_panic!(isize);
+div_with!(u8);
+div_with!(u16);
+div_with!(u32);
+div_with!(u64);
+div_with!(u128);
+div_with!(usize);
+div_with!(i8);
+div_with!(i16);
+div_with!(i32);
+div_with!(i64);
+div_with!(i128);
+div_with!(isize);
+
+/// Shift by the number of bits modulo the width of the type if it is out of
+/// range, as a type-level shift policy (see `ShlWith`)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Mask;
+
+/// Shift all bits out of the value if the number of bits is out of range, as a
+/// type-level shift policy (see `ShlWith`)
+///
+/// This gives 0 for left shifts (as long as the overflow policy allows it) and
+/// 0 or -1 for right shifts, as if the value was shifted one bit at a time.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ZeroFill;
 
-/// Negate a value, wrap on overflow
+/// Rotate by the number of bits modulo the width of the type if it is out of
+/// range, as a type-level shift policy (see `ShlWith`)
 ///
-/// This does the same as the `std::ops::Neg` trait for most types.
-/// it is specialized for integer types to wrap on overflow.
-pub trait NegWrap {
-    /// the result type of the negation
+/// A negative number of bits thus rotates the other way, e.g. `x << -1`
+/// rotates `x` right by one bit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rotate;
+
+/// Shift left under the overflow policy `P`, handling numbers of bits that are
+/// negative or not lower than the width of the type as the shift policy `S`
+/// (`Mask`, `ZeroFill` or `Rotate`) does
+///
+/// This is what `#[overflow(.., shifts = ..)]` rewrites left shifts to. `P`
+/// decides what happens to bits shifted out of the value, so e.g.
+/// `ShlWith::<Panic, ZeroFill>::shl_with(x, 9)` panics unless `x` is 0.
+/// Rotations never shift bits out.
+pub trait ShlWith<P, S, RHS = usize> {
+    /// The result type of the left shift
     type Output;
-    /// negate a value, wrap on overflow
-    fn neg_wrap(self) -> Self::Output;
+    /// shift left under `P`, or `S` for an out-of-range number of bits
+    fn shl_with(self, rhs: RHS) -> Self::Output;
+}
+
+/// Shift right under the overflow policy `P`, handling numbers of bits that
+/// are negative or not lower than the width of the type as the shift policy
+/// `S` does
+pub trait ShrWith<P, S, RHS = usize> {
+    /// The result type of the right shift
+    type Output;
+    /// shift right under `P`, or `S` for an out-of-range number of bits
+    fn shr_with(self, rhs: RHS) -> Self::Output;
+}
+
+/// Shift left in-place under the overflow policy `P`, handling numbers of bits
+/// that are negative or not lower than the width of the type as the shift
+/// policy `S` does
+pub trait ShlAssignWith<P, S, RHS = usize> {
+    /// shift left in-place under `P`, or `S` for an out-of-range number of
+    /// bits
+    fn shl_assign_with(&mut self, rhs: RHS);
+}
+
+/// Shift right in-place under the overflow policy `P`, handling numbers of
+/// bits that are negative or not lower than the width of the type as the shift
+/// policy `S` does
+pub trait ShrAssignWith<P, S, RHS = usize> {
+    /// shift right in-place under `P`, or `S` for an out-of-range number of
+    /// bits
+    fn shr_assign_with(&mut self, rhs: RHS);
 }
 
 #[cfg(feature = "specialization")]
-impl<T> NegWrap for T where T: Neg {
-    type Output = <T as Neg>::Output;
-    default fn neg_wrap(self) -> Self::Output {
-        -self
+impl<P, S, T, R> ShlWith<P, S, R> for T where T: Shl<R> {
+    type Output = <T as Shl<R>>::Output;
+    default fn shl_with(self, rhs: R) -> Self::Output {
+        Shl::shl(self, rhs)
     }
 }
 
-macro_rules! neg_wrap {
+#[cfg(feature = "specialization")]
+impl<P, S, T, R> ShrWith<P, S, R> for T where T: Shr<R> {
+    type Output = <T as Shr<R>>::Output;
+    default fn shr_with(self, rhs: R) -> Self::Output {
+        Shr::shr(self, rhs)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<P, S, T, R> ShlAssignWith<P, S, R> for T where T: ShlAssign<R> {
+    default fn shl_assign_with(&mut self, rhs: R) {
+        ShlAssign::shl_assign(self, rhs)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<P, S, T, R> ShrAssignWith<P, S, R> for T where T: ShrAssign<R> {
+    default fn shr_assign_with(&mut self, rhs: R) {
+        ShrAssign::shr_assign(self, rhs)
+    }
+}
+
+macro_rules! shift_with {
     ($ty:ty) => {
-        impl NegWrap for $ty {
+        shift_with!($ty, u8);
+        shift_with!($ty, u16);
+        shift_with!($ty, u32);
+        shift_with!($ty, u64);
+        shift_with!($ty, u128);
+        shift_with!($ty, usize);
+        shift_with!($ty, i8);
+        shift_with!($ty, i16);
+        shift_with!($ty, i32);
+        shift_with!($ty, i64);
+        shift_with!($ty, i128);
+        shift_with!($ty, isize);
+    };
+    ($ty:ty, $rty:ty) => {
+        shift_with!($ty, $rty, Mask, |x, n| P::shl(x, n), |x, n| P::shr(x, n));
+        shift_with!($ty, $rty, ZeroFill,
+            |x, _n| P::shl(P::shl(x, <$ty>::BITS - 1), 1u32),
+            |x, _n| P::shr(P::shr(x, <$ty>::BITS - 1), 1u32));
+        shift_with!($ty, $rty, Rotate, |x, n| x.rotate_left(n), |x, n| x.rotate_right(n));
+    };
+    ($ty:ty, $rty:ty, $shifts:ident, |$x:ident, $n:ident| $shl:expr,
+            |$y:ident, $m:ident| $shr:expr) => {
+        impl<P: Policy> ShlWith<P, $shifts, $rty> for $ty {
             #[cfg(not(feature = "specialization"))]
             type Output = $ty;
 
-            fn neg_wrap(self) -> Self::Output {
-                self.wrapping_neg()
+            fn shl_with(self, rhs: $rty) -> $ty {
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) => P::shl(self, n),
+                    Err($n) => { let $x = self; $shl }
+                }
             }
         }
-    }
-}
 
-neg_wrap!(i8);
-neg_wrap!(i16);
-neg_wrap!(i32);
-neg_wrap!(i64);
-neg_wrap!(isize);
+        impl<P: Policy> ShrWith<P, $shifts, $rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-/// Negate a value, saturate on overflow
-///
-/// This does the same as the `std::ops::Neg` trait for most types.
-/// it is specialized for integer types to saturate on overflow.
-pub trait NegSaturate {
-    /// the output type of the negation
-    type Output;
-    /// negate a value, saturate on overflow
-    fn neg_saturate(self) -> Self::Output;
-}
+            fn shr_with(self, rhs: $rty) -> $ty {
+                match rhs.bits_within(<$ty>::BITS) {
+                    Ok(n) => P::shr(self, n),
+                    Err($m) => { let $y = self; $shr }
+                }
+            }
+        }
 
-macro_rules! neg_saturate {
-    ($ty:ty, $min:expr, $max:expr) => {
-        impl NegSaturate for $ty {
-            type Output = Self;
+        impl<P: Policy> ShlAssignWith<P, $shifts, $rty> for $ty {
+            fn shl_assign_with(&mut self, rhs: $rty) {
+                *self = ShlWith::<P, $shifts, $rty>::shl_with(*self, rhs);
+            }
+        }
 
-            fn neg_saturate(self) -> Self {
-                if self == $min { $max } else { -self }
+        impl<P: Policy> ShrAssignWith<P, $shifts, $rty> for $ty {
+            fn shr_assign_with(&mut self, rhs: $rty) {
+                *self = ShrWith::<P, $shifts, $rty>::shr_with(*self, rhs);
             }
         }
-    };
-}
 
-neg_saturate!(i8, std::i8::MIN, std::i8::MAX);
-neg_saturate!(i16, std::i16::MIN, std::i16::MAX);
-neg_saturate!(i32, std::i32::MIN, std::i32::MAX);
-neg_saturate!(i64, std::i64::MIN, std::i64::MAX);
-neg_saturate!(isize, std::isize::MIN, std::isize::MAX);
+        impl<P: Policy> ShlWith<P, $shifts, $rty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-/// Compute the absolute value of `self`, panicking on overflow
-///
-/// This does the same as the `std::i*::abs(_)` methods, but panics on overflow
-pub trait AbsPanic {
-    /// compute the absolute value of `self`, panicking on overflow
-    fn abs_panic(self) -> Self;
-}
+            fn shl_with(self, rhs: $rty) -> $ty {
+                ShlWith::<P, $shifts, $rty>::shl_with(*self, rhs)
+            }
+        }
 
-/// Compute the absolute value of `self`, wrapping on overflow
-///
-/// This does the same as the `std::i*::abs(_)` methods, but wraps on overflow
-pub trait AbsWrap {
-    /// compute the absolute value of `self`, wrapping on overflow
-    fn abs_wrap(self) -> Self;
-}
+        impl<P: Policy> ShrWith<P, $shifts, $rty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-/// Compute the absolute value of `self`, saturating on overflow
-///
-/// This does the same as the `std::i*::abs(_)` methods, but saturates on
-/// overflow
-pub trait AbsSaturate {
-    /// compute the absolute value of `self`, saturating on overflow
-    fn abs_saturate(self) -> Self;
-}
+            fn shr_with(self, rhs: $rty) -> $ty {
+                ShrWith::<P, $shifts, $rty>::shr_with(*self, rhs)
+            }
+        }
 
-macro_rules! abs_unsigned {
-    ($ty:ty) => {
-        impl AbsPanic for $ty {
-            fn abs_panic(self) -> Self {
-                self
+        impl<P: Policy> ShlWith<P, $shifts, &$rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn shl_with(self, rhs: &$rty) -> $ty {
+                ShlWith::<P, $shifts, $rty>::shl_with(self, *rhs)
             }
         }
 
-        impl AbsWrap for $ty {
-            fn abs_wrap(self) -> Self {
-                self
+        impl<P: Policy> ShrWith<P, $shifts, &$rty> for $ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn shr_with(self, rhs: &$rty) -> $ty {
+                ShrWith::<P, $shifts, $rty>::shr_with(self, *rhs)
             }
         }
 
-        impl AbsSaturate for $ty {
-            fn abs_saturate(self) -> Self {
-                self
+        impl<P: Policy> ShlWith<P, $shifts, &$rty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
+
+            fn shl_with(self, rhs: &$rty) -> $ty {
+                ShlWith::<P, $shifts, $rty>::shl_with(*self, *rhs)
             }
         }
-    };
-}
 
-abs_unsigned!(u8);
-abs_unsigned!(u16);
-abs_unsigned!(u32);
-abs_unsigned!(u64);
-abs_unsigned!(usize);
+        impl<P: Policy> ShrWith<P, $shifts, &$rty> for &$ty {
+            #[cfg(not(feature = "specialization"))]
+            type Output = $ty;
 
-macro_rules! abs_signed {
-    ($ty:ty) => {
-        impl AbsPanic for $ty {
-            fn abs_panic(self) -> Self {
-                if self < 0 { 0.sub_panic(self) } else { self }
+            fn shr_with(self, rhs: &$rty) -> $ty {
+                ShrWith::<P, $shifts, $rty>::shr_with(*self, *rhs)
             }
         }
 
-        impl AbsWrap for $ty {
-            fn abs_wrap(self) -> Self {
-                if self < 0 { 0.sub_wrap(self) } else { self }
+        impl<P: Policy> ShlAssignWith<P, $shifts, &$rty> for $ty {
+            fn shl_assign_with(&mut self, rhs: &$rty) {
+                *self = ShlWith::<P, $shifts, $rty>::shl_with(*self, *rhs);
             }
         }
 
-        impl AbsSaturate for $ty {
-            fn abs_saturate(self) -> Self {
-                if self < 0 { 0.sub_saturate(self) } else { self }
+        impl<P: Policy> ShrAssignWith<P, $shifts, &$rty> for $ty {
+            fn shr_assign_with(&mut self, rhs: &$rty) {
+                *self = ShrWith::<P, $shifts, $rty>::shr_with(*self, *rhs);
             }
         }
     };
 }
 
-abs_signed!(i8);
-abs_signed!(i16);
-abs_signed!(i32);
-abs_signed!(i64);
-abs_signed!(isize);
+shift_with!(u8);
+shift_with!(u16);
+shift_with!(u32);
+shift_with!(u64);
+shift_with!(u128);
+shift_with!(usize);
+shift_with!(i8);
+shift_with!(i16);
+shift_with!(i32);
+shift_with!(i64);
+shift_with!(i128);
+shift_with!(isize);
 
 macro_rules! impls_inner {
     (op all, $ty:ty, $($gen:tt),*) => {
@@ -1468,6 +2886,8 @@ macro_rules! impls_inner {
         impls_inner!(op rem_assign, $ty, $($gen),*);
         impls_inner!(op shl_assign, $ty, $($gen),*);
         impls_inner!(op shr_assign, $ty, $($gen),*);
+        impls_inner!(op sum, $ty, $($gen),*);
+        impls_inner!(op product, $ty, $($gen),*);
     };
     (op add, $ty:ty, $($gen:tt),*) => {
         impls_inner!(bin +, Add, AddWrap, add_wrap, $ty, $($gen),*);
@@ -1549,6 +2969,16 @@ macro_rules! impls_inner {
         impls_inner!(assign >>=, ShrAssign, ShrAssignPanic, shr_assign_panic, $ty, $($gen),*);
         impls_inner!(assign >>=, ShrAssign, ShrAssignSaturate, shr_assign_saturate, $ty, $($gen),*);
     };
+    (op sum, $ty:ty, $($gen:tt),*) => {
+        impls_inner!(iter Sum, sum, SumWrap, sum_wrap, $ty, $($gen),*);
+        impls_inner!(iter Sum, sum, SumPanic, sum_panic, $ty, $($gen),*);
+        impls_inner!(iter Sum, sum, SumSaturate, sum_saturate, $ty, $($gen),*);
+    };
+    (op product, $ty:ty, $($gen:tt),*) => {
+        impls_inner!(iter Product, product, ProductWrap, product_wrap, $ty, $($gen),*);
+        impls_inner!(iter Product, product, ProductPanic, product_panic, $ty, $($gen),*);
+        impls_inner!(iter Product, product, ProductSaturate, product_saturate, $ty, $($gen),*);
+    };
     (neg $origtrait:ident, $newtrait:ident, $fun:ident, $ty:ty,) => {
         impl $newtrait for $ty
         where $ty: $origtrait {
@@ -1571,6 +3001,22 @@ macro_rules! impls_inner {
             }
         }
     };
+    (iter $origtrait:ident, $origfun:ident, $newtrait:ident, $fun:ident, $ty:ty,) => {
+        impl<A> $newtrait<A> for $ty
+        where $ty: ::core::iter::$origtrait<A> {
+            fn $fun<I: Iterator<Item = A>>(iter: I) -> Self {
+                ::core::iter::$origtrait::$origfun(iter)
+            }
+        }
+    };
+    (iter $origtrait:ident, $origfun:ident, $newtrait:ident, $fun:ident, $ty:ty, $($gen:tt),+) => {
+        impl<$($gen),*, A> $newtrait<A> for $ty
+        where $ty: ::core::iter::$origtrait<A> {
+            fn $fun<I: Iterator<Item = A>>(iter: I) -> Self {
+                ::core::iter::$origtrait::$origfun(iter)
+            }
+        }
+    };
     (abs $newtrait:ident, $fun:ident, $ty:ty,) => {
         impl $newtrait for $ty {
             fn $fun(self) -> Self {
@@ -1654,6 +3100,7 @@ macro_rules! impls_plain {
 /// * shl, shl_assign
 /// * shr, shr_assign
 /// * neg
+/// * sum, product (for `Iterator::sum` and `Iterator::product`)
 /// * all implements all operators
 ///
 /// # Examples:
@@ -1672,8 +3119,10 @@ macro_rules! impls {
     };
 }
 
-impls!(std::borrow::Cow<'a, str> : 'a; add add_assign);
-impls!(String; add add_assign);
+#[cfg(feature = "alloc")]
+impls!(alloc::borrow::Cow<'a, str> : 'a; add add_assign);
+#[cfg(feature = "alloc")]
+impls!(alloc::string::String; add add_assign);
 impls!(core::num::Wrapping<N> : N; all);
 impls!(&'a core::num::Wrapping<N> : 'a, N; all);
 #[cfg(feature = "wrapping_int_impl")]
@@ -1686,3 +3135,12 @@ mod wrapping_int_impls {
     impls!(core::num::Wrapping<isize>; abs);
 
 }
+
+pub mod dispatch;
+mod floats;
+mod time;
+mod wrappers;
+pub use floats::{AddAssignFinite, AddFinite, DivAssignFinite, DivFinite, Finite, MulAssignFinite,
+    MulFinite, NegFinite, ProductFinite, RemAssignFinite, RemFinite, SubAssignFinite, SubFinite,
+    SumFinite};
+pub use wrappers::{Checked, OverflowError, Panicking, Saturating};
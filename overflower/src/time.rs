@@ -0,0 +1,245 @@
+//! Impls for `core::time::Duration`, and with the `std` feature for
+//! `Instant` and `SystemTime` plus or minus a `Duration`
+//!
+//! Neither durations nor points in time wrap around, so the `*Wrap` traits
+//! panic on overflow like the `std::ops` traits do. The `*Saturate` traits
+//! saturate at `Duration::ZERO` and `Duration::MAX`, or at the earliest and
+//! latest point in time the platform can represent. Division by zero follows
+//! the table in the crate docs, with `Duration::MAX` as the maximum.
+//!
+//! Without the `specialization` feature, either operand may also be a
+//! reference, like for the integers. `std` has no operator impls for
+//! references to these types, so the blanket impls of the feature don't
+//! allow for them.
+
+use super::*;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{Instant, SystemTime};
+
+fn checked<T>(x: Option<T>) -> T {
+    if let Some(x) = x { x } else { panic!("arithmetic overflow") }
+}
+
+/// Move `t` by `d` (with `checked` being e.g. `Instant::checked_add`), or by
+/// as much of it as the platform can represent
+#[cfg(feature = "std")]
+fn furthest<T>(t: T, d: Duration, checked: fn(&T, Duration) -> Option<T>) -> T {
+    if let Some(x) = checked(&t, d) {
+        return x;
+    }
+    // moving by `lo` works, by `hi` it doesn't
+    let (mut lo, mut hi) = (Duration::ZERO, d);
+    while hi - lo > Duration::from_nanos(1) {
+        let mid = lo + (hi - lo) / 2;
+        if checked(&t, mid).is_some() { lo = mid } else { hi = mid }
+    }
+    checked(&t, lo).unwrap_or(t)
+}
+
+macro_rules! time_ops {
+    ($ty:ty, $rty:ty => $out:ty;
+            $($trait_name:ident::$fn_name:ident $(, $assign_name:ident::$assign_fn:ident)?:
+                |$x:ident, $y:ident| $e:expr;)*) => {
+        $(
+            impl $trait_name<$rty> for $ty {
+                #[cfg(not(feature = "specialization"))]
+                type Output = $out;
+
+                fn $fn_name(self, rhs: $rty) -> $out {
+                    let ($x, $y) = (self, rhs);
+                    $e
+                }
+            }
+
+            #[cfg(not(feature = "specialization"))]
+            impl $trait_name<$rty> for &$ty {
+                type Output = $out;
+
+                fn $fn_name(self, rhs: $rty) -> $out {
+                    $trait_name::$fn_name(*self, rhs)
+                }
+            }
+
+            #[cfg(not(feature = "specialization"))]
+            impl $trait_name<&$rty> for $ty {
+                type Output = $out;
+
+                fn $fn_name(self, rhs: &$rty) -> $out {
+                    $trait_name::$fn_name(self, *rhs)
+                }
+            }
+
+            #[cfg(not(feature = "specialization"))]
+            impl $trait_name<&$rty> for &$ty {
+                type Output = $out;
+
+                fn $fn_name(self, rhs: &$rty) -> $out {
+                    $trait_name::$fn_name(*self, *rhs)
+                }
+            }
+
+            $(
+                impl $assign_name<$rty> for $ty {
+                    fn $assign_fn(&mut self, rhs: $rty) {
+                        *self = $trait_name::$fn_name(*self, rhs);
+                    }
+                }
+
+                #[cfg(not(feature = "specialization"))]
+                impl $assign_name<&$rty> for $ty {
+                    fn $assign_fn(&mut self, rhs: &$rty) {
+                        *self = $trait_name::$fn_name(*self, *rhs);
+                    }
+                }
+            )?
+        )*
+    };
+}
+
+time_ops! { Duration, Duration => Duration;
+    AddWrap::add_wrap, AddAssignWrap::add_assign_wrap: |x, y| x + y;
+    AddPanic::add_panic, AddAssignPanic::add_assign_panic: |x, y| checked(x.checked_add(y));
+    AddSaturate::add_saturate, AddAssignSaturate::add_assign_saturate:
+        |x, y| x.saturating_add(y);
+    AddFinite::add_finite, AddAssignFinite::add_assign_finite: |x, y| x.add_panic(y);
+    SubWrap::sub_wrap, SubAssignWrap::sub_assign_wrap: |x, y| x - y;
+    SubPanic::sub_panic, SubAssignPanic::sub_assign_panic: |x, y| checked(x.checked_sub(y));
+    SubSaturate::sub_saturate, SubAssignSaturate::sub_assign_saturate:
+        |x, y| x.saturating_sub(y);
+    SubFinite::sub_finite, SubAssignFinite::sub_assign_finite: |x, y| x.sub_panic(y);
+}
+
+time_ops! { Duration, u32 => Duration;
+    MulWrap::mul_wrap, MulAssignWrap::mul_assign_wrap: |x, y| x * y;
+    MulPanic::mul_panic, MulAssignPanic::mul_assign_panic: |x, y| checked(x.checked_mul(y));
+    MulSaturate::mul_saturate, MulAssignSaturate::mul_assign_saturate:
+        |x, y| x.saturating_mul(y);
+    MulFinite::mul_finite, MulAssignFinite::mul_assign_finite: |x, y| x.mul_panic(y);
+    DivWrap::div_wrap, DivAssignWrap::div_assign_wrap: |x, y| x / y;
+    DivPanic::div_panic, DivAssignPanic::div_assign_panic: |x, y| checked(x.checked_div(y));
+    DivSaturate::div_saturate, DivAssignSaturate::div_assign_saturate: |x, y| {
+        if y != 0 { x / y } else if x.is_zero() { Duration::ZERO } else { Duration::MAX }
+    };
+    DivFinite::div_finite, DivAssignFinite::div_assign_finite: |x, y| x.div_panic(y);
+}
+
+time_ops! { u32, Duration => Duration;
+    MulWrap::mul_wrap: |x, y| x * y;
+    MulPanic::mul_panic: |x, y| y.mul_panic(x);
+    MulSaturate::mul_saturate: |x, y| y.saturating_mul(x);
+    MulFinite::mul_finite: |x, y| y.mul_panic(x);
+}
+
+impl<P: Policy, Z: Policy> DivWith<P, Z, u32> for Duration {
+    #[cfg(not(feature = "specialization"))]
+    type Output = Duration;
+
+    fn div_with(self, rhs: u32) -> Duration {
+        if rhs == 0 { Z::div(self, rhs) } else { P::div(self, rhs) }
+    }
+}
+
+#[cfg(not(feature = "specialization"))]
+impl<P: Policy, Z: Policy> DivWith<P, Z, u32> for &Duration {
+    type Output = Duration;
+
+    fn div_with(self, rhs: u32) -> Duration {
+        DivWith::<P, Z, u32>::div_with(*self, rhs)
+    }
+}
+
+#[cfg(not(feature = "specialization"))]
+impl<P: Policy, Z: Policy> DivWith<P, Z, &u32> for Duration {
+    type Output = Duration;
+
+    fn div_with(self, rhs: &u32) -> Duration {
+        DivWith::<P, Z, u32>::div_with(self, *rhs)
+    }
+}
+
+#[cfg(not(feature = "specialization"))]
+impl<P: Policy, Z: Policy> DivWith<P, Z, &u32> for &Duration {
+    type Output = Duration;
+
+    fn div_with(self, rhs: &u32) -> Duration {
+        DivWith::<P, Z, u32>::div_with(*self, *rhs)
+    }
+}
+
+impl<P: Policy, Z: Policy> DivAssignWith<P, Z, u32> for Duration {
+    fn div_assign_with(&mut self, rhs: u32) {
+        *self = DivWith::<P, Z, u32>::div_with(*self, rhs);
+    }
+}
+
+#[cfg(not(feature = "specialization"))]
+impl<P: Policy, Z: Policy> DivAssignWith<P, Z, &u32> for Duration {
+    fn div_assign_with(&mut self, rhs: &u32) {
+        *self = DivWith::<P, Z, u32>::div_with(*self, *rhs);
+    }
+}
+
+macro_rules! duration_sum {
+    ($($trait_name:ident::$fn_name:ident: |$iter:ident| $e:expr;)*) => {
+        $(
+            impl $trait_name for Duration {
+                fn $fn_name<I: Iterator<Item = Duration>>($iter: I) -> Self {
+                    $e
+                }
+            }
+
+            impl<'a> $trait_name<&'a Duration> for Duration {
+                fn $fn_name<I: Iterator<Item = &'a Duration>>($iter: I) -> Self {
+                    let $iter = $iter.copied();
+                    $e
+                }
+            }
+        )*
+    };
+}
+
+duration_sum! {
+    SumWrap::sum_wrap: |iter| iter.sum();
+    SumPanic::sum_panic: |iter| iter.fold(Duration::ZERO, AddPanic::add_panic);
+    SumSaturate::sum_saturate: |iter| iter.fold(Duration::ZERO, Duration::saturating_add);
+    SumFinite::sum_finite: |iter| SumPanic::sum_panic(iter);
+}
+
+#[cfg(feature = "std")]
+time_ops! { Instant, Duration => Instant;
+    AddWrap::add_wrap, AddAssignWrap::add_assign_wrap: |x, y| x + y;
+    AddPanic::add_panic, AddAssignPanic::add_assign_panic: |x, y| checked(x.checked_add(y));
+    AddSaturate::add_saturate, AddAssignSaturate::add_assign_saturate:
+        |x, y| furthest(x, y, Instant::checked_add);
+    AddFinite::add_finite, AddAssignFinite::add_assign_finite: |x, y| x.add_panic(y);
+    SubWrap::sub_wrap, SubAssignWrap::sub_assign_wrap: |x, y| x - y;
+    SubPanic::sub_panic, SubAssignPanic::sub_assign_panic: |x, y| checked(x.checked_sub(y));
+    SubSaturate::sub_saturate, SubAssignSaturate::sub_assign_saturate:
+        |x, y| furthest(x, y, Instant::checked_sub);
+    SubFinite::sub_finite, SubAssignFinite::sub_assign_finite: |x, y| x.sub_panic(y);
+}
+
+// `std` saturates the difference of instants at zero, but that is an
+// underflow all the same
+#[cfg(feature = "std")]
+time_ops! { Instant, Instant => Duration;
+    SubWrap::sub_wrap: |x, y| x - y;
+    SubPanic::sub_panic: |x, y| checked(x.checked_duration_since(y));
+    SubSaturate::sub_saturate: |x, y| x.saturating_duration_since(y);
+    SubFinite::sub_finite: |x, y| x.sub_panic(y);
+}
+
+#[cfg(feature = "std")]
+time_ops! { SystemTime, Duration => SystemTime;
+    AddWrap::add_wrap, AddAssignWrap::add_assign_wrap: |x, y| x + y;
+    AddPanic::add_panic, AddAssignPanic::add_assign_panic: |x, y| checked(x.checked_add(y));
+    AddSaturate::add_saturate, AddAssignSaturate::add_assign_saturate:
+        |x, y| furthest(x, y, SystemTime::checked_add);
+    AddFinite::add_finite, AddAssignFinite::add_assign_finite: |x, y| x.add_panic(y);
+    SubWrap::sub_wrap, SubAssignWrap::sub_assign_wrap: |x, y| x - y;
+    SubPanic::sub_panic, SubAssignPanic::sub_assign_panic: |x, y| checked(x.checked_sub(y));
+    SubSaturate::sub_saturate, SubAssignSaturate::sub_assign_saturate:
+        |x, y| furthest(x, y, SystemTime::checked_sub);
+    SubFinite::sub_finite, SubAssignFinite::sub_assign_finite: |x, y| x.sub_panic(y);
+}
@@ -0,0 +1,504 @@
+//! Integer wrappers that carry their overflow policy, like `core::num::Wrapping`
+//!
+//! Attributes only cover the code they are put on, while these types follow
+//! the values, e.g. into struct fields or across crates. Their operators
+//! delegate to the respective policy traits, so they also work within code
+//! annotated with a different policy.
+
+use super::*;
+use core::fmt;
+use core::iter::{Product, Sum};
+
+/// An integer that panics on overflow
+///
+/// All operators of `Panicking<T>` delegate to the `*Panic` traits of `T`,
+/// so they panic on overflow regardless of the `overflow-checks` setting.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Panicking<T>(pub T);
+
+/// An integer that saturates on overflow
+///
+/// All operators of `Saturating<T>` delegate to the `*Saturate` traits of
+/// `T`, so they stay at the bound they would overflow.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Saturating<T>(pub T);
+
+/// An integer whose arithmetic is checked, holding `None` once it overflowed
+///
+/// Operators use the `checked_*` methods of the integer types. Once a value
+/// overflowed, so does every result computed from it, like a NaN, so a whole
+/// formula can be computed before checking for overflow once with `get` or
+/// `into_result`. Plain integers can be mixed in on either side. Overflowed
+/// values compare neither equal nor ordered to anything, including
+/// themselves.
+#[derive(Copy, Clone)]
+pub struct Checked<T>(pub Option<T>);
+
+/// The error of a `Checked` value that overflowed
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("arithmetic overflow")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OverflowError {}
+
+impl<T> Checked<T> {
+    /// wrap a value that didn't overflow
+    pub const fn new(t: T) -> Checked<T> {
+        Checked(Some(t))
+    }
+
+    /// a value that overflowed
+    pub const fn overflowed() -> Checked<T> {
+        Checked(None)
+    }
+
+    /// whether this value or any it was computed from overflowed
+    pub fn is_overflowed(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// get the value, or `None` if it overflowed
+    pub fn get(self) -> Option<T> {
+        self.0
+    }
+
+    /// get the value, or an `OverflowError` if it overflowed
+    pub fn into_result(self) -> Result<T, OverflowError> {
+        self.0.ok_or(OverflowError)
+    }
+}
+
+macro_rules! wrapper_bin {
+    ($name:ident, $trait_name:ident, $fn_name:ident, $policy_trait:ident, $policy_fn:ident) => {
+        impl<T> $trait_name for $name<T> where T: $policy_trait<Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: $name<T>) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs.0))
+            }
+        }
+
+        impl<'a, T: Copy> $trait_name<&'a $name<T>> for $name<T>
+        where T: $policy_trait<Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: &'a $name<T>) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs.0))
+            }
+        }
+
+        impl<'a, T: Copy> $trait_name<$name<T>> for &'a $name<T>
+        where T: $policy_trait<Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: $name<T>) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs.0))
+            }
+        }
+
+        impl<'a, 'b, T: Copy> $trait_name<&'a $name<T>> for &'b $name<T>
+        where T: $policy_trait<Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: &'a $name<T>) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs.0))
+            }
+        }
+    };
+}
+
+macro_rules! wrapper_assign {
+    ($name:ident, $trait_name:ident, $fn_name:ident, $policy_trait:ident, $policy_fn:ident) => {
+        impl<T> $trait_name for $name<T> where T: $policy_trait {
+            fn $fn_name(&mut self, rhs: $name<T>) {
+                $policy_trait::$policy_fn(&mut self.0, rhs.0)
+            }
+        }
+
+        impl<'a, T: Copy> $trait_name<&'a $name<T>> for $name<T> where T: $policy_trait {
+            fn $fn_name(&mut self, rhs: &'a $name<T>) {
+                $policy_trait::$policy_fn(&mut self.0, rhs.0)
+            }
+        }
+    };
+}
+
+// Shifts take any right-hand side the policy trait does, as the number of
+// bits is a plain integer.
+macro_rules! wrapper_shift {
+    ($name:ident, $trait_name:ident, $fn_name:ident, $policy_trait:ident, $policy_fn:ident,
+            $trait_assign:ident, $fn_assign:ident, $policy_assign:ident, $policy_assign_fn:ident) => {
+        impl<T, R> $trait_name<R> for $name<T> where T: $policy_trait<R, Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: R) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs))
+            }
+        }
+
+        impl<'a, T: Copy, R> $trait_name<R> for &'a $name<T>
+        where T: $policy_trait<R, Output = T> {
+            type Output = $name<T>;
+
+            fn $fn_name(self, rhs: R) -> $name<T> {
+                $name($policy_trait::$policy_fn(self.0, rhs))
+            }
+        }
+
+        impl<T, R> $trait_assign<R> for $name<T> where T: $policy_assign<R> {
+            fn $fn_assign(&mut self, rhs: R) {
+                $policy_assign::$policy_assign_fn(&mut self.0, rhs)
+            }
+        }
+    };
+}
+
+macro_rules! wrapper_iter {
+    ($name:ident, $trait_name:ident, $fn_name:ident, $policy_trait:ident, $policy_fn:ident) => {
+        impl<T> $trait_name for $name<T> where T: $policy_trait {
+            fn $fn_name<I: Iterator<Item = $name<T>>>(iter: I) -> $name<T> {
+                $name(T::$policy_fn(iter.map(|x| x.0)))
+            }
+        }
+
+        impl<'a, T: Copy + 'a> $trait_name<&'a $name<T>> for $name<T> where T: $policy_trait {
+            fn $fn_name<I: Iterator<Item = &'a $name<T>>>(iter: I) -> $name<T> {
+                $name(T::$policy_fn(iter.map(|x| x.0)))
+            }
+        }
+    };
+}
+
+macro_rules! wrapper {
+    ($name:ident, $add:ident::$add_fn:ident, $sub:ident::$sub_fn:ident,
+            $mul:ident::$mul_fn:ident, $div:ident::$div_fn:ident, $rem:ident::$rem_fn:ident,
+            $shl:ident::$shl_fn:ident, $shr:ident::$shr_fn:ident, $neg:ident::$neg_fn:ident,
+            $add_assign:ident::$add_assign_fn:ident, $sub_assign:ident::$sub_assign_fn:ident,
+            $mul_assign:ident::$mul_assign_fn:ident, $div_assign:ident::$div_assign_fn:ident,
+            $rem_assign:ident::$rem_assign_fn:ident, $shl_assign:ident::$shl_assign_fn:ident,
+            $shr_assign:ident::$shr_assign_fn:ident, $sum:ident::$sum_fn:ident,
+            $product:ident::$product_fn:ident) => {
+        wrapper_bin!($name, Add, add, $add, $add_fn);
+        wrapper_bin!($name, Sub, sub, $sub, $sub_fn);
+        wrapper_bin!($name, Mul, mul, $mul, $mul_fn);
+        wrapper_bin!($name, Div, div, $div, $div_fn);
+        wrapper_bin!($name, Rem, rem, $rem, $rem_fn);
+        wrapper_assign!($name, AddAssign, add_assign, $add_assign, $add_assign_fn);
+        wrapper_assign!($name, SubAssign, sub_assign, $sub_assign, $sub_assign_fn);
+        wrapper_assign!($name, MulAssign, mul_assign, $mul_assign, $mul_assign_fn);
+        wrapper_assign!($name, DivAssign, div_assign, $div_assign, $div_assign_fn);
+        wrapper_assign!($name, RemAssign, rem_assign, $rem_assign, $rem_assign_fn);
+        wrapper_shift!($name, Shl, shl, $shl, $shl_fn, ShlAssign, shl_assign, $shl_assign,
+            $shl_assign_fn);
+        wrapper_shift!($name, Shr, shr, $shr, $shr_fn, ShrAssign, shr_assign, $shr_assign,
+            $shr_assign_fn);
+        wrapper_iter!($name, Sum, sum, $sum, $sum_fn);
+        wrapper_iter!($name, Product, product, $product, $product_fn);
+
+        impl<T> Neg for $name<T> where T: $neg<Output = T> {
+            type Output = $name<T>;
+
+            fn neg(self) -> $name<T> {
+                $name($neg::$neg_fn(self.0))
+            }
+        }
+
+        impl<'a, T: Copy> Neg for &'a $name<T> where T: $neg<Output = T> {
+            type Output = $name<T>;
+
+            fn neg(self) -> $name<T> {
+                $name($neg::$neg_fn(self.0))
+            }
+        }
+
+        impl<T> From<T> for $name<T> {
+            fn from(t: T) -> $name<T> {
+                $name(t)
+            }
+        }
+
+        impl<T: fmt::Debug> fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl<T: fmt::Display> fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impls!($name<N> : N; all);
+        impls!(&'a $name<N> : 'a, N; all);
+    };
+}
+
+wrapper!(Panicking, AddPanic::add_panic, SubPanic::sub_panic, MulPanic::mul_panic,
+    DivPanic::div_panic, RemPanic::rem_panic, ShlPanic::shl_panic, ShrPanic::shr_panic,
+    NegPanic::neg_panic, AddAssignPanic::add_assign_panic, SubAssignPanic::sub_assign_panic,
+    MulAssignPanic::mul_assign_panic, DivAssignPanic::div_assign_panic,
+    RemAssignPanic::rem_assign_panic, ShlAssignPanic::shl_assign_panic,
+    ShrAssignPanic::shr_assign_panic, SumPanic::sum_panic, ProductPanic::product_panic);
+
+wrapper!(Saturating, AddSaturate::add_saturate, SubSaturate::sub_saturate,
+    MulSaturate::mul_saturate, DivSaturate::div_saturate, RemSaturate::rem_saturate,
+    ShlSaturate::shl_saturate, ShrSaturate::shr_saturate, NegSaturate::neg_saturate,
+    AddAssignSaturate::add_assign_saturate, SubAssignSaturate::sub_assign_saturate,
+    MulAssignSaturate::mul_assign_saturate, DivAssignSaturate::div_assign_saturate,
+    RemAssignSaturate::rem_assign_saturate, ShlAssignSaturate::shl_assign_saturate,
+    ShrAssignSaturate::shr_assign_saturate, SumSaturate::sum_saturate,
+    ProductSaturate::product_saturate);
+
+impl<T> From<T> for Checked<T> {
+    fn from(t: T) -> Checked<T> {
+        Checked(Some(t))
+    }
+}
+
+impl<T: Default> Default for Checked<T> {
+    fn default() -> Checked<T> {
+        Checked(Some(T::default()))
+    }
+}
+
+impl<T: PartialEq> PartialEq for Checked<T> {
+    fn eq(&self, other: &Checked<T>) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Checked<T> {
+    fn partial_cmp(&self, other: &Checked<T>) -> Option<Ordering> {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(ref t) => t.fmt(f),
+            None => f.write_str("overflow"),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(ref t) => t.fmt(f),
+            None => f.write_str("overflow"),
+        }
+    }
+}
+
+macro_rules! checked_lhs {
+    ($ty:ty, $trait_name:ident, $fn_name:ident, $($policy_trait:ident::$policy_fn:ident),*) => {
+        $(
+            impl $policy_trait<Checked<$ty>> for $ty {
+                #[cfg(not(feature = "specialization"))]
+                type Output = Checked<$ty>;
+
+                fn $policy_fn(self, rhs: Checked<$ty>) -> Checked<$ty> {
+                    $trait_name::$fn_name(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! checked_bin {
+    ($ty:ty, $trait_name:ident, $fn_name:ident, $checked_fn:ident, $trait_assign:ident,
+            $fn_assign:ident, $wrap:ident::$wrap_fn:ident, $panic:ident::$panic_fn:ident,
+            $saturate:ident::$saturate_fn:ident) => {
+        impl $trait_name for Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: Checked<$ty>) -> Checked<$ty> {
+                Checked(match (self.0, rhs.0) {
+                    (Some(a), Some(b)) => a.$checked_fn(b),
+                    _ => None,
+                })
+            }
+        }
+
+        impl<'a> $trait_name<&'a Checked<$ty>> for Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: &'a Checked<$ty>) -> Checked<$ty> {
+                $trait_name::$fn_name(self, *rhs)
+            }
+        }
+
+        impl<'a> $trait_name<Checked<$ty>> for &'a Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: Checked<$ty>) -> Checked<$ty> {
+                $trait_name::$fn_name(*self, rhs)
+            }
+        }
+
+        impl<'a, 'b> $trait_name<&'a Checked<$ty>> for &'b Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: &'a Checked<$ty>) -> Checked<$ty> {
+                $trait_name::$fn_name(*self, *rhs)
+            }
+        }
+
+        impl $trait_name<$ty> for Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: $ty) -> Checked<$ty> {
+                Checked(self.0.and_then(|a| a.$checked_fn(rhs)))
+            }
+        }
+
+        impl $trait_name<Checked<$ty>> for $ty {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: Checked<$ty>) -> Checked<$ty> {
+                Checked(rhs.0.and_then(|b| self.$checked_fn(b)))
+            }
+        }
+
+        impl $trait_assign for Checked<$ty> {
+            fn $fn_assign(&mut self, rhs: Checked<$ty>) {
+                *self = $trait_name::$fn_name(*self, rhs);
+            }
+        }
+
+        impl $trait_assign<$ty> for Checked<$ty> {
+            fn $fn_assign(&mut self, rhs: $ty) {
+                *self = $trait_name::$fn_name(*self, rhs);
+            }
+        }
+
+        // the rewriting of plain integer operations needs these, as they
+        // aren't covered by `impls!`
+        checked_lhs!($ty, $trait_name, $fn_name, $wrap::$wrap_fn, $panic::$panic_fn,
+            $saturate::$saturate_fn);
+
+        impl<'a> $trait_assign<&'a Checked<$ty>> for Checked<$ty> {
+            fn $fn_assign(&mut self, rhs: &'a Checked<$ty>) {
+                *self = $trait_name::$fn_name(*self, *rhs);
+            }
+        }
+    };
+}
+
+// Like the shift methods of the integer types, these take the number of bits
+// as `u32`. As with `ShlPanic`, shifting bits out of the value overflows.
+macro_rules! checked_shift {
+    ($ty:ty, $trait_name:ident, $fn_name:ident, $checked_fn:ident,
+            $trait_assign:ident, $fn_assign:ident) => {
+        impl $trait_name<u32> for Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: u32) -> Checked<$ty> {
+                Checked(self.0.and_then(|a| a.$checked_fn(rhs)))
+            }
+        }
+
+        impl<'a> $trait_name<u32> for &'a Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn $fn_name(self, rhs: u32) -> Checked<$ty> {
+                $trait_name::$fn_name(*self, rhs)
+            }
+        }
+
+        impl $trait_assign<u32> for Checked<$ty> {
+            fn $fn_assign(&mut self, rhs: u32) {
+                *self = $trait_name::$fn_name(*self, rhs);
+            }
+        }
+    };
+}
+
+macro_rules! checked {
+    ($ty:ty) => {
+        checked_bin!($ty, Add, add, checked_add, AddAssign, add_assign, AddWrap::add_wrap,
+            AddPanic::add_panic, AddSaturate::add_saturate);
+        checked_bin!($ty, Sub, sub, checked_sub, SubAssign, sub_assign, SubWrap::sub_wrap,
+            SubPanic::sub_panic, SubSaturate::sub_saturate);
+        checked_bin!($ty, Mul, mul, checked_mul, MulAssign, mul_assign, MulWrap::mul_wrap,
+            MulPanic::mul_panic, MulSaturate::mul_saturate);
+        checked_bin!($ty, Div, div, checked_div, DivAssign, div_assign, DivWrap::div_wrap,
+            DivPanic::div_panic, DivSaturate::div_saturate);
+        checked_bin!($ty, Rem, rem, checked_remainder, RemAssign, rem_assign, RemWrap::rem_wrap,
+            RemPanic::rem_panic, RemSaturate::rem_saturate);
+        checked_shift!($ty, Shl, shl, checked_shift_left, ShlAssign, shl_assign);
+        checked_shift!($ty, Shr, shr, checked_shr, ShrAssign, shr_assign);
+
+        impl Neg for Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn neg(self) -> Checked<$ty> {
+                Checked(self.0.and_then(<$ty>::checked_neg))
+            }
+        }
+
+        impl<'a> Neg for &'a Checked<$ty> {
+            type Output = Checked<$ty>;
+
+            fn neg(self) -> Checked<$ty> {
+                -*self
+            }
+        }
+
+        impl Sum for Checked<$ty> {
+            fn sum<I: Iterator<Item = Checked<$ty>>>(iter: I) -> Checked<$ty> {
+                iter.fold(Checked(Some(0)), Add::add)
+            }
+        }
+
+        impl<'a> Sum<&'a Checked<$ty>> for Checked<$ty> {
+            fn sum<I: Iterator<Item = &'a Checked<$ty>>>(iter: I) -> Checked<$ty> {
+                iter.fold(Checked(Some(0)), Add::add)
+            }
+        }
+
+        impl Product for Checked<$ty> {
+            fn product<I: Iterator<Item = Checked<$ty>>>(iter: I) -> Checked<$ty> {
+                iter.fold(Checked(Some(1)), Mul::mul)
+            }
+        }
+
+        impl<'a> Product<&'a Checked<$ty>> for Checked<$ty> {
+            fn product<I: Iterator<Item = &'a Checked<$ty>>>(iter: I) -> Checked<$ty> {
+                iter.fold(Checked(Some(1)), Mul::mul)
+            }
+        }
+    };
+}
+
+checked!(u8);
+checked!(u16);
+checked!(u32);
+checked!(u64);
+checked!(u128);
+checked!(usize);
+checked!(i8);
+checked!(i16);
+checked!(i32);
+checked!(i64);
+checked!(i128);
+checked!(isize);
+
+impls!(Checked<N> : N; all);
+impls!(&'a Checked<N> : 'a, N; all);
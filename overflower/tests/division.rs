@@ -0,0 +1,158 @@
+//! Exhaustive checks of the division table in the crate docs for `i8` and
+//! `u8`, for every way to divide
+
+use overflower::*;
+use std::panic::{self, catch_unwind, UnwindSafe};
+use std::sync::Once;
+
+static HANDLER: Once = Once::new();
+
+// only show the panics of failed checks
+fn install_handler() {
+    HANDLER.call_once(|| {
+        let p = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.location().is_some_and(|l| l.file().ends_with("division.rs")) {
+                p(info);
+            }
+        }));
+    })
+}
+
+// `None` where the table says it panics (or overflows, for `Checked`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Expected {
+    wrap: (Option<i32>, Option<i32>),
+    panic: (Option<i32>, Option<i32>),
+    saturate: (Option<i32>, Option<i32>),
+}
+
+fn expected(x: i32, y: i32, min: i32, max: i32) -> Expected {
+    let (q, r) = if y == 0 { (None, None) } else { (Some(x / y), Some(x % y)) };
+    let fits = |v: &i32| (min..=max).contains(v);
+    let by_zero = if x > 0 { max } else if x < 0 { min } else { 0 };
+    Expected {
+        wrap: (q.map(|q| if fits(&q) { q } else { min }), r),
+        panic: (q.filter(fits), r),
+        saturate: (Some(q.map_or(by_zero, |q| q.clamp(min, max))), Some(r.unwrap_or(x))),
+    }
+}
+
+fn run<T: Into<i32>, F: FnOnce() -> T + UnwindSafe>(f: F) -> Option<i32> {
+    catch_unwind(f).ok().map(Into::into)
+}
+
+macro_rules! test_division {
+    ($ty:ty, $name:ident) => {
+        #[test]
+        fn $name() {
+            install_handler();
+            for x in <$ty>::MIN..=<$ty>::MAX {
+                for y in <$ty>::MIN..=<$ty>::MAX {
+                    let e = expected(x.into(), y.into(), <$ty>::MIN.into(), <$ty>::MAX.into());
+                    let args = (x, y);
+
+                    for &(div, rem) in &[
+                        (run(|| DivWrap::div_wrap(x, y)), run(|| RemWrap::rem_wrap(x, y))),
+                        (run(|| DivWrap::div_wrap(&x, &y)), run(|| RemWrap::rem_wrap(&x, &y))),
+                        (run(|| { let mut z = x; z.div_assign_wrap(y); z }),
+                            run(|| { let mut z = x; z.rem_assign_wrap(y); z })),
+                        (run(|| <Wrap as Policy>::div(x, y)), run(|| <Wrap as Policy>::rem(x, y))),
+                        (run(|| Arith::<Wrap>::div(x, y)), run(|| Arith::<Wrap>::rem(x, y))),
+                        (run(|| DivWith::<Wrap, Panic>::div_with(x, y)),
+                            run(|| RemWith::<Wrap, Panic>::rem_with(x, y))),
+                    ] {
+                        assert_eq!(e.wrap, (div, rem), "wrap {:?}", args);
+                    }
+
+                    for &(div, rem) in &[
+                        (run(|| DivPanic::div_panic(x, y)), run(|| RemPanic::rem_panic(x, y))),
+                        (run(|| DivPanic::div_panic(&x, &y)), run(|| RemPanic::rem_panic(&x, &y))),
+                        (run(|| { let mut z = x; z.div_assign_panic(y); z }),
+                            run(|| { let mut z = x; z.rem_assign_panic(y); z })),
+                        (run(|| <Panic as Policy>::div(x, y)), run(|| <Panic as Policy>::rem(x, y))),
+                        (run(|| Arith::<Panic>::div(x, y)), run(|| Arith::<Panic>::rem(x, y))),
+                        (run(|| DivWith::<Panic, Wrap>::div_with(x, y)),
+                            run(|| RemWith::<Panic, Wrap>::rem_with(x, y))),
+                        (run(|| (Panicking(x) / Panicking(y)).0),
+                            run(|| (Panicking(x) % Panicking(y)).0)),
+                        (run(|| { let mut z = Panicking(x); z /= Panicking(y); z.0 }),
+                            run(|| { let mut z = Panicking(x); z %= Panicking(y); z.0 })),
+                        (Checked::new(x).checked_div(y), Checked::new(x).checked_rem(y)),
+                    ] {
+                        assert_eq!(e.panic, (div, rem), "panic {:?}", args);
+                    }
+
+                    for &(div, rem) in &[
+                        (run(|| DivSaturate::div_saturate(x, y)),
+                            run(|| RemSaturate::rem_saturate(x, y))),
+                        (run(|| DivSaturate::div_saturate(&x, &y)),
+                            run(|| RemSaturate::rem_saturate(&x, &y))),
+                        (run(|| { let mut z = x; z.div_assign_saturate(y); z }),
+                            run(|| { let mut z = x; z.rem_assign_saturate(y); z })),
+                        (run(|| <Saturate as Policy>::div(x, y)),
+                            run(|| <Saturate as Policy>::rem(x, y))),
+                        (run(|| Arith::<Saturate>::div(x, y)), run(|| Arith::<Saturate>::rem(x, y))),
+                        (run(|| DivWith::<Saturate, Saturate>::div_with(x, y)),
+                            run(|| RemWith::<Saturate, Saturate>::rem_with(x, y))),
+                        (run(|| (Saturating(x) / Saturating(y)).0),
+                            run(|| (Saturating(x) % Saturating(y)).0)),
+                        (run(|| { let mut z = Saturating(x); z /= Saturating(y); z.0 }),
+                            run(|| { let mut z = Saturating(x); z %= Saturating(y); z.0 })),
+                    ] {
+                        assert_eq!(e.saturate, (div, rem), "saturate {:?}", args);
+                    }
+
+                    // mixing the zero divisor handling of one policy with another
+                    let mixed = if y == 0 { e.saturate } else { e.wrap };
+                    assert_eq!(mixed, (run(|| DivWith::<Wrap, Saturate, &$ty>::div_with(x, &y)),
+                        run(|| { let mut z = x; RemAssignWith::<Wrap, Saturate>::rem_assign_with(&mut z, y); z })),
+                        "wrap, div_by_zero = saturate {:?}", args);
+                    assert_eq!(mixed, (run(|| DivWith::<Wrap, Saturate, $ty>::div_with(&x, y)),
+                        run(|| RemWith::<Wrap, Saturate, &$ty>::rem_with(&x, &y))),
+                        "wrap, div_by_zero = saturate, by reference {:?}", args);
+                    let mixed = if y == 0 { e.panic } else { e.saturate };
+                    assert_eq!(mixed,
+                        (run(|| { let mut z = x; DivAssignWith::<Saturate, Panic, &$ty>::div_assign_with(&mut z, &y); z }),
+                        run(|| RemWith::<Saturate, Panic>::rem_with(x, y))),
+                        "saturate, div_by_zero = panic {:?}", args);
+                }
+            }
+        }
+    };
+}
+
+// `Checked` division results, converted like the others
+trait CheckedDivision {
+    fn checked_div(self, y: Self::Int) -> Option<i32>;
+    fn checked_rem(self, y: Self::Int) -> Option<i32>;
+    type Int;
+}
+
+macro_rules! checked_division {
+    ($ty:ty) => {
+        impl CheckedDivision for Checked<$ty> {
+            type Int = $ty;
+
+            fn checked_div(self, y: $ty) -> Option<i32> {
+                let mut z = self;
+                z /= Checked::new(y);
+                assert_eq!(z.get(), (self / y).get());
+                z.get().map(Into::into)
+            }
+
+            fn checked_rem(self, y: $ty) -> Option<i32> {
+                let mut z = self;
+                z %= y;
+                assert_eq!(z.get(), (self % Checked::new(y)).get());
+                z.get().map(Into::into)
+            }
+        }
+    };
+}
+
+checked_division!(i8);
+checked_division!(u8);
+
+test_division!(i8, test_division_i8);
+test_division!(u8, test_division_u8);
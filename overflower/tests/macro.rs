@@ -1,5 +1,7 @@
+// Attributes on macro statements need a nightly compiler, same as specialization
+#![cfg(feature = "specialization")]
 #![feature(proc_macro_hygiene)]
-#![allow(const_err, unused)]
+#![allow(arithmetic_overflow, unused)]
 
 use overflower::overflow;
 
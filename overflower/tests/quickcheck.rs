@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::panic::{self, catch_unwind};
 use std::sync::Once;
 use quickcheck::quickcheck;
@@ -10,7 +11,7 @@ fn install_handler() {
     HANDLER.call_once(|| {
         let p = panic::take_hook();
         panic::set_hook(Box::new(move|info| {
-            if info.location().map_or(false, |l| l.file() != "src/lib.rs" &&
+            if info.location().is_some_and(|l| l.file() != "src/lib.rs" &&
                     !l.file().ends_with("/num/mod.rs")) {
                 p(info);
             }
@@ -35,11 +36,13 @@ macro_rules! test_add_panic {
 }
 
 test_add_panic!(usize, test_add_panic_usize);
+test_add_panic!(u128, test_add_panic_u128);
 test_add_panic!(u64, test_add_panic_u64);
 test_add_panic!(u32, test_add_panic_u32);
 test_add_panic!(u16, test_add_panic_u16);
 test_add_panic!(u8,  test_add_panic_u8);
 test_add_panic!(isize, test_add_panic_isize);
+test_add_panic!(i128, test_add_panic_i128);
 test_add_panic!(i64, test_add_panic_i64);
 test_add_panic!(i32, test_add_panic_i32);
 test_add_panic!(i16, test_add_panic_i16);
@@ -61,11 +64,13 @@ macro_rules! test_add_wrap {
 }
 
 test_add_wrap!(usize, test_add_wrap_usize);
+test_add_wrap!(u128, test_add_wrap_u128);
 test_add_wrap!(u64, test_add_wrap_u64);
 test_add_wrap!(u32, test_add_wrap_u32);
 test_add_wrap!(u16, test_add_wrap_u16);
 test_add_wrap!(u8,  test_add_wrap_u8);
 test_add_wrap!(isize, test_add_wrap_isize);
+test_add_wrap!(i128, test_add_wrap_i128);
 test_add_wrap!(i64, test_add_wrap_i64);
 test_add_wrap!(i32, test_add_wrap_i32);
 test_add_wrap!(i16, test_add_wrap_i16);
@@ -87,11 +92,13 @@ macro_rules! test_add_saturate {
 }
 
 test_add_saturate!(usize, test_add_saturate_usize);
+test_add_saturate!(u128, test_add_saturate_u128);
 test_add_saturate!(u64, test_add_saturate_u64);
 test_add_saturate!(u32, test_add_saturate_u32);
 test_add_saturate!(u16, test_add_saturate_u16);
 test_add_saturate!(u8,  test_add_saturate_u8);
 test_add_saturate!(isize, test_add_saturate_isize);
+test_add_saturate!(i128, test_add_saturate_i128);
 test_add_saturate!(i64, test_add_saturate_i64);
 test_add_saturate!(i32, test_add_saturate_i32);
 test_add_saturate!(i16, test_add_saturate_i16);
@@ -114,11 +121,13 @@ macro_rules! test_sub_panic {
 }
 
 test_sub_panic!(usize, test_sub_panic_usize);
+test_sub_panic!(u128, test_sub_panic_u128);
 test_sub_panic!(u64, test_sub_panic_u64);
 test_sub_panic!(u32, test_sub_panic_u32);
 test_sub_panic!(u16, test_sub_panic_u16);
 test_sub_panic!(u8,  test_sub_panic_u8);
 test_sub_panic!(isize, test_sub_panic_isize);
+test_sub_panic!(i128, test_sub_panic_i128);
 test_sub_panic!(i64, test_sub_panic_i64);
 test_sub_panic!(i32, test_sub_panic_i32);
 test_sub_panic!(i16, test_sub_panic_i16);
@@ -140,11 +149,13 @@ macro_rules! test_sub_wrap {
 }
 
 test_sub_wrap!(usize, test_sub_wrap_usize);
+test_sub_wrap!(u128, test_sub_wrap_u128);
 test_sub_wrap!(u64, test_sub_wrap_u64);
 test_sub_wrap!(u32, test_sub_wrap_u32);
 test_sub_wrap!(u16, test_sub_wrap_u16);
 test_sub_wrap!(u8,  test_sub_wrap_u8);
 test_sub_wrap!(isize, test_sub_wrap_isize);
+test_sub_wrap!(i128, test_sub_wrap_i128);
 test_sub_wrap!(i64, test_sub_wrap_i64);
 test_sub_wrap!(i32, test_sub_wrap_i32);
 test_sub_wrap!(i16, test_sub_wrap_i16);
@@ -166,11 +177,13 @@ macro_rules! test_sub_saturate {
 }
 
 test_sub_saturate!(usize, test_sub_saturate_usize);
+test_sub_saturate!(u128, test_sub_saturate_u128);
 test_sub_saturate!(u64, test_sub_saturate_u64);
 test_sub_saturate!(u32, test_sub_saturate_u32);
 test_sub_saturate!(u16, test_sub_saturate_u16);
 test_sub_saturate!(u8,  test_sub_saturate_u8);
 test_sub_saturate!(isize, test_sub_saturate_isize);
+test_sub_saturate!(i128, test_sub_saturate_i128);
 test_sub_saturate!(i64, test_sub_saturate_i64);
 test_sub_saturate!(i32, test_sub_saturate_i32);
 test_sub_saturate!(i16, test_sub_saturate_i16);
@@ -193,11 +206,13 @@ macro_rules! test_mul_panic {
 }
 
 test_mul_panic!(usize, test_mul_panic_usize);
+test_mul_panic!(u128, test_mul_panic_u128);
 test_mul_panic!(u64, test_mul_panic_u64);
 test_mul_panic!(u32, test_mul_panic_u32);
 test_mul_panic!(u16, test_mul_panic_u16);
 test_mul_panic!(u8,  test_mul_panic_u8);
 test_mul_panic!(isize, test_mul_panic_isize);
+test_mul_panic!(i128, test_mul_panic_i128);
 test_mul_panic!(i64, test_mul_panic_i64);
 test_mul_panic!(i32, test_mul_panic_i32);
 test_mul_panic!(i16, test_mul_panic_i16);
@@ -219,11 +234,13 @@ macro_rules! test_mul_wrap {
 }
 
 test_mul_wrap!(usize, test_mul_wrap_usize);
+test_mul_wrap!(u128, test_mul_wrap_u128);
 test_mul_wrap!(u64, test_mul_wrap_u64);
 test_mul_wrap!(u32, test_mul_wrap_u32);
 test_mul_wrap!(u16, test_mul_wrap_u16);
 test_mul_wrap!(u8,  test_mul_wrap_u8);
 test_mul_wrap!(isize, test_mul_wrap_isize);
+test_mul_wrap!(i128, test_mul_wrap_i128);
 test_mul_wrap!(i64, test_mul_wrap_i64);
 test_mul_wrap!(i32, test_mul_wrap_i32);
 test_mul_wrap!(i16, test_mul_wrap_i16);
@@ -245,11 +262,13 @@ macro_rules! test_mul_saturate {
 }
 
 test_mul_saturate!(usize, test_mul_saturate_usize);
+test_mul_saturate!(u128, test_mul_saturate_u128);
 test_mul_saturate!(u64, test_mul_saturate_u64);
 test_mul_saturate!(u32, test_mul_saturate_u32);
 test_mul_saturate!(u16, test_mul_saturate_u16);
 test_mul_saturate!(u8,  test_mul_saturate_u8);
 test_mul_saturate!(isize, test_mul_saturate_isize);
+test_mul_saturate!(i128, test_mul_saturate_i128);
 test_mul_saturate!(i64, test_mul_saturate_i64);
 test_mul_saturate!(i32, test_mul_saturate_i32);
 test_mul_saturate!(i16, test_mul_saturate_i16);
@@ -272,11 +291,13 @@ macro_rules! test_div_panic {
 }
 
 test_div_panic!(usize, test_div_panic_usize);
+test_div_panic!(u128, test_div_panic_u128);
 test_div_panic!(u64, test_div_panic_u64);
 test_div_panic!(u32, test_div_panic_u32);
 test_div_panic!(u16, test_div_panic_u16);
 test_div_panic!(u8,  test_div_panic_u8);
 test_div_panic!(isize, test_div_panic_isize);
+test_div_panic!(i128, test_div_panic_i128);
 test_div_panic!(i64, test_div_panic_i64);
 test_div_panic!(i32, test_div_panic_i32);
 test_div_panic!(i16, test_div_panic_i16);
@@ -299,11 +320,13 @@ macro_rules! test_div_wrap {
 }
 
 test_div_wrap!(usize, test_div_wrap_usize);
+test_div_wrap!(u128, test_div_wrap_u128);
 test_div_wrap!(u64, test_div_wrap_u64);
 test_div_wrap!(u32, test_div_wrap_u32);
 test_div_wrap!(u16, test_div_wrap_u16);
 test_div_wrap!(u8,  test_div_wrap_u8);
 test_div_wrap!(isize, test_div_wrap_isize);
+test_div_wrap!(i128, test_div_wrap_i128);
 test_div_wrap!(i64, test_div_wrap_i64);
 test_div_wrap!(i32, test_div_wrap_i32);
 test_div_wrap!(i16, test_div_wrap_i16);
@@ -326,11 +349,12 @@ macro_rules! test_div_saturate {
     };
 }
 
-test_div_saturate!(usize, test_div_saturate_usize, std::usize::MAX);
-test_div_saturate!(u64, test_div_saturate_u64, std::u64::MAX);
-test_div_saturate!(u32, test_div_saturate_u32, std::u32::MAX);
-test_div_saturate!(u16, test_div_saturate_u16, std::u16::MAX);
-test_div_saturate!(u8,  test_div_saturate_u8, std::u8::MAX);
+test_div_saturate!(usize, test_div_saturate_usize, usize::MAX);
+test_div_saturate!(u128, test_div_saturate_u128, u128::MAX);
+test_div_saturate!(u64, test_div_saturate_u64, u64::MAX);
+test_div_saturate!(u32, test_div_saturate_u32, u32::MAX);
+test_div_saturate!(u16, test_div_saturate_u16, u16::MAX);
+test_div_saturate!(u8,  test_div_saturate_u8, u8::MAX);
 
 macro_rules! test_idiv_saturate {
     ($ty:ty, $name:ident, $max:expr, $min:expr) => {
@@ -355,18 +379,58 @@ macro_rules! test_idiv_saturate {
     };
 }
 
-test_idiv_saturate!(isize, test_div_saturate_isize, std::isize::MAX, std::isize::MIN);
-test_idiv_saturate!(i64, test_div_saturate_i64, std::i64::MAX, std::i64::MIN);
-test_idiv_saturate!(i32, test_div_saturate_i32, std::i32::MAX, std::i32::MIN);
-test_idiv_saturate!(i16, test_div_saturate_i16, std::i16::MAX, std::i16::MIN);
-test_idiv_saturate!(i8,  test_div_saturate_i8, std::i8::MAX, std::i8::MIN);
+test_idiv_saturate!(isize, test_div_saturate_isize, isize::MAX, isize::MIN);
+test_idiv_saturate!(i128, test_div_saturate_i128, i128::MAX, i128::MIN);
+test_idiv_saturate!(i64, test_div_saturate_i64, i64::MAX, i64::MIN);
+test_idiv_saturate!(i32, test_div_saturate_i32, i32::MAX, i32::MIN);
+test_idiv_saturate!(i16, test_div_saturate_i16, i16::MAX, i16::MIN);
+test_idiv_saturate!(i8,  test_div_saturate_i8, i8::MAX, i8::MIN);
+
+macro_rules! test_div_assign_saturate {
+    ($ty:ty, $name:ident) => {
+        #[test]
+        fn $name() {
+            fn check(args: ($ty, $ty)) -> bool {
+                let quotient = catch_unwind(|| {
+                    let mut quotient = args.0;
+                    quotient.div_assign_saturate(args.1);
+                    quotient
+                }).ok();
+                let remainder = catch_unwind(|| {
+                    let mut remainder = args.0;
+                    remainder.rem_assign_saturate(args.1);
+                    remainder
+                }).ok();
+                quotient == catch_unwind(|| DivSaturate::div_saturate(args.0, args.1)).ok() &&
+                    remainder == catch_unwind(|| RemSaturate::rem_saturate(args.0, args.1)).ok()
+            }
+            install_handler();
+            quickcheck(check as fn(($ty, $ty)) -> bool);
+        }
+    };
+}
+
+test_div_assign_saturate!(usize, test_div_assign_saturate_usize);
+test_div_assign_saturate!(u128, test_div_assign_saturate_u128);
+test_div_assign_saturate!(u64, test_div_assign_saturate_u64);
+test_div_assign_saturate!(u32, test_div_assign_saturate_u32);
+test_div_assign_saturate!(u16, test_div_assign_saturate_u16);
+test_div_assign_saturate!(u8,  test_div_assign_saturate_u8);
+test_div_assign_saturate!(isize, test_div_assign_saturate_isize);
+test_div_assign_saturate!(i128, test_div_assign_saturate_i128);
+test_div_assign_saturate!(i64, test_div_assign_saturate_i64);
+test_div_assign_saturate!(i32, test_div_assign_saturate_i32);
+test_div_assign_saturate!(i16, test_div_assign_saturate_i16);
+test_div_assign_saturate!(i8,  test_div_assign_saturate_i8);
 
 macro_rules! test_rem_panic {
     ($ty:ty, $name:ident) => {
         #[test]
         fn $name() {
             fn check(args: ($ty, $ty)) -> bool {
-                let expected = args.0.checked_rem(args.1);
+                // `MIN % -1` is 0 in every mode
+                let expected = args.0.checked_rem(args.1)
+                    .or_else(|| if args.1 == 0 { None } else { Some(0) });
                 let actual = catch_unwind(
                              || RemPanic::rem_panic(args.0, args.1)).ok();
                 expected == actual
@@ -378,11 +442,13 @@ macro_rules! test_rem_panic {
 }
 
 test_rem_panic!(usize, test_rem_panic_usize);
+test_rem_panic!(u128, test_rem_panic_u128);
 test_rem_panic!(u64, test_rem_panic_u64);
 test_rem_panic!(u32, test_rem_panic_u32);
 test_rem_panic!(u16, test_rem_panic_u16);
 test_rem_panic!(u8,  test_rem_panic_u8);
 test_rem_panic!(isize, test_rem_panic_isize);
+test_rem_panic!(i128, test_rem_panic_i128);
 test_rem_panic!(i64, test_rem_panic_i64);
 test_rem_panic!(i32, test_rem_panic_i32);
 test_rem_panic!(i16, test_rem_panic_i16);
@@ -393,7 +459,9 @@ macro_rules! test_rem_wrap {
         #[test]
         fn $name() {
             fn check(args: ($ty, $ty)) -> bool {
-                let expected = args.0.checked_rem(args.1);
+                // `MIN % -1` is 0 in every mode
+                let expected = args.0.checked_rem(args.1)
+                    .or_else(|| if args.1 == 0 { None } else { Some(0) });
                 let actual = catch_unwind(
                              || RemWrap::rem_wrap(args.0, args.1)).ok();
                 expected == actual
@@ -405,26 +473,24 @@ macro_rules! test_rem_wrap {
 }
 
 test_rem_wrap!(usize, test_rem_wrap_usize);
+test_rem_wrap!(u128, test_rem_wrap_u128);
 test_rem_wrap!(u64, test_rem_wrap_u64);
 test_rem_wrap!(u32, test_rem_wrap_u32);
 test_rem_wrap!(u16, test_rem_wrap_u16);
 test_rem_wrap!(u8,  test_rem_wrap_u8);
 test_rem_wrap!(isize, test_rem_wrap_isize);
+test_rem_wrap!(i128, test_rem_wrap_i128);
 test_rem_wrap!(i64, test_rem_wrap_i64);
 test_rem_wrap!(i32, test_rem_wrap_i32);
 test_rem_wrap!(i16, test_rem_wrap_i16);
 test_rem_wrap!(i8,  test_rem_wrap_i8);
 
 macro_rules! test_rem_saturate {
-    ($ty:ty, $name:ident, $max:expr) => {
+    ($ty:ty, $name:ident) => {
         #[test]
         fn $name() {
             fn check(args: ($ty, $ty)) -> bool {
-                let expected = if args.1 == 0 {
-                    if args.0 == 0 { 0 } else { $max }
-                } else {
-                    args.0 % args.1
-                };
+                let expected = if args.1 == 0 { args.0 } else { args.0.wrapping_rem(args.1) };
                 let actual = RemSaturate::rem_saturate(args.0, args.1);
                 expected == actual
             }
@@ -434,38 +500,28 @@ macro_rules! test_rem_saturate {
     };
 }
 
-test_rem_saturate!(usize, test_rem_saturate_usize, std::usize::MAX);
-test_rem_saturate!(u64, test_rem_saturate_u64, std::u64::MAX);
-test_rem_saturate!(u32, test_rem_saturate_u32, std::u32::MAX);
-test_rem_saturate!(u16, test_rem_saturate_u16, std::u16::MAX);
-test_rem_saturate!(u8,  test_rem_saturate_u8, std::u8::MAX);
-test_rem_saturate!(isize, test_rem_saturate_isize, std::isize::MAX);
-test_rem_saturate!(i64, test_rem_saturate_i64, std::i64::MAX);
-test_rem_saturate!(i32, test_rem_saturate_i32, std::i32::MAX);
-test_rem_saturate!(i16, test_rem_saturate_i16, std::i16::MAX);
-test_rem_saturate!(i8,  test_rem_saturate_i8, std::i8::MAX);
-
-#[cfg(target_pointer_width = "16")]
-const USIZE_BITS: usize = 16;
-
-#[cfg(target_pointer_width = "32")]
-const USIZE_BITS: usize = 16;
-
-#[cfg(target_pointer_width = "64")]
-const USIZE_BITS: usize = 64;
+test_rem_saturate!(usize, test_rem_saturate_usize);
+test_rem_saturate!(u128, test_rem_saturate_u128);
+test_rem_saturate!(u64, test_rem_saturate_u64);
+test_rem_saturate!(u32, test_rem_saturate_u32);
+test_rem_saturate!(u16, test_rem_saturate_u16);
+test_rem_saturate!(u8,  test_rem_saturate_u8);
+test_rem_saturate!(isize, test_rem_saturate_isize);
+test_rem_saturate!(i128, test_rem_saturate_i128);
+test_rem_saturate!(i64, test_rem_saturate_i64);
+test_rem_saturate!(i32, test_rem_saturate_i32);
+test_rem_saturate!(i16, test_rem_saturate_i16);
+test_rem_saturate!(i8,  test_rem_saturate_i8);
 
 macro_rules! test_shl_panic {
-    ($ty:ty, $name:ident, $bits:expr) => {
+    ($ty:ty, $name:ident) => {
         #[test]
         fn $name() {
             fn check(args: ($ty, $ty)) -> bool {
-                let expected = if args.0 == 0 {
-                    Some(0)
-                } else if args.1 < $bits as $ty && (!0) >> args.1 >= args.0 {
-                    Some(args.0 << args.1)
-                } else {
-                    None
-                };
+                // out-of-range amounts panic even for 0
+                let expected = u32::try_from(args.1).ok()
+                    .filter(|&n| n < <$ty>::BITS && (!0) >> n >= args.0)
+                    .map(|n| args.0 << n);
                 let actual = catch_unwind(
                              || ShlPanic::shl_panic(args.0, args.1)).ok();
                 expected == actual
@@ -476,25 +532,29 @@ macro_rules! test_shl_panic {
     };
 }
 
-test_shl_panic!(usize, test_shl_panic_usize, USIZE_BITS);
-test_shl_panic!(u64, test_shl_panic_u64, 64);
-test_shl_panic!(u32, test_shl_panic_u32, 32);
-test_shl_panic!(u16, test_shl_panic_u16, 16);
-test_shl_panic!(u8,  test_shl_panic_u8, 8);
+test_shl_panic!(usize, test_shl_panic_usize);
+test_shl_panic!(u128, test_shl_panic_u128);
+test_shl_panic!(u64, test_shl_panic_u64);
+test_shl_panic!(u32, test_shl_panic_u32);
+test_shl_panic!(u16, test_shl_panic_u16);
+test_shl_panic!(u8,  test_shl_panic_u8);
 
 macro_rules! test_ishl_panic {
-    ($ty:ty, $name:ident, $bits:expr, $max:expr, $min:expr) => {
+    ($ty:ty, $name:ident, $max:expr, $min:expr) => {
         #[test]
         fn $name() {
             fn check(args: ($ty, $ty)) -> bool {
-                let expected = match args.0.cmp(&0) {
-                    Ordering::Equal => Some(0),
-                    Ordering::Greater => {
-                        if args.1 as usize >= $bits || ($max >> args.1) < args.0 { None } else { Some(args.0 << args.1) }
-                    }
-                    Ordering::Less => {
-                        if args.1 as usize >= $bits || ($min >> args.1) > args.0 { None } else { Some(args.0 << args.1) }
-                    }
+                let expected = match u32::try_from(args.1) {
+                    Ok(n) if n < <$ty>::BITS => match args.0.cmp(&0) {
+                        Ordering::Equal => Some(0),
+                        Ordering::Greater => {
+                            if ($max >> n) < args.0 { None } else { Some(args.0 << n) }
+                        }
+                        Ordering::Less => {
+                            if ($min >> n) > args.0 { None } else { Some(args.0 << n) }
+                        }
+                    },
+                    _ => None,
                 };
                 let actual = catch_unwind(
                              || ShlPanic::shl_panic(args.0, args.1)).ok();
@@ -506,11 +566,12 @@ macro_rules! test_ishl_panic {
     };
 }
 
-test_ishl_panic!(isize, test_shl_panic_isize, (USIZE_BITS - 1), std::isize::MAX, std::isize::MIN);
-test_ishl_panic!(i64, test_shl_panic_i64, 63, std::i64::MAX, std::i64::MIN);
-test_ishl_panic!(i32, test_shl_panic_i32, 31, std::i32::MAX, std::i32::MIN);
-test_ishl_panic!(i16, test_shl_panic_i16, 15, std::i16::MAX, std::i16::MIN);
-test_ishl_panic!(i8,  test_shl_panic_i8, 7, std::i8::MAX, std::i8::MIN);
+test_ishl_panic!(isize, test_shl_panic_isize, isize::MAX, isize::MIN);
+test_ishl_panic!(i128, test_shl_panic_i128, i128::MAX, i128::MIN);
+test_ishl_panic!(i64, test_shl_panic_i64, i64::MAX, i64::MIN);
+test_ishl_panic!(i32, test_shl_panic_i32, i32::MAX, i32::MIN);
+test_ishl_panic!(i16, test_shl_panic_i16, i16::MAX, i16::MIN);
+test_ishl_panic!(i8,  test_shl_panic_i8, i8::MAX, i8::MIN);
 
 #[test]
 fn check_shl_wrap_usize() {
@@ -526,8 +587,8 @@ fn check_shl_wrap_usize() {
 #[test]
 fn check_shl_saturate_usize() {
     fn check(args: (usize, usize)) -> bool {
-        let expected = if args.1 as usize >= 64 || ((!0) >> args.1) < args.0 {
-            if args.0 == 0 { 0 } else { std::usize::MAX }
+        let expected = if args.1 >= usize::BITS as usize || ((!0) >> args.1) < args.0 {
+            if args.0 == 0 { 0 } else { usize::MAX }
         } else {
             args.0 << args.1
         };
@@ -541,7 +602,7 @@ fn check_shl_saturate_usize() {
 #[test]
 fn check_shr_panic_usize() {
     fn check(args: (usize, usize)) -> bool {
-        let expected = args.0.checked_shr(args.1 as u32);
+        let expected = u32::try_from(args.1).ok().and_then(|n| args.0.checked_shr(n));
         let actual = catch_unwind(|| ShrPanic::shr_panic(args.0, args.1)).ok();
         expected == actual
     }
@@ -563,10 +624,298 @@ fn check_shr_wrap_usize() {
 #[test]
 fn check_shr_saturate_usize() {
     fn check(args: (usize, usize)) -> bool {
-        let expected = args.0.checked_shr(args.1 as u32).unwrap_or(0);
+        let expected = u32::try_from(args.1).ok().and_then(|n| args.0.checked_shr(n)).unwrap_or(0);
         let actual = ShrSaturate::shr_saturate(args.0, args.1);
         expected == actual
     }
     install_handler();
     quickcheck(check as fn((usize, usize)) -> bool);
 }
+
+macro_rules! test_neg {
+    ($ty:ty, $panic:ident, $wrap:ident, $saturate:ident) => {
+        #[test]
+        fn $panic() {
+            fn check(arg: $ty) -> bool {
+                let expected = arg.checked_neg();
+                let actual = catch_unwind(|| NegPanic::neg_panic(arg)).ok();
+                let actual_ref = catch_unwind(|| NegPanic::neg_panic(&arg)).ok();
+                expected == actual && expected == actual_ref
+            }
+            install_handler();
+            // quickcheck only generates small values, so check `MIN` by hand
+            assert!(check(<$ty>::MIN));
+            quickcheck(check as fn($ty) -> bool);
+        }
+
+        #[test]
+        fn $wrap() {
+            fn check(arg: $ty) -> bool {
+                arg.wrapping_neg() == NegWrap::neg_wrap(arg) &&
+                    arg.wrapping_neg() == NegWrap::neg_wrap(&arg)
+            }
+            install_handler();
+            assert!(check(<$ty>::MIN));
+            quickcheck(check as fn($ty) -> bool);
+        }
+
+        #[test]
+        fn $saturate() {
+            fn check(arg: $ty) -> bool {
+                arg.saturating_neg() == NegSaturate::neg_saturate(arg) &&
+                    arg.saturating_neg() == NegSaturate::neg_saturate(&arg)
+            }
+            install_handler();
+            assert!(check(<$ty>::MIN));
+            quickcheck(check as fn($ty) -> bool);
+        }
+    };
+}
+
+test_neg!(isize, test_neg_panic_isize, test_neg_wrap_isize, test_neg_saturate_isize);
+test_neg!(i128, test_neg_panic_i128, test_neg_wrap_i128, test_neg_saturate_i128);
+test_neg!(i64, test_neg_panic_i64, test_neg_wrap_i64, test_neg_saturate_i64);
+test_neg!(i32, test_neg_panic_i32, test_neg_wrap_i32, test_neg_saturate_i32);
+test_neg!(i16, test_neg_panic_i16, test_neg_wrap_i16, test_neg_saturate_i16);
+test_neg!(i8,  test_neg_panic_i8,  test_neg_wrap_i8,  test_neg_saturate_i8);
+
+macro_rules! test_iter_fold {
+    ($ty:ty, $panic:ident, $wrap:ident, $saturate:ident, $init:expr,
+     $checked:ident, $wrapping:ident, $saturating:ident,
+     $trait_panic:ident::$fn_panic:ident, $trait_wrap:ident::$fn_wrap:ident,
+     $trait_saturate:ident::$fn_saturate:ident) => {
+        #[test]
+        fn $panic() {
+            fn check(args: Vec<$ty>) -> bool {
+                let expected = args.iter().try_fold($init, |a: $ty, &b| a.$checked(b));
+                let actual = catch_unwind(|| {
+                    <$ty as $trait_panic<&$ty>>::$fn_panic(args.iter())
+                }).ok();
+                expected == actual
+            }
+            install_handler();
+            quickcheck(check as fn(Vec<$ty>) -> bool);
+        }
+
+        #[test]
+        fn $wrap() {
+            fn check(args: Vec<$ty>) -> bool {
+                let expected = args.iter().fold($init, |a: $ty, &b| a.$wrapping(b));
+                let actual: $ty = $trait_wrap::$fn_wrap(args.into_iter());
+                expected == actual
+            }
+            install_handler();
+            quickcheck(check as fn(Vec<$ty>) -> bool);
+        }
+
+        #[test]
+        fn $saturate() {
+            fn check(args: Vec<$ty>) -> bool {
+                let expected = args.iter().fold($init, |a: $ty, &b| a.$saturating(b));
+                let actual: $ty = $trait_saturate::$fn_saturate(args.into_iter());
+                expected == actual
+            }
+            install_handler();
+            quickcheck(check as fn(Vec<$ty>) -> bool);
+        }
+    };
+}
+
+macro_rules! test_sum {
+    ($ty:ty, $panic:ident, $wrap:ident, $saturate:ident) => {
+        test_iter_fold!($ty, $panic, $wrap, $saturate, 0,
+            checked_add, wrapping_add, saturating_add,
+            SumPanic::sum_panic, SumWrap::sum_wrap, SumSaturate::sum_saturate);
+    };
+}
+
+macro_rules! test_product {
+    ($ty:ty, $panic:ident, $wrap:ident, $saturate:ident) => {
+        test_iter_fold!($ty, $panic, $wrap, $saturate, 1,
+            checked_mul, wrapping_mul, saturating_mul,
+            ProductPanic::product_panic, ProductWrap::product_wrap,
+            ProductSaturate::product_saturate);
+    };
+}
+
+test_sum!(usize, test_sum_panic_usize, test_sum_wrap_usize, test_sum_saturate_usize);
+test_sum!(u128, test_sum_panic_u128, test_sum_wrap_u128, test_sum_saturate_u128);
+test_sum!(u64, test_sum_panic_u64, test_sum_wrap_u64, test_sum_saturate_u64);
+test_sum!(u32, test_sum_panic_u32, test_sum_wrap_u32, test_sum_saturate_u32);
+test_sum!(u16, test_sum_panic_u16, test_sum_wrap_u16, test_sum_saturate_u16);
+test_sum!(u8,  test_sum_panic_u8,  test_sum_wrap_u8,  test_sum_saturate_u8);
+test_sum!(isize, test_sum_panic_isize, test_sum_wrap_isize, test_sum_saturate_isize);
+test_sum!(i128, test_sum_panic_i128, test_sum_wrap_i128, test_sum_saturate_i128);
+test_sum!(i64, test_sum_panic_i64, test_sum_wrap_i64, test_sum_saturate_i64);
+test_sum!(i32, test_sum_panic_i32, test_sum_wrap_i32, test_sum_saturate_i32);
+test_sum!(i16, test_sum_panic_i16, test_sum_wrap_i16, test_sum_saturate_i16);
+test_sum!(i8,  test_sum_panic_i8,  test_sum_wrap_i8,  test_sum_saturate_i8);
+
+test_product!(usize, test_product_panic_usize, test_product_wrap_usize, test_product_saturate_usize);
+test_product!(u128, test_product_panic_u128, test_product_wrap_u128, test_product_saturate_u128);
+test_product!(u64, test_product_panic_u64, test_product_wrap_u64, test_product_saturate_u64);
+test_product!(u32, test_product_panic_u32, test_product_wrap_u32, test_product_saturate_u32);
+test_product!(u16, test_product_panic_u16, test_product_wrap_u16, test_product_saturate_u16);
+test_product!(u8,  test_product_panic_u8,  test_product_wrap_u8,  test_product_saturate_u8);
+test_product!(isize, test_product_panic_isize, test_product_wrap_isize, test_product_saturate_isize);
+test_product!(i128, test_product_panic_i128, test_product_wrap_i128, test_product_saturate_i128);
+test_product!(i64, test_product_panic_i64, test_product_wrap_i64, test_product_saturate_i64);
+test_product!(i32, test_product_panic_i32, test_product_wrap_i32, test_product_saturate_i32);
+test_product!(i16, test_product_panic_i16, test_product_wrap_i16, test_product_saturate_i16);
+test_product!(i8,  test_product_panic_i8,  test_product_wrap_i8,  test_product_saturate_i8);
+
+macro_rules! test_pow {
+    ($ty:ty, $panic:ident, $wrap:ident, $saturate:ident) => {
+        #[test]
+        fn $panic() {
+            fn check(args: ($ty, u32)) -> bool {
+                let expected = args.0.checked_pow(args.1);
+                let actual = catch_unwind(|| PowPanic::pow_panic(args.0, args.1)).ok();
+                expected == actual && expected == PowChecked::pow_checked(args.0, args.1)
+            }
+            install_handler();
+            quickcheck(check as fn(($ty, u32)) -> bool);
+        }
+
+        #[test]
+        fn $wrap() {
+            fn check(args: ($ty, u32)) -> bool {
+                // beyond the number of bits, even bases wrap to zero, while odd
+                // ones repeat with a period of a power of two below that
+                let wide = args.1 as u128 + (1 << 127);
+                let expected_wide = if args.0 % 2 == 0 { 0 } else { args.0.wrapping_pow(args.1) };
+                args.0.wrapping_pow(args.1) == PowWrap::pow_wrap(args.0, args.1) &&
+                    expected_wide == PowWrap::pow_wrap(args.0, wide)
+            }
+            install_handler();
+            quickcheck(check as fn(($ty, u32)) -> bool);
+        }
+
+        #[test]
+        fn $saturate() {
+            fn check(args: ($ty, u32)) -> bool {
+                args.0.saturating_pow(args.1) == PowSaturate::pow_saturate(args.0, args.1) &&
+                    args.0.saturating_pow(args.1) == PowSaturate::pow_saturate(args.0, args.1 as i64)
+            }
+            install_handler();
+            quickcheck(check as fn(($ty, u32)) -> bool);
+        }
+    };
+}
+
+test_pow!(usize, test_pow_panic_usize, test_pow_wrap_usize, test_pow_saturate_usize);
+test_pow!(u128, test_pow_panic_u128, test_pow_wrap_u128, test_pow_saturate_u128);
+test_pow!(u64, test_pow_panic_u64, test_pow_wrap_u64, test_pow_saturate_u64);
+test_pow!(u32, test_pow_panic_u32, test_pow_wrap_u32, test_pow_saturate_u32);
+test_pow!(u16, test_pow_panic_u16, test_pow_wrap_u16, test_pow_saturate_u16);
+test_pow!(u8,  test_pow_panic_u8,  test_pow_wrap_u8,  test_pow_saturate_u8);
+test_pow!(isize, test_pow_panic_isize, test_pow_wrap_isize, test_pow_saturate_isize);
+test_pow!(i128, test_pow_panic_i128, test_pow_wrap_i128, test_pow_saturate_i128);
+test_pow!(i64, test_pow_panic_i64, test_pow_wrap_i64, test_pow_saturate_i64);
+test_pow!(i32, test_pow_panic_i32, test_pow_wrap_i32, test_pow_saturate_i32);
+test_pow!(i16, test_pow_panic_i16, test_pow_wrap_i16, test_pow_saturate_i16);
+test_pow!(i8,  test_pow_panic_i8,  test_pow_wrap_i8,  test_pow_saturate_i8);
+
+// the reference results, computed on integers modulo `MAX` with `MAX` for zero
+macro_rules! test_nonzero {
+    ($ty:ty, $int:ty, $add:ident, $mul:ident) => {
+        #[test]
+        #[cfg(not(feature = "specialization"))]
+        fn $add() {
+            fn check(args: ($int, $int)) -> bool {
+                let (a, b) = match <$ty>::new(args.0) {
+                    Some(a) => (a, args.1),
+                    None => return true,
+                };
+                let sum = a.get() as u128 + b as u128;
+                let max = <$int>::MAX as u128;
+                let wrapped = if sum > max { sum - max } else { sum };
+                catch_unwind(|| AddPanic::add_panic(a, b).get() as u128).ok() ==
+                        if sum > max { None } else { Some(sum) } &&
+                    AddWrap::add_wrap(a, b).get() as u128 == wrapped &&
+                    AddSaturate::add_saturate(a, b).get() as u128 == sum.min(max)
+            }
+            install_handler();
+            assert!(check((<$int>::MAX, <$int>::MAX)));
+            quickcheck(check as fn(($int, $int)) -> bool);
+        }
+
+        #[test]
+        #[cfg(not(feature = "specialization"))]
+        fn $mul() {
+            fn check(args: ($int, $int)) -> bool {
+                let (a, b) = match (<$ty>::new(args.0), <$ty>::new(args.1)) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return true,
+                };
+                let product = a.get() as u128 * b.get() as u128;
+                let max = <$int>::MAX as u128;
+                let wrapped = match product % max { 0 => max, x => x };
+                catch_unwind(|| MulPanic::mul_panic(a, b).get() as u128).ok() ==
+                        if product > max { None } else { Some(product) } &&
+                    MulWrap::mul_wrap(a, b).get() as u128 == wrapped &&
+                    MulSaturate::mul_saturate(a, b).get() as u128 == product.min(max)
+            }
+            install_handler();
+            assert!(check((<$int>::MAX, <$int>::MAX)));
+            assert!(check((1 << (<$int>::BITS - 1), 2)));
+            quickcheck(check as fn(($int, $int)) -> bool);
+        }
+    };
+}
+
+test_nonzero!(std::num::NonZeroU64, u64, test_nonzero_add_u64, test_nonzero_mul_u64);
+test_nonzero!(std::num::NonZeroU32, u32, test_nonzero_add_u32, test_nonzero_mul_u32);
+test_nonzero!(std::num::NonZeroU16, u16, test_nonzero_add_u16, test_nonzero_mul_u16);
+test_nonzero!(std::num::NonZeroU8,  u8,  test_nonzero_add_u8,  test_nonzero_mul_u8);
+
+macro_rules! test_overflowing {
+    ($ty:ty, $name:ident) => {
+        #[test]
+        fn $name() {
+            fn check(args: ($ty, $ty, u8)) -> bool {
+                let (a, b, s) = args;
+                let (mut sum, mut difference, mut product, mut left, mut right) = (a, a, a, a, a);
+                AddOverflowing::add_overflowing(a, b) == a.overflowing_add(b) &&
+                    AddOverflowing::add_overflowing(&a, &b) == a.overflowing_add(b) &&
+                    SubOverflowing::sub_overflowing(a, &b) == a.overflowing_sub(b) &&
+                    MulOverflowing::mul_overflowing(&a, b) == a.overflowing_mul(b) &&
+                    ShlOverflowing::shl_overflowing(a, s) == a.overflowing_shl(s as u32) &&
+                    ShrOverflowing::shr_overflowing(&a, s) == a.overflowing_shr(s as u32) &&
+                    NegOverflowing::neg_overflowing(a) == a.overflowing_neg() &&
+                    (sum.add_assign_overflowing(b), sum) == a.overflowing_add(b).swap() &&
+                    (difference.sub_assign_overflowing(&b), difference) ==
+                        a.overflowing_sub(b).swap() &&
+                    (product.mul_assign_overflowing(b), product) == a.overflowing_mul(b).swap() &&
+                    (left.shl_assign_overflowing(s), left) ==
+                        a.overflowing_shl(s as u32).swap() &&
+                    (right.shr_assign_overflowing(s), right) ==
+                        a.overflowing_shr(s as u32).swap()
+            }
+            quickcheck(check as fn(($ty, $ty, u8)) -> bool);
+        }
+    };
+}
+
+trait Swap<A, B> {
+    fn swap(self) -> (B, A);
+}
+
+impl<A, B> Swap<A, B> for (A, B) {
+    fn swap(self) -> (B, A) {
+        (self.1, self.0)
+    }
+}
+
+test_overflowing!(usize, test_overflowing_usize);
+test_overflowing!(u128, test_overflowing_u128);
+test_overflowing!(u64, test_overflowing_u64);
+test_overflowing!(u32, test_overflowing_u32);
+test_overflowing!(u16, test_overflowing_u16);
+test_overflowing!(u8,  test_overflowing_u8);
+test_overflowing!(isize, test_overflowing_isize);
+test_overflowing!(i128, test_overflowing_i128);
+test_overflowing!(i64, test_overflowing_i64);
+test_overflowing!(i32, test_overflowing_i32);
+test_overflowing!(i16, test_overflowing_i16);
+test_overflowing!(i8,  test_overflowing_i8);
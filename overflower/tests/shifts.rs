@@ -0,0 +1,223 @@
+//! Checks of the shift table in the crate docs for every pair of integer types
+//! as the value and the number of bits, for every way to shift, by value and
+//! by reference
+
+use overflower::*;
+use std::convert::TryFrom;
+use std::panic::{self, catch_unwind, RefUnwindSafe};
+use std::sync::Once;
+
+static HANDLER: Once = Once::new();
+
+// only show the panics of failed checks
+fn install_handler() {
+    HANDLER.call_once(|| {
+        let p = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.location().is_some_and(|l| l.file().ends_with("shifts.rs")) {
+                p(info);
+            }
+        }));
+    })
+}
+
+// not generic over the closure, which keeps the many checks quick to compile
+fn run<T>(f: &(dyn Fn() -> T + RefUnwindSafe)) -> Option<T> {
+    catch_unwind(f).ok()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Mode { Wrap, Panic, Saturate }
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Amounts { Mask, ZeroFill, Rotate }
+
+// numbers of bits around the widths of the types, where the type of the
+// number can hold them (besides its `MIN` and `MAX`)
+const AMOUNTS: [i128; 27] = [
+    -129, -128, -9, -8, -1, 0, 1, 2, 5, 7, 8, 9, 15, 16, 17, 31, 32, 33, 63, 64, 65, 127, 128,
+    129, 1 << 32, (1 << 32) + 3, 1 << 40,
+];
+
+macro_rules! check_shifts {
+    ($ty:ty, $rty:ty) => {{
+        // a function per pair of types, as one large one is slow to compile
+        fn check() {
+            type Shift = fn($ty, $rty) -> $ty;
+
+            let bits = <$ty>::BITS;
+            // the references shift one bit at a time, with `None` for a panic
+            let exact = |x: $ty, n: u32| (0..n).try_fold(x, |v, _| v.checked_mul(2));
+            let shl = |mode, x: $ty, n: u32| match mode {
+                Mode::Wrap => Some((0..n).fold(x, |v, _| v.wrapping_mul(2))),
+                Mode::Panic => exact(x, n),
+                Mode::Saturate => Some(exact(x, n).unwrap_or(
+                    if <$ty>::MIN != 0 && x.leading_zeros() == 0 { <$ty>::MIN } else { <$ty>::MAX })),
+            };
+            let shr = |x: $ty, n: u32| (0..n).fold(x, |v, _| v.div_euclid(2));
+
+            let wrap: [(Shift, Shift); 5] = [
+                (<$ty as ShlWrap<$rty>>::shl_wrap, <$ty as ShrWrap<$rty>>::shr_wrap),
+                (|z, r| ShlWrap::shl_wrap(&z, r), |z, r| ShrWrap::shr_wrap(&z, &r)),
+                (|mut z, r| { z.shl_assign_wrap(r); z }, |mut z, r| { z.shr_assign_wrap(r); z }),
+                (<Wrap as Policy>::shl, <Wrap as Policy>::shr),
+                (<$ty as ShlWith<Wrap, Mask, $rty>>::shl_with,
+                    <$ty as ShrWith<Wrap, Mask, $rty>>::shr_with),
+            ];
+            let panic: [(Shift, Shift); 6] = [
+                (<$ty as ShlPanic<$rty>>::shl_panic, <$ty as ShrPanic<$rty>>::shr_panic),
+                (|z, r| ShlPanic::shl_panic(&z, &r), |z, r| ShrPanic::shr_panic(&z, r)),
+                (|mut z, r| { z.shl_assign_panic(r); z }, |mut z, r| { z.shr_assign_panic(r); z }),
+                (<Panic as Policy>::shl, <Panic as Policy>::shr),
+                (|z, r| (Panicking(z) << r).0, |z, r| (Panicking(z) >> r).0),
+                (|z, r| { let mut z = Panicking(z); z <<= r; z.0 },
+                    |z, r| { let mut z = Panicking(z); z >>= r; z.0 }),
+            ];
+            let saturate: [(Shift, Shift); 7] = [
+                (<$ty as ShlSaturate<$rty>>::shl_saturate,
+                    <$ty as ShrSaturate<$rty>>::shr_saturate),
+                (|z, r| ShlSaturate::shl_saturate(&z, r), |z, r| ShrSaturate::shr_saturate(&z, &r)),
+                (|mut z, r| { z.shl_assign_saturate(r); z },
+                    |mut z, r| { z.shr_assign_saturate(r); z }),
+                (<Saturate as Policy>::shl, <Saturate as Policy>::shr),
+                (|z, r| (Saturating(z) << r).0, |z, r| (Saturating(z) >> r).0),
+                (|z, r| { let mut z = Saturating(z); z <<= r; z.0 },
+                    |z, r| { let mut z = Saturating(z); z >>= r; z.0 }),
+                (<$ty as ShlWith<Saturate, ZeroFill, $rty>>::shl_with,
+                    <$ty as ShrWith<Saturate, ZeroFill, $rty>>::shr_with),
+            ];
+
+            macro_rules! with {
+                ($mode:ident, $amounts:ident) => {
+                    (Mode::$mode, Amounts::$amounts, [
+                        (<$ty as ShlWith<$mode, $amounts, $rty>>::shl_with,
+                            <$ty as ShrWith<$mode, $amounts, $rty>>::shr_with),
+                        (|mut z, r| { ShlAssignWith::<$mode, $amounts, $rty>::shl_assign_with(&mut z, r); z },
+                            |mut z, r| { ShrAssignWith::<$mode, $amounts, $rty>::shr_assign_with(&mut z, r); z }),
+                        (|z, r| ShlWith::<$mode, $amounts, $rty>::shl_with(&z, r),
+                            |z, r| ShrWith::<$mode, $amounts, &$rty>::shr_with(&z, &r)),
+                        (|z, r| ShlWith::<$mode, $amounts, &$rty>::shl_with(z, &r),
+                            |mut z, r| { ShrAssignWith::<$mode, $amounts, &$rty>::shr_assign_with(&mut z, &r); z }),
+                    ])
+                };
+            }
+
+            let with: [(Mode, Amounts, [(Shift, Shift); 4]); 9] = [
+                with!(Wrap, Mask), with!(Wrap, ZeroFill), with!(Wrap, Rotate),
+                with!(Panic, Mask), with!(Panic, ZeroFill), with!(Panic, Rotate),
+                with!(Saturate, Mask), with!(Saturate, ZeroFill), with!(Saturate, Rotate),
+            ];
+
+            let values = [0, 1, 2, 3, 0x55, -1, -2, -3, -0x55].iter()
+                .filter_map(|&v: &i128| <$ty>::try_from(v).ok())
+                .chain(vec![<$ty>::MIN, <$ty>::MIN + 1, <$ty>::MIN >> 1, <$ty>::MAX, <$ty>::MAX - 1,
+                    <$ty>::MAX >> 1, (<$ty>::MAX >> 1) + 1])
+                .collect::<Vec<$ty>>();
+            let amounts = AMOUNTS.iter()
+                .filter_map(|&a| <$rty>::try_from(a).ok())
+                .chain(vec![<$rty>::MIN, <$rty>::MAX])
+                .collect::<Vec<$rty>>();
+
+            for &x in &values {
+                for &r in &amounts {
+                    // large `u128` amounts become negative, which is out of
+                    // range and the same modulo the width all the same
+                    let a = r as i128;
+                    let in_range = 0 <= a && a < i128::from(bits);
+                    let m = a.rem_euclid(i128::from(bits)) as u32;
+                    let args = (x, r);
+
+                    let expected = |mode, amounts| if in_range {
+                        (shl(mode, x, m), Some(shr(x, m)))
+                    } else {
+                        match amounts {
+                            Some(Amounts::Mask) => (shl(mode, x, m), Some(shr(x, m))),
+                            Some(Amounts::ZeroFill) => (shl(mode, x, bits), Some(shr(x, bits))),
+                            Some(Amounts::Rotate) =>
+                                (Some(x.rotate_left(m)), Some(x.rotate_right(m))),
+                            None => (None, None),
+                        }
+                    };
+                    let shifted = |(shl, shr): (Shift, Shift)|
+                        (run(&|| shl(x, r)), run(&|| shr(x, r)));
+
+                    for &forms in &wrap {
+                        assert_eq!(expected(Mode::Wrap, Some(Amounts::Mask)), shifted(forms),
+                            "wrap {:?}", args);
+                    }
+                    for &forms in &panic {
+                        assert_eq!(expected(Mode::Panic, None), shifted(forms), "panic {:?}", args);
+                    }
+                    if let Ok(n) = u32::try_from(a) {
+                        assert_eq!(expected(Mode::Panic, None),
+                            ((Checked::new(x) << n).get(), (Checked::new(x) >> n).get()),
+                            "checked {:?}", args);
+                        assert_eq!(expected(Mode::Panic, None), (
+                            { let mut z = Checked::new(x); z <<= n; z.get() },
+                            { let mut z = Checked::new(x); z >>= n; z.get() }),
+                            "checked assign {:?}", args);
+                    }
+                    for &forms in &saturate {
+                        assert_eq!(expected(Mode::Saturate, Some(Amounts::ZeroFill)), shifted(forms),
+                            "saturate {:?}", args);
+                    }
+                    for &(mode, amounts, forms) in &with {
+                        for &forms in &forms {
+                            assert_eq!(expected(mode, Some(amounts)), shifted(forms),
+                                "{:?}, shifts = {:?} {:?}", mode, amounts, args);
+                        }
+                    }
+
+                    let (shl_wrapped, shr_wrapped) = expected(Mode::Wrap, Some(Amounts::Mask));
+                    assert_eq!((shl_wrapped, !in_range), (Some(x.shl_overflowing(r).0),
+                        (&x).shl_overflowing(r).1), "shl_overflowing {:?}", args);
+                    assert_eq!((shl_wrapped, !in_range), (Some(x.shl_overflowing(&r).0),
+                        (&x).shl_overflowing(&r).1), "shl_overflowing ref {:?}", args);
+                    assert_eq!((shr_wrapped, !in_range), (Some((&x).shr_overflowing(r).0),
+                        (&x).shr_overflowing(&r).1), "shr_overflowing ref {:?}", args);
+                    assert_eq!((shr_wrapped, !in_range),
+                        { let mut z = x; let o = z.shr_assign_overflowing(r); (Some(z), o) },
+                        "shr_assign_overflowing {:?}", args);
+                    assert_eq!((shl_wrapped, !in_range),
+                        { let mut z = x; let o = z.shl_assign_overflowing(&r); (Some(z), o) },
+                        "shl_assign_overflowing ref {:?}", args);
+                }
+            }
+        }
+        check();
+    }};
+}
+
+macro_rules! test_shifts {
+    ($ty:ty, $name:ident) => {
+        #[test]
+        fn $name() {
+            install_handler();
+            check_shifts!($ty, u8);
+            check_shifts!($ty, u16);
+            check_shifts!($ty, u32);
+            check_shifts!($ty, u64);
+            check_shifts!($ty, u128);
+            check_shifts!($ty, usize);
+            check_shifts!($ty, i8);
+            check_shifts!($ty, i16);
+            check_shifts!($ty, i32);
+            check_shifts!($ty, i64);
+            check_shifts!($ty, i128);
+            check_shifts!($ty, isize);
+        }
+    };
+}
+
+test_shifts!(u8, test_shifts_u8);
+test_shifts!(u16, test_shifts_u16);
+test_shifts!(u32, test_shifts_u32);
+test_shifts!(u64, test_shifts_u64);
+test_shifts!(u128, test_shifts_u128);
+test_shifts!(usize, test_shifts_usize);
+test_shifts!(i8, test_shifts_i8);
+test_shifts!(i16, test_shifts_i16);
+test_shifts!(i32, test_shifts_i32);
+test_shifts!(i64, test_shifts_i64);
+test_shifts!(i128, test_shifts_i128);
+test_shifts!(isize, test_shifts_isize);
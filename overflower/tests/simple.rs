@@ -1,5 +1,6 @@
 use overflower::*;
 use std::borrow::Cow;
+use std::ops::{Add, MulAssign, Neg, Shl};
 
 #[test]
 #[overflow(wrap)]
@@ -30,7 +31,7 @@ fn test_simple_panic_add() {
 #[test]
 #[overflow(wrap)]
 fn test_simple_wrap_abs() {
-    i8::abs(-128 as i8);
+    i8::abs(-128i8);
 }
 
 #[test]
@@ -93,3 +94,639 @@ fn test_sub_wrap() {
 fn test_saturating_mul() {
     assert_eq!(255, 16u8.mul_saturate(16u8));
 }
+
+#[overflow(saturate)]
+fn wide_ops(a: u128, b: i128, shift: u128) -> (u128, i128, u8, i128) {
+    (a * a, -b, 1u8 << shift, i128::abs(b))
+}
+
+#[test]
+fn test_wide_ops() {
+    assert_eq!((u128::MAX, i128::MAX, 255, i128::MAX), wide_ops(u128::MAX, i128::MIN, 100));
+    assert_eq!((9, -5, 4, 5), wide_ops(3, 5, 2));
+}
+
+#[overflow(wrap)]
+fn generic_sum<T: Add<Output = T>>(a: T, b: T) -> T {
+    a + b
+}
+
+#[overflow(panic)]
+fn generic_diff<T>(a: T, b: T) -> T where T: std::ops::Sub<Output = T> {
+    a - b
+}
+
+#[overflow(saturate)]
+fn generic_mul_assign<T: MulAssign + Copy>(a: &mut T, b: T) {
+    *a *= b;
+}
+
+#[overflow(wrap)]
+fn generic_shl<T: Shl<u32, Output = T>, U: Neg<Output = U>>(a: T, b: u32, c: U) -> (T, U) {
+    (a << b, -c)
+}
+
+#[overflow(saturate)]
+fn generic_ref_sum<T>(a: &T, b: &T) -> T where for<'a> &'a T: Add<&'a T, Output = T> {
+    a + b
+}
+
+#[test]
+fn test_generic_ops() {
+    assert_eq!(0u8, generic_sum(255u8, 1));
+    assert_eq!(1i32, generic_diff(3, 2));
+    let mut x = 100u8;
+    generic_mul_assign(&mut x, 3);
+    assert_eq!(255, x);
+    assert_eq!((2u8, -3i32), generic_shl(1u8, 9, 3i32));
+    assert_eq!(255u8, generic_ref_sum(&200u8, &100u8));
+}
+
+#[test]
+#[should_panic]
+fn test_generic_panic() {
+    generic_diff(1u8, 2);
+}
+
+struct Pair {
+    a: u8,
+    b: u8,
+}
+
+#[overflow(wrap)]
+fn assign_op_places(v: &mut [u8], p: &mut Pair, r: &mut u8) {
+    let (i, j) = (0, 1);
+    v[i] += v[j];
+    p.a *= p.b;
+    *r += *r;
+}
+
+#[test]
+fn test_assign_op_places() {
+    let mut v = [1u8, 255];
+    let mut p = Pair { a: 2, b: 255 };
+    let mut x = 128u8;
+    assign_op_places(&mut v, &mut p, &mut x);
+    assert_eq!([0, 255], v);
+    assert_eq!(254, p.a);
+    assert_eq!(0, x);
+}
+
+#[overflow(saturate)]
+fn assign_op_order(v: &mut [u8], order: &mut Vec<&'static str>) {
+    v[{ order.push("place"); 0 }] += { order.push("value"); v[1] };
+}
+
+fn plain_assign_op_order(v: &mut [u8], order: &mut Vec<&'static str>) {
+    v[{ order.push("place"); 0 }] -= { order.push("value"); v[1] };
+}
+
+#[test]
+fn test_assign_op_evaluation_order() {
+    let (mut order, mut plain_order) = (vec![], vec![]);
+    let mut v = [250u8, 10];
+    assign_op_order(&mut v, &mut order);
+    assert_eq!(255, v[0]);
+    plain_assign_op_order(&mut [250u8, 10], &mut plain_order);
+    assert_eq!(plain_order, order);
+    assert_eq!(vec!["value", "place"], order);
+}
+
+#[overflow(saturate)]
+fn neg_saturate(x: i8) -> i8 {
+    -x
+}
+
+#[overflow(wrap)]
+fn neg_wrap(x: i8) -> (i8, i8) {
+    (-x, -(x + 1))
+}
+
+#[overflow(panic)]
+fn neg_literals(x: i8) -> i8 {
+    match -x {
+        -128 => -128i8,
+        -1..=1 => -(1),
+        _ => -2,
+    }
+}
+
+#[test]
+fn test_neg() {
+    assert_eq!(127, neg_saturate(-128));
+    assert_eq!(-5, neg_saturate(5));
+    assert_eq!((-128, 127), neg_wrap(-128));
+    assert_eq!(-1, neg_literals(0));
+    assert_eq!(-2, neg_literals(-100));
+}
+
+#[test]
+#[should_panic]
+fn test_neg_panic() {
+    neg_literals(-128);
+}
+
+#[overflow(saturate)]
+fn iter_sums(v: &[u8]) -> (u8, u8, u32) {
+    let sum = v.iter().sum();
+    let product = v.iter().copied().product::<u8>();
+    (sum, product, v.iter().map(|&x| x as u32).sum())
+}
+
+#[overflow(wrap)]
+fn generic_sum_all<T: std::iter::Sum>(v: Vec<T>) -> T {
+    v.into_iter().sum()
+}
+
+#[overflow(panic)]
+fn panicking_product(v: &[i8]) -> i8 {
+    v.iter().product()
+}
+
+#[test]
+fn test_iter_sums() {
+    assert_eq!((255, 255, 300), iter_sums(&[100, 200]));
+    assert_eq!((6, 6, 6), iter_sums(&[1, 2, 3]));
+    assert_eq!(44, generic_sum_all(vec![100u8, 200]));
+    assert_eq!(-120, panicking_product(&[-10, 12]));
+}
+
+#[test]
+#[should_panic]
+fn test_iter_product_panic() {
+    panicking_product(&[-16, 8, 2]);
+}
+
+#[overflow(saturate)]
+fn saturating_powers(x: u8, n: u32) -> (u8, u8, i16) {
+    (x.pow(n), u8::pow(x, 2), i16::pow(-(x as i16), n))
+}
+
+#[overflow(wrap)]
+fn wrapping_powers(x: &u8, n: u64) -> u8 {
+    x.pow(n)
+}
+
+#[overflow(panic)]
+fn panicking_power(x: i32) -> i32 {
+    x.pow(3)
+}
+
+#[test]
+fn test_pow() {
+    assert_eq!((255, 255, i16::MIN), saturating_powers(20, 5));
+    assert_eq!((8, 4, -8), saturating_powers(2, 3));
+    assert_eq!(0, wrapping_powers(&2, 8));
+    assert_eq!(1, wrapping_powers(&3, 1 << 40));
+    assert_eq!(-1000, panicking_power(-10));
+}
+
+#[test]
+#[should_panic]
+fn test_pow_panic() {
+    panicking_power(2000);
+}
+
+mod facade {
+    pub use ::overflower as arith;
+}
+
+#[overflow(saturate, crate = crate::facade::arith)]
+fn facade_add(a: u8, b: u8) -> u8 {
+    i8::abs(-128i8);
+    a + b
+}
+
+#[test]
+fn test_crate_path() {
+    assert_eq!(255, facade_add(200, 100));
+}
+
+#[overflow(wrap, div_by_zero = saturate)]
+fn average(total: i8, count: i8) -> (i8, i8) {
+    let mut rest = total;
+    rest %= count;
+    (total / count, rest)
+}
+
+#[overflow(wrap, div_by_zero = saturate)]
+fn average_of(total: &i8, count: i8) -> (i8, i8) {
+    (total / count, total % &count)
+}
+
+#[test]
+fn test_div_by_zero_policy() {
+    assert_eq!((-5, -1), average(-21, 4));
+    assert_eq!((i8::MIN, 0), average(i8::MIN, -1));
+    assert_eq!((i8::MAX, 100), average(100, 0));
+    assert_eq!((i8::MIN, -1), average(-1, 0));
+    assert_eq!((i8::MIN, 0), average_of(&i8::MIN, -1));
+    assert_eq!((i8::MAX, 100), average_of(&100, 0));
+}
+
+#[overflow(panic, shifts = rotate)]
+fn rotate_bits(x: u8, n: i32) -> (u8, u8) {
+    let mut y = x;
+    y >>= n;
+    (x << n, y)
+}
+
+#[test]
+fn test_shift_amount_policy() {
+    assert_eq!((0b1000, 0b10), rotate_bits(0b100, 1));
+    assert_eq!((0b10, 0b1000_0000), rotate_bits(1, 9));
+    assert_eq!((0b1000_0000, 0b10), rotate_bits(1, -1));
+}
+
+#[test]
+#[should_panic]
+fn test_shift_amount_policy_panic() {
+    rotate_bits(3, 7);
+}
+
+#[overflow(inherit)]
+mod helpers {
+    pub fn double(x: u8) -> u8 {
+        x * 2
+    }
+
+    pub fn quadruple(x: u8) -> u8 {
+        double(double(x))
+    }
+
+    pub fn total(v: &[u8]) -> u8 {
+        v.iter().sum::<u8>()
+    }
+}
+
+#[overflow(saturate)]
+mod inherit_saturate {
+    use overflower::overflow;
+    use super::helpers::quadruple;
+
+    #[overflow(inherit)]
+    fn offset(x: u8, by: u8) -> u8 {
+        x + by
+    }
+
+    #[overflow(inherit)]
+    pub mod scaler {
+        pub struct Scaler(pub u8);
+
+        impl Scaler {
+            pub fn scale<T: Into<u8>>(&self, x: T) -> u8 {
+                let mut y = x.into();
+                y *= self.0;
+                y
+            }
+        }
+    }
+
+    pub fn compute(x: u8) -> (u8, u8, u8) {
+        // functions outside of this item and methods called on other values
+        // than `self` need to be passed a policy explicitly
+        let scaled = scaler::Scaler(3).scale::<overflower::Saturate, _>(x);
+        (offset(x, 200), scaled, quadruple::<overflower::Saturate>(x))
+    }
+}
+
+#[test]
+fn test_inherit() {
+    use overflower::{Panic, Saturate, Wrap};
+    assert_eq!(144, helpers::quadruple::<Wrap>(100));
+    assert_eq!(255, helpers::quadruple::<Saturate>(100));
+    assert_eq!(40, helpers::quadruple::<Panic>(10));
+    assert_eq!(44, helpers::total::<Wrap>(&[100, 200]));
+    assert_eq!(255, helpers::total::<Saturate>(&[100, 200]));
+    assert_eq!(44, inherit_saturate::scaler::Scaler(3).scale::<Wrap, _>(100u8));
+    assert_eq!((255, 255, 255), inherit_saturate::compute(100));
+}
+
+#[test]
+#[should_panic]
+fn test_inherit_panic() {
+    helpers::double::<overflower::Panic>(200);
+}
+
+#[overflow(saturate)]
+mod nested_spellings {
+    use overflower::overflow;
+
+    #[overflower::overflow(wrap)]
+    pub fn wrap_add(a: u8, b: u8) -> u8 {
+        a + b
+    }
+
+    #[cfg_attr(all(), overflow(panic))]
+    pub fn panic_add(a: u8, b: u8) -> u8 {
+        a + b
+    }
+
+    pub fn saturate_add(a: u8, b: u8) -> u8 {
+        a + b
+    }
+}
+
+#[test]
+fn test_nested_spellings() {
+    assert_eq!(44, nested_spellings::wrap_add(100, 200));
+    assert_eq!(255, nested_spellings::saturate_add(100, 200));
+    assert_eq!(30, nested_spellings::panic_add(10, 20));
+}
+
+#[test]
+#[should_panic]
+fn test_nested_spellings_panic() {
+    nested_spellings::panic_add(100, 200);
+}
+
+#[cfg(not(feature = "specialization"))]
+#[overflow(saturate)]
+fn nonzero_ops(id: std::num::NonZeroU32, n: std::num::NonZeroI8) -> (std::num::NonZeroU32, std::num::NonZeroI8, u32) {
+    let mut next = id + 1;
+    next *= id;
+    (next, -n, 100 / id)
+}
+
+#[test]
+#[cfg(not(feature = "specialization"))]
+fn test_nonzero_ops() {
+    use std::num::{NonZeroI8, NonZeroU32};
+    let (one, min) = (NonZeroU32::new(1).unwrap(), NonZeroI8::new(i8::MIN).unwrap());
+    assert_eq!((NonZeroU32::new(2).unwrap(), NonZeroI8::MAX, 100), nonzero_ops(one, min));
+    assert_eq!(NonZeroU32::MAX, nonzero_ops(NonZeroU32::MAX, min).0);
+}
+
+// the std `Add` and `Neg` are in scope, so those need the qualified form
+fn arith_ops<P: Policy, T: Arith<P> + Copy>(a: T, b: T) -> (T, T, T, T) {
+    let mut c = a;
+    c.mul_assign(b);
+    c.shl_assign(1);
+    (Arith::<P>::add(a, b), a.sub(b), c, Arith::<P>::neg(b))
+}
+
+#[test]
+fn test_arith() {
+    assert_eq!((44, 100, 64, 156), arith_ops::<Wrap, u8>(200, 100));
+    assert_eq!((255, 100, 255, 0), arith_ops::<Saturate, u8>(200, 100));
+    assert_eq!((0, 127, -128, 100), arith_ops::<Saturate, i8>(100, -100));
+    assert_eq!((3, -1, 4, -2), arith_ops::<Panic, i32>(1, 2));
+    assert_eq!((3.5, -0.5, 6.0, -2.0), arith_ops::<Panic, f64>(1.5, 2.0));
+    assert_eq!((f32::MAX, f32::MAX, f32::INFINITY, -2.0),
+        arith_ops::<Saturate, f32>(f32::MAX, 2.0));
+    // shifts multiply or divide by powers of two, rounding once
+    let tiny = f64::from_bits(1);
+    assert_eq!((f64::INFINITY, 2f64.powi(1023)),
+        (Arith::<Wrap>::shl(1.0, 1024), Arith::<Wrap>::shl(tiny, 2097)));
+    assert_eq!((tiny, 0.0, f64::from_bits(2)), (Arith::<Wrap>::shr(1.0, 1074),
+        Arith::<Wrap>::shr(1.0, 1075), Arith::<Wrap>::shr(3.0, 1075)));
+    assert_eq!(-0.25f32, Arith::<Panic>::shr(-1.0, 2));
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_arith_panic() {
+    arith_ops::<Panic, u8>(1, 2);
+}
+
+struct Counters {
+    hits: Panicking<u8>,
+    level: Saturating<i8>,
+    total: Checked<u16>,
+}
+
+// the wrappers keep their own policy within wrap-annotated code
+#[overflow(wrap)]
+fn count(c: &mut Counters, n: u8) {
+    c.hits += Panicking(n);
+    c.level = -(c.level * Saturating(n as i8));
+    c.total = c.total * Checked::from(n as u16);
+}
+
+#[test]
+fn test_wrappers() {
+    let mut c = Counters { hits: Panicking(1), level: Saturating(100), total: Checked::from(300) };
+    count(&mut c, 2);
+    assert_eq!((Panicking(3), Saturating(-127), Checked::from(600)), (c.hits, c.level, c.total));
+    count(&mut c, 200);
+    assert_eq!((Saturating(-127), None), (c.level, c.total.0));
+    assert_eq!("203 -127 overflow", format!("{} {:?} {}", c.hits, c.level, c.total));
+    assert!(c.total != c.total);
+    assert_eq!(Panicking(6u32), [1, 2, 3].iter().map(|&x| Panicking(x)).sum());
+    assert_eq!(Saturating(255u8), (1..10).map(Saturating).product());
+    assert_eq!(Checked::from(8), Checked::from(1u8) << 3);
+    assert_eq!(None, (Checked::from(1u8) << 8).0);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_wrappers_panic() {
+    let mut c = Counters { hits: Panicking(200), level: Saturating(0), total: Checked::from(0) };
+    count(&mut c, 100);
+}
+
+// overflow anywhere in the formula only shows in the result
+#[overflow(panic)]
+fn checked_formula(x: u8, y: u8) -> Result<u8, OverflowError> {
+    let mut z = 2 * Checked::new(x) + y;
+    z -= 10;
+    (100 / z + z % 7).into_result()
+}
+
+#[test]
+fn test_checked_poisoning() {
+    assert_eq!(Ok(100 / 20 + 20 % 7), checked_formula(10, 10));
+    assert_eq!(Err(OverflowError), checked_formula(200, 0));
+    assert_eq!(Err(OverflowError), checked_formula(1, 1));
+    assert_eq!(Err(OverflowError), checked_formula(5, 0));
+    let poisoned = Checked::<i32>::overflowed() * 2 - Checked::new(1);
+    assert!(poisoned.is_overflowed());
+    assert_eq!(None, poisoned.get());
+    assert_eq!("arithmetic overflow", OverflowError.to_string());
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Meters(f32);
+
+impl Add for Meters {
+    type Output = Meters;
+
+    fn add(self, rhs: Meters) -> Meters {
+        Meters(self.0 + rhs.0)
+    }
+}
+
+// types without policy trait impls use their `std::ops` traits, on stable,
+// too
+#[overflow(saturate)]
+fn mixed(x: u8, f: f64, v: &[f64], m: Meters) -> (u8, f64, f64, Meters, String) {
+    let mut g = f * 2.0 - 1.0;
+    g /= 4.0;
+    let mut s = String::from("a");
+    s += "b";
+    (x + 200, -g % 1.0, v.iter().sum::<f64>() + v.iter().product::<f64>(), m + m, s + "c")
+}
+
+#[overflow(inherit)]
+mod float_helpers {
+    pub fn double(x: u8, f: f32) -> (u8, f32) {
+        (x * 2, -(f * 2.0))
+    }
+}
+
+#[overflow(wrap, div_by_zero = saturate, shifts = rotate)]
+fn ratios(x: i8, y: i8, f: f64, n: u32) -> (i8, f64, u8) {
+    let mut g = f;
+    g %= f / 2.0;
+    (x / y, f / 0.0 + g, 1u8 << n)
+}
+
+#[test]
+fn test_mixed_fallback() {
+    assert_eq!((255, -0.75, 12.0, Meters(3.0), "abc".to_string()),
+        mixed(100, 2.0, &[1.0, 2.0, 3.0], Meters(1.5)));
+    assert_eq!((144, -3.0), float_helpers::double::<Wrap>(200, 1.5));
+    assert_eq!((255, -3.0), float_helpers::double::<Saturate>(200, 1.5));
+    assert_eq!((i8::MAX, f64::INFINITY, 2), ratios(3, 0, 1.0, 9));
+}
+
+// the arguments of macro invocations are rewritten in place on stable (with
+// `specialization`, the invocations get the attribute, see `tests/macro.rs`)
+#[cfg(not(feature = "specialization"))]
+#[overflow(saturate)]
+fn macro_args(x: u8) -> (Vec<u8>, String) {
+    assert!(x + 100 >= 100);
+    println!("{}", x * 3);
+    (vec![x + 100, x - 100], format!("{}", x * 2))
+}
+
+#[test]
+#[cfg(not(feature = "specialization"))]
+fn test_macro_args() {
+    assert_eq!((vec![255, 100], "255".to_string()), macro_args(200));
+}
+
+// a `pow` method of another type than the integers
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Scale(f64);
+
+impl Scale {
+    fn pow(self, n: i32) -> Scale {
+        Scale(self.0.powi(n))
+    }
+}
+
+#[overflow(saturate)]
+fn powers(x: u8, r: &u8, s: Scale) -> (u8, u8, Scale) {
+    (x.pow(2), r.pow(3), s.pow(2))
+}
+
+#[test]
+fn test_pow_fallback() {
+    assert_eq!((255, 8, Scale(6.25)), powers(16, &2, Scale(2.5)));
+}
+
+// references to numbers never fall back to `std::ops`, also where closures
+// leave their types to inference
+#[overflow(wrap)]
+fn ref_closures_wrap(x: &u8, y: &i8) -> (u8, i8, u8) {
+    let shl = |a: &u8| a << 1;
+    let neg = |a| -a;
+    let add = |a: &u8, b| a + b;
+    (shl(x), neg(y), add(x, x))
+}
+
+#[overflow(panic)]
+fn ref_closures_panic(x: &u8, y: &i8) -> (u8, i8, u8) {
+    let shl = |a: &u8| a << 1;
+    let neg = |a| -a;
+    let add = |a: &u8, b| a + b;
+    (shl(x), neg(y), add(x, x))
+}
+
+#[overflow(saturate)]
+fn ref_closures_saturate(x: &u8, y: &i8) -> (u8, i8, u8) {
+    let shl = |a: &u8| a << 1;
+    let neg = |a| -a;
+    let add = |a: &u8, b| a + b;
+    (shl(x), neg(y), add(x, x))
+}
+
+#[overflow(wrap, div_by_zero = saturate)]
+fn ref_closures_div(x: &i8) -> (i8, i8) {
+    let div = |a: &i8| a / 0;
+    let rem = |a: &i8, b: &i8| a % b;
+    (div(x), rem(x, &0))
+}
+
+#[overflow(panic, shifts = rotate)]
+fn ref_closures_shifts(x: &u8) -> (u8, u8) {
+    let shl = |a: &u8| a << 9;
+    let shr = |a: &u8, n: &i32| a >> n;
+    (shl(x), shr(x, &-1))
+}
+
+#[test]
+fn test_ref_closures() {
+    assert_eq!((144, i8::MIN, 144), ref_closures_wrap(&200, &i8::MIN));
+    assert_eq!((255, i8::MAX, 255), ref_closures_saturate(&200, &i8::MIN));
+    assert_eq!((20, -5, 20), ref_closures_panic(&10, &5));
+    assert_eq!((i8::MAX, 5), ref_closures_div(&5));
+    assert_eq!((2, 0b10), ref_closures_shifts(&1));
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_ref_closures_panic() {
+    ref_closures_panic(&10, &i8::MIN);
+}
+
+#[test]
+fn test_float_policies() {
+    assert_eq!(3.5f32, 1.5f32.add_wrap(2.0));
+    assert_eq!(f64::INFINITY, f64::MAX.mul_saturate(2.0));
+    assert_eq!(-1.0f64, (&2.0f64).sub_panic(&3.0));
+    assert_eq!(1.5f64, AbsSaturate::abs_saturate(-1.5f64));
+    assert_eq!(6.0f64, SumWrap::sum_wrap([1.0f64, 2.0, 3.0].iter()));
+    assert_eq!(f32::INFINITY, DivWith::<Wrap, Saturate>::div_with(1.0f32, 0.0));
+    // generic code with policy bounds takes floats on stable, too
+    assert_eq!(3.5f64, generic_sum(1.5f64, 2.0));
+    let mut x = 1.5f32;
+    generic_mul_assign(&mut x, 3.0);
+    assert_eq!(4.5, x);
+    assert_eq!(7.0f32, generic_ref_sum(&3.0f32, &4.0));
+}
+
+#[overflow(finite)]
+fn finite(x: f64, y: f64, n: i32, v: &[f64]) -> (f64, f64, i32, f64) {
+    let mut z = x;
+    z *= y;
+    (x / y - 1.0, -z, n << 2, v.iter().sum::<f64>())
+}
+
+#[test]
+fn test_finite() {
+    assert_eq!((1.0, -8.0, 4, 6.0), finite(4.0, 2.0, 1, &[1.0, 2.0, 3.0]));
+    // only finite operands have to give finite results
+    assert_eq!(f64::INFINITY, finite(f64::INFINITY, 2.0, 1, &[]).0);
+    assert!(finite(f64::NAN, 2.0, 1, &[]).0.is_nan());
+    assert_eq!(-0.0f64, SumFinite::sum_finite([-0.0f64].iter()));
+}
+
+#[test]
+#[should_panic(expected = "non-finite float result")]
+fn test_finite_div_by_zero() {
+    finite(1.0, 0.0, 1, &[]);
+}
+
+#[test]
+#[should_panic(expected = "non-finite float result")]
+fn test_finite_overflow() {
+    finite(1.0, 1.0, 1, &[f64::MAX, f64::MAX]);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_finite_int_overflow() {
+    finite(1.0, 1.0, i32::MAX, &[]);
+}
@@ -0,0 +1,119 @@
+//! Checks of the policy traits for `Duration`, `Instant` and `SystemTime`,
+//! directly and within annotated code
+
+use overflower::*;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const SEC: Duration = Duration::from_secs(1);
+
+#[test]
+fn test_duration_policies() {
+    assert_eq!(Duration::MAX, Duration::MAX.add_saturate(SEC));
+    assert_eq!(Duration::ZERO, SEC.sub_saturate(Duration::MAX));
+    assert_eq!(Duration::MAX, Duration::MAX.mul_saturate(2));
+    assert_eq!(Duration::MAX, 2.mul_saturate(Duration::MAX));
+    assert_eq!(Duration::from_secs(3), 3.mul_panic(SEC));
+    assert_eq!(Duration::from_millis(500), SEC.div_panic(2));
+    assert_eq!(Duration::MAX, SEC.div_saturate(0));
+    assert_eq!(Duration::ZERO, Duration::ZERO.div_saturate(0));
+    assert_eq!(Duration::MAX, SumSaturate::sum_saturate([Duration::MAX, SEC].iter()));
+    assert_eq!(Duration::from_secs(2), SumPanic::sum_panic(std::iter::once(SEC).chain(Some(SEC))));
+    assert_eq!(Duration::MAX, DivWith::<Panic, Saturate, u32>::div_with(SEC, 0));
+    let mut d = SEC;
+    d.sub_assign_saturate(Duration::from_secs(2));
+    assert_eq!(Duration::ZERO, d);
+}
+
+#[test]
+#[cfg(not(feature = "specialization"))]
+fn test_duration_refs() {
+    assert_eq!(Duration::MAX, (&Duration::MAX).add_saturate(&SEC));
+    assert_eq!(Duration::from_secs(2), 2.mul_wrap(&SEC));
+    assert_eq!(Duration::MAX, DivWith::<Panic, Saturate, &u32>::div_with(&SEC, &0));
+    let mut d = Duration::ZERO;
+    d.add_assign_panic(&SEC);
+    assert_eq!(SEC, d);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_duration_panic() {
+    Duration::MAX.add_panic(SEC);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_duration_div_by_zero() {
+    SEC.div_finite(0);
+}
+
+#[test]
+fn test_instant_policies() {
+    let now = Instant::now();
+    let latest = now.add_saturate(Duration::MAX);
+    assert!(latest > now);
+    assert_eq!(None, latest.checked_add(Duration::from_nanos(1)));
+    let earliest = now.sub_saturate(Duration::MAX);
+    assert_eq!(None, earliest.checked_sub(Duration::from_nanos(1)));
+    assert_eq!(Duration::ZERO, now.sub_saturate(latest));
+    assert_eq!(now + SEC, now.add_panic(SEC));
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow")]
+fn test_instant_underflow() {
+    let now = Instant::now();
+    now.sub_panic(now + SEC);
+}
+
+#[test]
+fn test_system_time_policies() {
+    let latest = UNIX_EPOCH.add_saturate(Duration::MAX);
+    assert!(latest > UNIX_EPOCH);
+    assert_eq!(None, latest.checked_add(Duration::from_nanos(1)));
+    let mut t = SystemTime::now();
+    t.sub_assign_saturate(Duration::MAX);
+    assert_eq!(None, t.checked_sub(Duration::from_nanos(1)));
+}
+
+#[overflow(saturate)]
+fn deadline(start: Instant, timeout: Duration, retries: u32) -> (Instant, Duration) {
+    let mut total = timeout * retries;
+    total += timeout;
+    (start + total, [timeout, total].iter().sum())
+}
+
+#[cfg(not(feature = "specialization"))]
+#[overflow(saturate)]
+fn later(start: &Instant, timeout: &Duration, n: &u32) -> (Instant, Duration) {
+    (start + timeout, timeout * n)
+}
+
+#[overflow(saturate, div_by_zero = panic)]
+fn share(total: Duration, n: u32) -> Duration {
+    2 * total / n
+}
+
+#[test]
+fn test_annotated_time() {
+    let now = Instant::now();
+    assert_eq!((now + 4 * SEC, 5 * SEC), deadline(now, SEC, 3));
+    let (latest, total) = deadline(now, Duration::MAX, 2);
+    assert_eq!((None, Duration::MAX), (latest.checked_add(Duration::from_nanos(1)), total));
+    assert_eq!(SEC, share(SEC, 2));
+}
+
+#[test]
+#[cfg(not(feature = "specialization"))]
+fn test_annotated_time_refs() {
+    let now = Instant::now();
+    assert_eq!((now + SEC, 3 * SEC), later(&now, &SEC, &3));
+    let (latest, total) = later(&now, &Duration::MAX, &2);
+    assert_eq!((None, Duration::MAX), (latest.checked_add(Duration::from_nanos(1)), total));
+}
+
+#[test]
+#[should_panic]
+fn test_annotated_div_by_zero() {
+    share(SEC, 0);
+}